@@ -0,0 +1,58 @@
+extern crate criterion;
+extern crate hexgrid;
+
+use std::collections::{HashSet, VecDeque};
+use std::convert::TryFrom;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hexgrid::{CompactCoordinate, Coordinate};
+
+const RADIUS: i64 = 300;
+
+fn bfs_coordinate(start: Coordinate) -> usize {
+    let mut visited = HashSet::new();
+    let mut frontier = VecDeque::new();
+    visited.insert(start);
+    frontier.push_back(start);
+    while let Some(current) = frontier.pop_front() {
+        for next in current.neighbors() {
+            if start.distance_to(next) <= RADIUS && visited.insert(next) {
+                frontier.push_back(next);
+            }
+        }
+    }
+    visited.len()
+}
+
+fn bfs_compact(start: CompactCoordinate) -> usize {
+    let mut visited = HashSet::new();
+    let mut frontier = VecDeque::new();
+    visited.insert(start);
+    frontier.push_back(start);
+    while let Some(current) = frontier.pop_front() {
+        for next in current.neighbors() {
+            if start.distance_to(next) <= RADIUS && visited.insert(next) {
+                frontier.push_back(next);
+            }
+        }
+    }
+    visited.len()
+}
+
+fn bench_bfs(c: &mut Criterion) {
+    let start = Coordinate::at(0, 0, 0).unwrap();
+    let compact = CompactCoordinate::try_from(start).unwrap();
+    c.bench_function("bfs coordinate", |b| {
+        b.iter(|| bfs_coordinate(black_box(start)))
+    });
+    c.bench_function("bfs compact", |b| {
+        b.iter(|| bfs_compact(black_box(compact)))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_bfs
+}
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+extern crate criterion;
+extern crate hexgrid;
+
+use std::collections::HashMap;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hexgrid::{Coordinate, HexMap};
+
+const RADIUS: i64 = 100;
+
+fn lookup_dense(map: &HexMap<u32>, hexes: &[Coordinate]) -> u64 {
+    hexes.iter().map(|c| u64::from(*map.get(*c).unwrap())).sum()
+}
+
+fn lookup_hash(map: &HashMap<Coordinate, u32>, hexes: &[Coordinate]) -> u64 {
+    hexes.iter().map(|c| u64::from(map[c])).sum()
+}
+
+fn bench_lookup(c: &mut Criterion) {
+    let center = Coordinate::at(-3, -1, 4).unwrap();
+    let hexes = center.spiral(RADIUS);
+    let dense = HexMap::from_fn(hexes.iter().cloned(), |c| center.distance_to(*c) as u32);
+    let hash: HashMap<Coordinate, u32> = dense.iter().map(|(c, v)| (c, *v)).collect();
+    assert_eq!(lookup_dense(&dense, &hexes), lookup_hash(&hash, &hexes));
+    c.bench_function("lookup dense map", |b| {
+        b.iter(|| lookup_dense(black_box(&dense), &hexes))
+    });
+    c.bench_function("lookup hash map", |b| {
+        b.iter(|| lookup_hash(black_box(&hash), &hexes))
+    });
+}
+
+fn bench_iteration(c: &mut Criterion) {
+    let center = Coordinate::at(-3, -1, 4).unwrap();
+    let dense = HexMap::from_fn(center.spiral(RADIUS), |c| center.distance_to(*c) as u32);
+    let hash: HashMap<Coordinate, u32> = dense.iter().map(|(c, v)| (c, *v)).collect();
+    c.bench_function("iterate dense map", |b| {
+        b.iter(|| black_box(&dense).iter().map(|(c, v)| c.x() + i64::from(*v)).sum::<i64>())
+    });
+    c.bench_function("iterate hash map", |b| {
+        b.iter(|| black_box(&hash).iter().map(|(c, v)| c.x() + i64::from(*v)).sum::<i64>())
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_lookup, bench_iteration
+}
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+extern crate criterion;
+extern crate hexgrid;
+
+use std::collections::VecDeque;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hexgrid::{BoundedHexSet, Coordinate, HexSet};
+
+const RADIUS: i64 = 100;
+
+fn flood_hash(start: Coordinate) -> usize {
+    let mut visited = HexSet::new();
+    let mut frontier = VecDeque::new();
+    visited.insert(start);
+    frontier.push_back(start);
+    while let Some(current) = frontier.pop_front() {
+        for next in current.neighbors() {
+            if start.distance_to(next) <= RADIUS && visited.insert(next) {
+                frontier.push_back(next);
+            }
+        }
+    }
+    visited.len()
+}
+
+fn flood_bounded(start: Coordinate) -> usize {
+    let mut visited = BoundedHexSet::new(start, RADIUS);
+    let mut frontier = VecDeque::new();
+    visited.insert(start).unwrap();
+    frontier.push_back(start);
+    while let Some(current) = frontier.pop_front() {
+        for next in current.neighbors() {
+            if start.distance_to(next) <= RADIUS && visited.insert(next).unwrap() {
+                frontier.push_back(next);
+            }
+        }
+    }
+    visited.count_ones()
+}
+
+fn bench_flood_fill(c: &mut Criterion) {
+    let start = Coordinate::at(-3, -1, 4).unwrap();
+    assert_eq!(flood_hash(start), flood_bounded(start));
+    c.bench_function("flood fill hash set", |b| {
+        b.iter(|| flood_hash(black_box(start)))
+    });
+    c.bench_function("flood fill bounded set", |b| {
+        b.iter(|| flood_bounded(black_box(start)))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_flood_fill
+}
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+extern crate criterion;
+extern crate hexgrid;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hexgrid::{Coordinate, NearestIndex};
+
+/// Scatter `count` hexes over a hexagon of radius 1000, deterministically.
+fn scatter(count: usize) -> Vec<Coordinate> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % 2001) as i64 - 1000
+    };
+    let mut hexes = Vec::with_capacity(count);
+    while hexes.len() < count {
+        let c = Coordinate::from_axial(next(), next());
+        if c.distance_to(Coordinate::new()) <= 1000 {
+            hexes.push(c);
+        }
+    }
+    hexes
+}
+
+fn linear_nearest(members: &[Coordinate], from: Coordinate) -> Option<(Coordinate, i64)> {
+    members.iter().map(|&m| (m, from.distance_to(m))).min_by_key(|&(_, d)| d)
+}
+
+fn bench_nearest(c: &mut Criterion) {
+    let queries = scatter(100);
+    let mut group = c.benchmark_group("nearest");
+    for &count in [10_000, 100_000].iter() {
+        let members = scatter(count);
+        let index: NearestIndex = members.iter().cloned().collect();
+        group.bench_with_input(BenchmarkId::new("index", count), &index, |b, index| {
+            b.iter(|| queries.iter().filter_map(|&q| index.nearest(black_box(q))).count())
+        });
+        group.bench_with_input(BenchmarkId::new("linear", count), &members, |b, members| {
+            b.iter(|| queries.iter().filter_map(|&q| linear_nearest(members, black_box(q))).count())
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_nearest
+}
+criterion_main!(benches);
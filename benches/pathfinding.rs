@@ -0,0 +1,33 @@
+extern crate criterion;
+extern crate hexgrid;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use hexgrid::pathfinding::{astar_bidirectional_with_stats, find_path_weighted_with_stats};
+use hexgrid::Coordinate;
+
+/// Open ground costing 1 to 3, varying from hex to hex.
+fn terrain(c: Coordinate) -> Option<u32> {
+    let h = (c.x().wrapping_mul(31) ^ c.y().wrapping_mul(17)).rem_euclid(7);
+    Some(1 + (h % 3) as u32)
+}
+
+fn bench_long_path(c: &mut Criterion) {
+    let start = Coordinate::at(-100, 50, 50).unwrap();
+    let goal = Coordinate::at(100, -50, -50).unwrap();
+    let (_, one_way) = find_path_weighted_with_stats(start, goal, 1, terrain);
+    let (_, both_ways) = astar_bidirectional_with_stats(start, goal, 1, terrain);
+    println!("expanded: astar {}, bidirectional {}", one_way.expanded, both_ways.expanded);
+    c.bench_function("astar long path", |b| {
+        b.iter(|| find_path_weighted_with_stats(black_box(start), black_box(goal), 1, terrain))
+    });
+    c.bench_function("bidirectional astar long path", |b| {
+        b.iter(|| astar_bidirectional_with_stats(black_box(start), black_box(goal), 1, terrain))
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_long_path
+}
+criterion_main!(benches);
@@ -0,0 +1,1403 @@
+//! Algorithms over sets of hexes.
+use std::borrow::Borrow;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use pathfinding::nearest_sources;
+use hexmap::transform;
+use {
+    Coordinate, Direction, Edge, FractionalCoordinate, HexError, HexMap, HexRegion, Layout, Offset,
+    Symmetry, SymmetryCenter, SymmetryGroup, Vertex,
+};
+
+
+/// Get the hex nearest the average of `hexes`, or `None` if there are none.
+///
+/// The cube components are averaged and the result rounded with
+/// `FractionalCoordinate::round`, so the centroid of a shape symmetric about
+/// a hex is exactly that hex. The centroid of a concave shape may not be one
+/// of its hexes; see `centroid_in_set`.
+pub fn centroid<I: IntoIterator<Item = Coordinate>>(hexes: I) -> Option<Coordinate> {
+    weighted_centroid(hexes.into_iter().map(|c| (c, 1.0)))
+}
+
+/// Get the hex nearest the average of `hexes`, weighted by the `f64` paired
+/// with each, e.g. to place a capital at a territory's center of population.
+///
+/// This is `None` if there are no hexes, or if the weights don't sum to a
+/// positive finite number.
+pub fn weighted_centroid<I: IntoIterator<Item = (Coordinate, f64)>>(hexes: I) -> Option<Coordinate> {
+    mean(hexes).map(|mean| mean.round())
+}
+
+/// Get the member of `hexes` nearest their average, or `None` if there are
+/// none.
+///
+/// Unlike `centroid`, the result is always one of `hexes`, even when they
+/// form a concave shape like a crescent. Ties go to the least hex by `Ord`.
+pub fn centroid_in_set<I: IntoIterator<Item = Coordinate>>(hexes: I) -> Option<Coordinate> {
+    let mut hexes: Vec<Coordinate> = hexes.into_iter().collect();
+    hexes.sort();
+    let mean = mean(hexes.iter().map(|c| (*c, 1.0)))?;
+    let distance = |c: &Coordinate| FractionalCoordinate::from(*c).distance_to(mean);
+    hexes.into_iter().fold(None, |nearest: Option<Coordinate>, c| match nearest {
+        Some(n) if distance(&n) <= distance(&c) => Some(n),
+        _ => Some(c),
+    })
+}
+
+/// Get the hexes in both `a` and `b`.
+///
+/// Like `union` and `difference`, this takes anything that iterates over
+/// hexes or references to them, such as a `&HashSet` or a `Vec`, and ignores
+/// duplicates.
+pub fn intersect<A, B>(a: A, b: B) -> HashSet<Coordinate>
+    where A: IntoIterator, A::Item: Borrow<Coordinate>,
+          B: IntoIterator, B::Item: Borrow<Coordinate>
+{
+    let b = collect(b);
+    a.into_iter().map(|c| *c.borrow()).filter(|c| b.contains(c)).collect()
+}
+
+/// Get the hexes in either `a` or `b`.
+pub fn union<A, B>(a: A, b: B) -> HashSet<Coordinate>
+    where A: IntoIterator, A::Item: Borrow<Coordinate>,
+          B: IntoIterator, B::Item: Borrow<Coordinate>
+{
+    let mut union = collect(a);
+    union.extend(b.into_iter().map(|c| *c.borrow()));
+    union
+}
+
+/// Get the hexes in `a` but not in `b`.
+pub fn difference<A, B>(a: A, b: B) -> HashSet<Coordinate>
+    where A: IntoIterator, A::Item: Borrow<Coordinate>,
+          B: IntoIterator, B::Item: Borrow<Coordinate>
+{
+    let b = collect(b);
+    a.into_iter().map(|c| *c.borrow()).filter(|c| !b.contains(c)).collect()
+}
+
+/// Get the hexes in `region` together with all their neighbors, i.e. the
+/// region grown by one ring. This is `HexSet::expand(1)` for a `HashSet`.
+pub fn dilate(region: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+    region.iter().flat_map(|c| c.iter_neighbors().chain(Some(*c))).collect()
+}
+
+/// Get the hexes in `region` whose six neighbors are all in it too, i.e. the
+/// region worn away by one ring. This is `HexSet::shrink(1)` for a
+/// `HashSet`, and `erode(&dilate(region))` always contains `region`.
+pub fn erode(region: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+    region.iter()
+        .filter(|c| c.iter_neighbors().all(|n| region.contains(&n)))
+        .cloned()
+        .collect()
+}
+
+/// Get the hexes in `region` with at least one neighbor outside it, i.e.
+/// what `erode` removes.
+///
+/// Unlike `perimeter_edges`, this gives hexes inside the region rather than
+/// the edges around it.
+pub fn perimeter(region: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+    region.iter()
+        .filter(|c| c.iter_neighbors().any(|n| !region.contains(&n)))
+        .cloned()
+        .collect()
+}
+
+/// Get the hexes whose nearest hex in `region` is exactly `d` away.
+///
+/// `region_ring(region, 0)` is the region itself. The rings are found one
+/// after another, each from the one before, in a breadth-first search from
+/// the whole region at once, so this takes time in proportion to the number
+/// of hexes within `d` of the region.
+pub fn region_ring(region: &HashSet<Coordinate>, d: u32) -> HashSet<Coordinate> {
+    let mut seen = region.clone();
+    let mut ring = region.clone();
+    for _ in 0..d {
+        ring = ring.iter()
+            .flat_map(|c| c.iter_neighbors())
+            .filter(|n| !seen.contains(n))
+            .collect();
+        seen.extend(ring.iter().cloned());
+    }
+    ring
+}
+
+/// Split `region` into its connected components, the largest sets of hexes
+/// that can each be crossed by stepping between neighbors in the region.
+///
+/// Components come in order of their least hexes by `Ord`.
+pub fn connected_components(region: &HashSet<Coordinate>) -> Vec<HashSet<Coordinate>> {
+    let mut starts: Vec<Coordinate> = region.iter().cloned().collect();
+    starts.sort();
+    let mut seen = HashSet::new();
+    let mut components = Vec::new();
+    for start in starts {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut component = HashSet::new();
+        let mut frontier = vec![start];
+        while let Some(current) = frontier.pop() {
+            component.insert(current);
+            for neighbor in current.iter_neighbors() {
+                if region.contains(&neighbor) && seen.insert(neighbor) {
+                    frontier.push(neighbor);
+                }
+            }
+        }
+        components.push(component);
+    }
+    components
+}
+
+/// Get each pair of adjacent hexes with the first in `a` and the second in
+/// `b`, ordered by the first hex and then the direction to the second, as
+/// in `Coordinate::neighbors`.
+///
+/// Each pair is one edge of the border between the two regions; see
+/// `Edge::new` to draw it.
+pub fn border_between(a: &HashSet<Coordinate>, b: &HashSet<Coordinate>) -> Vec<(Coordinate, Coordinate)> {
+    let mut hexes: Vec<Coordinate> = a.iter().cloned().collect();
+    hexes.sort();
+    hexes.into_iter()
+        .flat_map(|c| c.iter_neighbors().filter(|n| b.contains(n)).map(move |n| (c, n)))
+        .collect()
+}
+
+/// Reflect every hex of `region` with `Coordinate::reflect_x`, across the
+/// line through the origin where `y == z`.
+pub fn reflect_region_x(region: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+    region.iter().map(|c| c.reflect_x()).collect()
+}
+
+/// Reflect every hex of `region` with `Coordinate::reflect_y`, across the
+/// line through the origin where `x == z`.
+pub fn reflect_region_y(region: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+    region.iter().map(|c| c.reflect_y()).collect()
+}
+
+/// Reflect every hex of `region` with `Coordinate::reflect_z`, across the
+/// line through the origin where `x == y`.
+pub fn reflect_region_z(region: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+    region.iter().map(|c| c.reflect_z()).collect()
+}
+
+/// The least and greatest value of each cube component over a set of hexes.
+///
+/// Since the axial `q` and `r` components are the cube `x` and `y`
+/// components, this also bounds them. The hexes within the bounds form a
+/// hexagon, possibly with unequal sides.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct Bounds {
+    x: (i64, i64),
+    y: (i64, i64),
+    z: (i64, i64),
+}
+
+impl Bounds {
+    /// Get the least and greatest `x` components.
+    pub fn x(&self) -> (i64, i64) {
+        self.x
+    }
+
+    /// Get the least and greatest `y` components.
+    pub fn y(&self) -> (i64, i64) {
+        self.y
+    }
+
+    /// Get the least and greatest `z` components.
+    pub fn z(&self) -> (i64, i64) {
+        self.z
+    }
+
+    /// Get the least and greatest axial `q` components, i.e. `x`.
+    pub fn q(&self) -> (i64, i64) {
+        self.x
+    }
+
+    /// Get the least and greatest axial `r` components, i.e. `y`.
+    pub fn r(&self) -> (i64, i64) {
+        self.y
+    }
+
+    /// Get the region these bounds enclose.
+    pub fn region(&self) -> HexRegion {
+        HexRegion {
+            x_min: self.x.0,
+            x_max: self.x.1,
+            y_min: self.y.0,
+            y_max: self.y.1,
+            z_min: self.z.0,
+            z_max: self.z.1,
+        }
+    }
+
+    /// Create the bounds of a nonempty region whose every range is reached
+    /// by one of its hexes.
+    pub(crate) fn from_tight(region: &HexRegion) -> Bounds {
+        Bounds {
+            x: (region.x_min, region.x_max),
+            y: (region.y_min, region.y_max),
+            z: (region.z_min, region.z_max),
+        }
+    }
+
+    /// Whether a hex lies within these bounds.
+    pub fn contains(&self, c: Coordinate) -> bool {
+        self.region().contains(c)
+    }
+
+    /// Get the smallest bounds containing both these and `other`.
+    pub fn merge(&self, other: Bounds) -> Bounds {
+        let merge = |a: (i64, i64), b: (i64, i64)| (a.0.min(b.0), a.1.max(b.1));
+        Bounds { x: merge(self.x, other.x), y: merge(self.y, other.y), z: merge(self.z, other.z) }
+    }
+
+    /// Iterate over every hex within these bounds, by increasing `y` and
+    /// then increasing `x`, i.e. in `Ord` order.
+    pub fn iter(&self) -> impl Iterator<Item = Coordinate> {
+        self.region().iter()
+    }
+
+    /// Get the number of columns spanned by these bounds when laid out in
+    /// `offset` coordinates.
+    pub fn width(&self, offset: Offset) -> i64 {
+        let (lo, hi) = self.region().offset_extent(offset, true);
+        hi - lo + 1
+    }
+
+    /// Get the number of rows spanned by these bounds when laid out in
+    /// `offset` coordinates.
+    pub fn height(&self, offset: Offset) -> i64 {
+        let (lo, hi) = self.region().offset_extent(offset, false);
+        hi - lo + 1
+    }
+}
+
+/// Get the tightest bounds containing `hexes`, or `None` if there are none.
+pub fn bounds<I: IntoIterator<Item = Coordinate>>(hexes: I) -> Option<Bounds> {
+    hexes.into_iter()
+        .map(|c| Bounds { x: (c.x(), c.x()), y: (c.y(), c.y()), z: (c.z(), c.z()) })
+        .fold(None, |merged: Option<Bounds>, b| Some(merged.map_or(b, |m| m.merge(b))))
+}
+
+/// Get the center and radius of the smallest hexagon containing `hexes`, or
+/// `None` if there are none.
+///
+/// When several centers give the same radius, the one with the least `x`
+/// and then the least `y` is chosen.
+pub fn bounding_hexagon<I: IntoIterator<Item = Coordinate>>(hexes: I) -> Option<(Coordinate, u32)> {
+    let b = bounds(hexes)?;
+    let axes = [b.x, b.y, b.z];
+    // A center within `radius` of every hex must have each component within
+    // `radius` of both of that component's bounds.
+    let mut radius = axes.iter().map(|&(lo, hi)| (hi - lo + 1) / 2).max().unwrap_or(0);
+    loop {
+        let lows: Vec<i64> = axes.iter().map(|&(_, hi)| hi - radius).collect();
+        let highs: Vec<i64> = axes.iter().map(|&(lo, _)| lo + radius).collect();
+        if lows.iter().sum::<i64>() <= 0 && highs.iter().sum::<i64>() >= 0 {
+            // Take the least `x` that leaves room for `y` and `z` to sum to
+            // zero with it, then the least `y` that leaves room for `z`.
+            let x = lows[0].max(-highs[1] - highs[2]);
+            let y = lows[1].max(-x - highs[2]);
+            return Some((Coordinate::at_unchecked(x, y, -x - y), radius as u32));
+        }
+        radius += 1;
+    }
+}
+
+/// Get the convex hull of `hexes`: the smallest set containing them and
+/// every hex of the `line_to` between two of its hexes.
+///
+/// Such a set is always bounded by twelve half-planes, a least and greatest
+/// extent along each cube axis and each diagonal, so the hull starts as the
+/// hexes within the extents of `hexes`. Where a line between two of its
+/// border hexes strays outside, the extents grow to take it in, which
+/// rarely takes more than one check. Each check joins every pair of border
+/// hexes, so finding the hull takes time cubic in its width.
+///
+/// The hull lies within the `bounds` of `hexes`, but only fills them when
+/// the extremes along each axis make it a hexagon, as for a ring.
+pub fn convex_hull(hexes: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+    let mut hull = within_extents(hexes.iter().cloned());
+    loop {
+        let border: Vec<Coordinate> = hull.iter().cloned()
+            .filter(|c| c.neighbors().iter().any(|n| !hull.contains(n)))
+            .collect();
+        // Lines don't depend on which end they start from, so each pair of
+        // border hexes is joined once.
+        let strays: Vec<Coordinate> = border.iter().enumerate()
+            .flat_map(|(i, a)| border[..i].iter().flat_map(move |b| a.line_iter(*b)))
+            .filter(|c| !hull.contains(c))
+            .collect();
+        if strays.is_empty() {
+            return hull;
+        }
+        hull = within_extents(hull.iter().cloned().chain(strays));
+    }
+}
+
+/// Get the hexes within the least and greatest extents of `hexes` along
+/// each cube axis and each diagonal, i.e. `x - y`, `y - z`, and `z - x`.
+fn within_extents<I: IntoIterator<Item = Coordinate>>(hexes: I) -> HashSet<Coordinate> {
+    let diagonals = |c: Coordinate| [c.x() - c.y(), c.y() - c.z(), c.z() - c.x()];
+    let hexes: Vec<Coordinate> = hexes.into_iter().collect();
+    let b = match bounds(hexes.iter().cloned()) {
+        Some(b) => b,
+        None => return HashSet::new(),
+    };
+    let mut lows = [i64::MAX; 3];
+    let mut highs = [i64::MIN; 3];
+    for c in hexes {
+        for (i, d) in diagonals(c).iter().enumerate() {
+            lows[i] = lows[i].min(*d);
+            highs[i] = highs[i].max(*d);
+        }
+    }
+    b.iter()
+        .filter(|c| diagonals(*c).iter().enumerate().all(|(i, d)| lows[i] <= *d && *d <= highs[i]))
+        .collect()
+}
+
+/// Get the hexes on the border of the `convex_hull` of `hexes`, in order
+/// counterclockwise around it, starting from its least hex by `Ord`.
+///
+/// Each hex is listed once, when the walk around the hull first reaches it.
+/// Each is adjacent to the next, and the last to the first, except where
+/// the hull is a single hex wide: the walk passes such hexes on both sides,
+/// and skips over them the second time, as at the far end of a line.
+pub fn hull_boundary(hexes: &HashSet<Coordinate>) -> Vec<Coordinate> {
+    let hull = convex_hull(hexes);
+    let start = match hull.iter().min() {
+        Some(&start) => start,
+        None => return Vec::new(),
+    };
+    let mut border = vec![start];
+    let mut visited: HashSet<Coordinate> = border.iter().cloned().collect();
+    let mut current = start;
+    let mut first_step = None;
+    // The least hex has nothing to its west. Each step searches
+    // counterclockwise from just past a neighbor known to be outside.
+    let mut outside = Direction::W;
+    loop {
+        let mut dir = outside.counter_clockwise();
+        while !hull.contains(&(current + dir.offset())) && dir != outside {
+            dir = dir.counter_clockwise();
+        }
+        let next = current + dir.offset();
+        if !hull.contains(&next) {
+            return border;
+        }
+        // The walk is done once it leaves the start the way it first did.
+        if current == start {
+            if first_step == Some(dir) {
+                return border;
+            }
+            first_step = first_step.or(Some(dir));
+        }
+        if visited.insert(next) {
+            border.push(next);
+        }
+        outside = dir.opposite().counter_clockwise();
+        current = next;
+    }
+}
+
+/// Get every edge between a hex in `region` and a hex outside it.
+///
+/// Edges are ordered by their hex in `region`, by `Ord`, and then by
+/// direction in the order of `Direction::ALL`.
+pub fn perimeter_edges(region: &HashSet<Coordinate>) -> Vec<Edge> {
+    border_sides(region)
+        .into_iter()
+        .map(|(hex, side)| Edge::from_direction(hex, Direction::ALL[side]))
+        .collect()
+}
+
+/// Get the `perimeter_edges` of `region` stitched into closed loops of
+/// corner positions in pixel space.
+///
+/// Each loop keeps `region` on its left, so an outer boundary runs
+/// counterclockwise as viewed on screen and the boundary of a hole runs
+/// clockwise. Every corner is shared by three mutually adjacent hexes, so
+/// two loops never meet at a corner and each is a simple polygon. A loop's
+/// last corner connects back to its first, which is not repeated. The
+/// first loop is the outer boundary of the part of `region` holding its
+/// least hex by `Ord`.
+pub fn perimeter_loops(region: &HashSet<Coordinate>, layout: &Layout) -> Vec<Vec<(f64, f64)>> {
+    border_loops(region)
+        .into_iter()
+        .map(|sides| {
+            sides.into_iter().map(|(hex, i)| corner_pixel(layout, hex, (i + 5) % 6)).collect()
+        })
+        .collect()
+}
+
+/// Get the `perimeter_edges` of `region` in order around it, so that a
+/// polyline through them can be stroked.
+///
+/// The edges run loop by loop as in `perimeter_loops`, with `region` on the
+/// left, and each edge shares a corner with the next in the same loop. The
+/// last edge of each loop shares a corner with the loop's first.
+pub fn boundary_edges(region: &HashSet<Coordinate>) -> Vec<Edge> {
+    border_loops(region)
+        .into_iter()
+        .flatten()
+        .map(|(hex, side)| Edge::from_direction(hex, Direction::ALL[side]))
+        .collect()
+}
+
+/// Stitch the `border_sides` of `region` into closed loops, each starting
+/// from its least side.
+fn border_loops(region: &HashSet<Coordinate>) -> Vec<Vec<(Coordinate, usize)>> {
+    let sides = border_sides(region);
+    let mut remaining: HashSet<(Coordinate, usize)> = sides.iter().cloned().collect();
+    let mut loops = Vec::new();
+    for &first in sides.iter() {
+        if !remaining.remove(&first) {
+            continue;
+        }
+        let mut border = Vec::new();
+        let mut side = first;
+        loop {
+            let (hex, i) = side;
+            border.push(side);
+            // Follow the boundary counterclockwise around this hex, or across
+            // to the neighbor this side's end corner is shared with.
+            let neighbor = hex + Direction::ALL[(i + 1) % 6].offset();
+            side = if region.contains(&neighbor) {
+                (neighbor, (i + 5) % 6)
+            } else {
+                (hex, (i + 1) % 6)
+            };
+            if !remaining.remove(&side) {
+                break;
+            }
+        }
+        loops.push(border);
+    }
+    loops
+}
+
+/// Get each hex in `region` by `Ord`, paired with the index into
+/// `Direction::ALL` of each direction leading out of `region`. The side in
+/// direction `i` runs from corner `i - 1` to corner `i` of the hex.
+fn border_sides(region: &HashSet<Coordinate>) -> Vec<(Coordinate, usize)> {
+    let mut hexes: Vec<Coordinate> = region.iter().cloned().collect();
+    hexes.sort();
+    hexes.into_iter()
+        .flat_map(|hex| (0..6).map(move |i| (hex, i)))
+        .filter(|&(hex, i)| !region.contains(&(hex + Direction::ALL[i].offset())))
+        .collect()
+}
+
+/// Get the pixel position of a corner of a hex: the average of the centers
+/// of the three hexes that meet there.
+fn corner_pixel(layout: &Layout, hex: Coordinate, corner: usize) -> (f64, f64) {
+    let hexes = Vertex::new(hex, corner).expect("corners are within 0..6").hexes();
+    let (x, y) = hexes.iter()
+        .map(|&c| layout.hex_to_pixel(c))
+        .fold((0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1));
+    (x / 3.0, y / 3.0)
+}
+
+/// Assign each of `hexes` to the nearest of `seeds` by `distance_to`,
+/// mapping it to that seed's index.
+///
+/// Where several seeds are equally near, the hex goes to the one with the
+/// lowest index. This fails with `HexError::EmptyInput` if there are no
+/// seeds.
+pub fn voronoi<I>(hexes: I, seeds: &[Coordinate]) -> Result<HashMap<Coordinate, usize>, HexError>
+    where I: IntoIterator<Item = Coordinate>
+{
+    if seeds.is_empty() {
+        return Err(HexError::EmptyInput);
+    }
+    Ok(hexes.into_iter()
+        .map(|hex| {
+            let nearest = (0..seeds.len()).min_by_key(|&i| (hex.distance_to(seeds[i]), i));
+            (hex, nearest.expect("there are seeds"))
+        })
+        .collect())
+}
+
+/// Assign each of `hexes` to the seed it is cheapest to reach from, moving
+/// only through `hexes`, mapping it to that seed's index.
+///
+/// `cost` gives the cost of entering a hex, or `None` if it is impassable,
+/// as for `pathfinding::find_path_weighted`. Where several seeds are equally
+/// cheap, the hex goes to the one with the lowest index. Hexes no seed can
+/// reach are left out. This is a single Dijkstra search seeded with every
+/// seed, and fails with `HexError::EmptyInput` if there are none.
+pub fn voronoi_weighted<I, F>(hexes: I, seeds: &[Coordinate], cost: F) -> Result<HashMap<Coordinate, usize>, HexError>
+    where I: IntoIterator<Item = Coordinate>,
+          F: Fn(Coordinate) -> Option<u32>
+{
+    if seeds.is_empty() {
+        return Err(HexError::EmptyInput);
+    }
+    let hexes = collect(hexes);
+    let nearest = nearest_sources(seeds, u64::MAX, |c| {
+        if hexes.contains(&c) { cost(c) } else { None }
+    });
+    Ok(nearest.into_iter()
+        .filter(|(c, _)| hexes.contains(c))
+        .map(|(c, (_, seed))| (c, seed))
+        .collect())
+}
+
+/// Get the number of steps from each hex in `shape` to the nearest hex in
+/// it for which `is_feature` is true, moving only through `shape`.
+///
+/// Features are at distance 0, and hexes no feature can reach have `None`.
+/// Every hex is visited once, in a breadth-first search from all the
+/// features at once. The result is a sparse map with a value at every hex in
+/// `shape`.
+pub fn distance_transform<I, F>(shape: I, is_feature: F) -> HexMap<Option<u32>>
+    where I: IntoIterator<Item = Coordinate>,
+          F: Fn(Coordinate) -> bool
+{
+    let shape = collect(shape);
+    let mut distances: HashMap<Coordinate, u32> = HashMap::new();
+    let mut frontier = VecDeque::new();
+    for &c in shape.iter().filter(|&&c| is_feature(c)) {
+        distances.insert(c, 0);
+        frontier.push_back(c);
+    }
+    while let Some(current) = frontier.pop_front() {
+        let next_distance = distances[&current] + 1;
+        for neighbor in current.neighbors().iter() {
+            if shape.contains(neighbor) && !distances.contains_key(neighbor) {
+                distances.insert(*neighbor, next_distance);
+                frontier.push_back(*neighbor);
+            }
+        }
+    }
+    shape.into_iter().map(|c| (c, distances.get(&c).cloned())).collect()
+}
+
+/// Get the sides exerting a zone of control on each hex, given the
+/// positions of units and their sides.
+///
+/// Each unit exerts a zone of control on the hexes adjacent to it, but not
+/// on its own hex, so a lone unit is never in its own zone. A hex holding a
+/// unit can still be in the zones of the units next to it, of either side.
+/// Hexes in no zone are left out.
+pub fn zone_of_control<S>(units: &[(Coordinate, S)]) -> HashMap<Coordinate, BTreeSet<S>>
+    where S: Copy + Ord
+{
+    let mut zones: HashMap<Coordinate, BTreeSet<S>> = HashMap::new();
+    for &(unit, side) in units {
+        for neighbor in unit.iter_neighbors() {
+            zones.entry(neighbor).or_default().insert(side);
+        }
+    }
+    zones
+}
+
+/// Get the rotations and reflections about the center of `center` that
+/// carry `region` onto itself.
+pub fn symmetries(region: &HashSet<Coordinate>, center: Coordinate) -> SymmetryGroup {
+    symmetries_about(region, SymmetryCenter::Hex(center))
+}
+
+/// Get the rotations and reflections about `center`, which may be an edge
+/// or a corner as well as a hex, that carry `region` onto itself.
+pub fn symmetries_about(region: &HashSet<Coordinate>, center: SymmetryCenter) -> SymmetryGroup {
+    let origin = center.scaled();
+    SymmetryGroup::from_elements(|element| {
+        region.iter().all(|c| {
+            let scaled = Coordinate::from_axial(6 * c.x(), 6 * c.y());
+            let image = transform(element, scaled - origin) + origin;
+            image.x() % 6 == 0 && image.y() % 6 == 0
+                && region.contains(&Coordinate::from_axial(image.x() / 6, image.y() / 6))
+        })
+    })
+}
+
+/// Whether every rotation or reflection of `symmetry` about the center of
+/// `center` carries `region` onto itself.
+pub fn is_symmetric(region: &HashSet<Coordinate>, center: Coordinate, symmetry: Symmetry) -> bool {
+    symmetries(region, center).contains(symmetry)
+}
+
+/// Find the point with the most symmetries of `region`, as for
+/// `symmetries_about`, or `None` if `region` is empty.
+///
+/// Any rotation that carries `region` onto itself is about its centroid, and
+/// any reflection is across a line through it, so only the hexes, edges, and
+/// corners near the centroid are tried. Of those with the most symmetries,
+/// the one nearest the centroid is chosen.
+pub fn best_symmetry_center(region: &HashSet<Coordinate>) -> Option<(SymmetryCenter, SymmetryGroup)> {
+    let centroid = mean(region.iter().map(|c| (*c, 1.0)))?;
+    let mut candidates = Vec::new();
+    for hex in centroid.round().iter_spiral(1) {
+        candidates.push(SymmetryCenter::Hex(hex));
+        for (i, dir) in Direction::ALL.iter().enumerate() {
+            candidates.push(SymmetryCenter::Edge(Edge::from_direction(hex, *dir)));
+            let corner = Vertex::new(hex, i).expect("every direction's index is a corner");
+            candidates.push(SymmetryCenter::Vertex(corner));
+        }
+    }
+    let distance = |center: &SymmetryCenter| {
+        let scaled = center.scaled();
+        let (q, r) = (scaled.x() as f64 / 6.0, scaled.y() as f64 / 6.0);
+        FractionalCoordinate::from_axial(q, r).distance_to(centroid)
+    };
+    let mut seen = HashSet::new();
+    candidates.retain(|center| seen.insert(*center));
+    candidates.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+    candidates.into_iter()
+        .map(|center| (center, symmetries_about(region, center)))
+        .fold(None, |best: Option<(SymmetryCenter, SymmetryGroup)>, (center, group)| match best {
+            Some((_, b)) if b.order() >= group.order() => best,
+            _ => Some((center, group)),
+        })
+}
+
+fn collect<I>(hexes: I) -> HashSet<Coordinate>
+    where I: IntoIterator, I::Item: Borrow<Coordinate>
+{
+    hexes.into_iter().map(|c| *c.borrow()).collect()
+}
+
+/// Get the weighted average of `hexes`.
+fn mean<I: IntoIterator<Item = (Coordinate, f64)>>(hexes: I) -> Option<FractionalCoordinate> {
+    let (mut q, mut r, mut total) = (0.0, 0.0, 0.0);
+    for (c, weight) in hexes {
+        let (cq, cr) = c.to_axial();
+        q += cq as f64 * weight;
+        r += cr as f64 * weight;
+        total += weight;
+    }
+    if total > 0.0 && total.is_finite() {
+        Some(FractionalCoordinate::from_axial(q / total, r / total))
+    } else {
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Orientation;
+    use Axis;
+    use DIAGONALS;
+    use HexSet;
+    use Direction;
+
+    #[test]
+    fn it_finds_the_symmetries_of_hexagons() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let hexagon: HashSet<_> = center.spiral(2).into_iter().collect();
+        let group = symmetries(&hexagon, center);
+        assert_eq!(group.order(), 12);
+        assert!(is_symmetric(&hexagon, center, Symmetry::Full));
+        assert!(is_symmetric(&hexagon, center, Symmetry::Rotate60));
+        assert_eq!(symmetries(&hexagon, Coordinate::new()).order(), 1);
+        let mut lopsided = hexagon.clone();
+        lopsided.insert(center + Coordinate::at(3, -1, -2).unwrap());
+        assert_eq!(symmetries(&lopsided, center).order(), 1);
+        assert!(!is_symmetric(&lopsided, center, Symmetry::Rotate180));
+    }
+
+    #[test]
+    fn it_finds_the_symmetries_of_nearly_symmetric_sets() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        // A ring missing one hex, which only the line through it preserves.
+        let mut ring: HashSet<_> = center.ring(2).into_iter().collect();
+        ring.remove(&(center + Coordinate::at(2, 0, -2).unwrap()));
+        let group = symmetries(&ring, center);
+        assert_eq!(group.order(), 2);
+        assert!(group.has_perpendicular_reflection(Axis::Y));
+        assert!(!group.has_rotation(3));
+        // Three arms, bent the same way, admit rotations but no reflections.
+        let mut pinwheel = HashSet::new();
+        for steps in [0, 2, 4].iter() {
+            pinwheel.insert(Coordinate::at(1, 0, -1).unwrap().rotate(*steps));
+            pinwheel.insert(Coordinate::at(2, -1, -1).unwrap().rotate(*steps));
+        }
+        let group = symmetries(&pinwheel, Coordinate::new());
+        assert!(is_symmetric(&pinwheel, Coordinate::new(), Symmetry::Rotate120));
+        assert!(!is_symmetric(&pinwheel, Coordinate::new(), Symmetry::Rotate60));
+        assert_eq!(group.order(), 3);
+        for axis in [Axis::X, Axis::Y, Axis::Z].iter() {
+            assert!(!group.has_reflection(*axis) && !group.has_perpendicular_reflection(*axis));
+        }
+    }
+
+    #[test]
+    fn it_finds_symmetry_centers_between_hexes() {
+        // Three hexes around a corner.
+        let corner = Vertex::new(Coordinate::new(), 0).unwrap();
+        let triangle: HashSet<_> = corner.hexes().iter().cloned().collect();
+        let (center, group) = best_symmetry_center(&triangle).unwrap();
+        assert_eq!(center, SymmetryCenter::Vertex(corner));
+        assert_eq!(group.order(), 6);
+        assert!(group.has_rotation(2) && !group.has_rotation(3));
+        assert_eq!(symmetries(&triangle, Coordinate::new()).order(), 2);
+        // Two hexes either side of an edge.
+        let edge = Edge::from_direction(Coordinate::at(-3, -1, 4).unwrap(), Direction::NE);
+        let (a, b) = edge.hexes();
+        let pair: HashSet<_> = vec![a, b].into_iter().collect();
+        let (center, group) = best_symmetry_center(&pair).unwrap();
+        assert_eq!(center, SymmetryCenter::Edge(edge));
+        assert_eq!(group.order(), 4);
+        assert!(group.has_rotation(3));
+        // A hexagon is most symmetric about its own center.
+        let hexagon: HashSet<_> = a.spiral(3).into_iter().collect();
+        assert_eq!(best_symmetry_center(&hexagon).unwrap().0, SymmetryCenter::Hex(a));
+        assert_eq!(best_symmetry_center(&HashSet::new()), None);
+    }
+
+    #[test]
+    fn it_finds_zones_of_control() {
+        let a = Coordinate::new();
+        let b = Coordinate::at(2, 0, -2).unwrap();
+        let c = Coordinate::at(-1, 0, 1).unwrap();
+        let zones = zone_of_control(&[(a, 'a'), (b, 'b'), (c, 'b')]);
+        let between = Coordinate::at(1, 0, -1).unwrap();
+        assert_eq!(zones[&between], ['a', 'b'].iter().cloned().collect());
+        // A unit is in the zones of its neighbors but not its own.
+        assert_eq!(zones[&a], ['b'].iter().cloned().collect());
+        assert_eq!(zones[&c], ['a'].iter().cloned().collect());
+        assert!(!zones.contains_key(&b));
+        assert_eq!(zones.len(), 6 + 5 + 4);
+        assert!(zone_of_control::<u8>(&[]).is_empty());
+    }
+
+    #[test]
+    fn it_finds_nothing_at_the_center_of_nothing() {
+        assert_eq!(centroid(vec![]), None);
+        assert_eq!(weighted_centroid(vec![]), None);
+        assert_eq!(centroid_in_set(vec![]), None);
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(weighted_centroid(vec![(coord, 0.0)]), None);
+    }
+
+    #[test]
+    fn it_intersects_overlapping_rings() {
+        let a = Coordinate::new();
+        let b = Coordinate::at(2, -1, -1).unwrap();
+        let ring_a = a.ring(1);
+        let ring_b: HashSet<_> = b.ring(1).into_iter().collect();
+        let shared = intersect(&ring_a, &ring_b);
+        let expected: HashSet<_> = vec![
+            Coordinate::at(1, -1, 0).unwrap(),
+            Coordinate::at(1, 0, -1).unwrap(),
+        ].into_iter().collect();
+        assert_eq!(shared, expected);
+        assert_eq!(intersect(ring_a.clone(), &ring_b), expected);
+
+        let remaining = difference(&ring_a, &ring_b);
+        assert_eq!(remaining.len(), 4);
+        assert!(remaining.iter().all(|c| !shared.contains(c)));
+        assert_eq!(union(&remaining, &shared), collect(&ring_a));
+    }
+
+    #[test]
+    fn it_dilates_and_erodes_regions() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let single: HashSet<_> = vec![coord].into_iter().collect();
+        let grown = dilate(&single);
+        assert_eq!(grown, coord.spiral(1).into_iter().collect());
+        assert_eq!(erode(&grown), single);
+        assert!(erode(&single).is_empty());
+        let ring: HashSet<_> = coord.ring(3).into_iter().collect();
+        assert!(erode(&ring).is_empty());
+        assert_eq!(dilate(&ring).len(), Coordinate::spiral_size(4) - Coordinate::spiral_size(1));
+        let hexagon: HashSet<_> = coord.spiral(3).into_iter().collect();
+        let hex_set: HexSet = hexagon.iter().cloned().collect();
+        assert_eq!(erode(&hexagon), hex_set.shrink(1).into_iter().collect());
+        assert_eq!(dilate(&hexagon), hex_set.expand(1).into_iter().collect());
+    }
+
+    #[test]
+    fn it_finds_rings_around_regions() {
+        // An L shape with a notch, so rings meet themselves going around it.
+        let mut region: HashSet<_> = (0..6).map(|q| Coordinate::from_axial(q, 0)).collect();
+        region.extend((0..6).map(|r| Coordinate::from_axial(0, r)));
+        region.insert(Coordinate::from_axial(2, 2));
+        assert_eq!(region_ring(&region, 0), region);
+        let hex_set: HexSet = region.iter().cloned().collect();
+        let mut covered = HashSet::new();
+        for d in 0..6 {
+            let ring = region_ring(&region, d);
+            assert!(ring.iter().all(|c| !covered.contains(c)));
+            covered.extend(ring.iter().cloned());
+            assert_eq!(covered, hex_set.expand(d).into_iter().collect());
+            for c in ring {
+                let nearest = region.iter().map(|r| r.distance_to(c)).min().unwrap();
+                assert_eq!(nearest, i64::from(d));
+            }
+        }
+        assert!(region_ring(&HashSet::new(), 3).is_empty());
+    }
+
+    #[test]
+    fn it_finds_rings_around_long_coastlines() {
+        let length = 10_000;
+        let coast: HashSet<_> = (0..length).map(|q| Coordinate::from_axial(q, 0)).collect();
+        for d in [1, 4].iter() {
+            let ring = region_ring(&coast, *d);
+            assert_eq!(ring.len() as i64, 2 * length + 6 * i64::from(*d) - 2);
+        }
+    }
+
+    #[test]
+    fn it_finds_connected_components() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(5, 0, -5).unwrap();
+        let cluster_a: HashSet<_> = a.spiral(1).into_iter().collect();
+        let cluster_b: HashSet<_> = b.ring(2).into_iter().collect();
+        let both = union(&cluster_a, &cluster_b);
+        assert_eq!(connected_components(&both), vec![cluster_a.clone(), cluster_b]);
+        assert_eq!(connected_components(&cluster_a), vec![cluster_a]);
+        // Hexes a diagonal step apart have no edge between them.
+        let diagonal: HashSet<_> = vec![a, a + DIAGONALS[0]].into_iter().collect();
+        assert_eq!(connected_components(&diagonal).len(), 2);
+        assert!(connected_components(&HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn it_finds_borders_between_regions() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = a + Direction::NE.offset();
+        let just_a: HashSet<_> = vec![a].into_iter().collect();
+        let just_b: HashSet<_> = vec![b].into_iter().collect();
+        assert_eq!(border_between(&just_a, &just_b), vec![(a, b)]);
+        assert_eq!(border_between(&just_b, &just_a), vec![(b, a)]);
+        let far: HashSet<_> = vec![a + Coordinate::at(5, 0, -5).unwrap()].into_iter().collect();
+        assert!(border_between(&just_a, &far).is_empty());
+        // A hexagon's border with the ring around it has two edges per ring
+        // hex, less one at each corner.
+        let inside: HashSet<_> = a.spiral(2).into_iter().collect();
+        let outside: HashSet<_> = a.ring(3).into_iter().collect();
+        let border = border_between(&inside, &outside);
+        assert_eq!(border.len(), 2 * 18 - 6);
+        assert!(border.iter().all(|&(x, y)| inside.contains(&x) && outside.contains(&y)));
+    }
+
+    #[test]
+    fn it_reflects_whole_regions() {
+        // An L-shaped region off to one side of the origin.
+        let corner = Coordinate::at(-3, -1, 4).unwrap();
+        let region: HashSet<_> = corner.line_to(corner + Coordinate::at(4, 0, -4).unwrap())
+            .into_iter()
+            .chain(corner.line_to(corner + Coordinate::at(0, 3, -3).unwrap()))
+            .collect();
+        let reflect = |axis: Axis, region: &HashSet<Coordinate>| match axis {
+            Axis::X => reflect_region_x(region),
+            Axis::Y => reflect_region_y(region),
+            Axis::Z => reflect_region_z(region),
+        };
+        for axis in [Axis::X, Axis::Y, Axis::Z].iter() {
+            let reflected = reflect(*axis, &region);
+            assert_eq!(reflected.len(), region.len());
+            assert_ne!(reflected, region);
+            assert!(region.iter().all(|c| reflected.contains(&c.reflect(*axis))));
+            assert_eq!(reflect(*axis, &reflected), region);
+        }
+        assert!(reflect_region_x(&HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn it_finds_the_hexes_around_a_region() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let hexagon: HashSet<_> = coord.spiral(2).into_iter().collect();
+        assert_eq!(perimeter(&hexagon), coord.ring(2).into_iter().collect());
+        let single: HashSet<_> = vec![coord].into_iter().collect();
+        assert_eq!(perimeter(&single), single);
+        assert_eq!(union(&perimeter(&hexagon), &erode(&hexagon)), hexagon);
+        assert!(perimeter(&HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn it_ignores_duplicates_in_set_operations() {
+        let a = Coordinate::new();
+        let b = Coordinate::at(1, 0, -1).unwrap();
+        assert_eq!(union(vec![a, a], vec![b, a]).len(), 2);
+        assert_eq!(intersect(vec![a, a, b], vec![a, a]).len(), 1);
+        assert_eq!(difference(vec![a, b, b], vec![a]), vec![b].into_iter().collect());
+    }
+
+    #[test]
+    fn it_bounds_a_known_shape() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(2, 1, -3).unwrap();
+        let c = Coordinate::at(0, -4, 4).unwrap();
+        let bounds = bounds(vec![a, b, c]).unwrap();
+        assert_eq!(bounds.x(), (-3, 2));
+        assert_eq!(bounds.y(), (-4, 1));
+        assert_eq!(bounds.z(), (-3, 4));
+        assert_eq!((bounds.q(), bounds.r()), (bounds.x(), bounds.y()));
+        assert_eq!(super::bounds(vec![]), None);
+    }
+
+    #[test]
+    fn it_contains_hexes_up_to_the_boundary() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let bounds = bounds(center.ring(2)).unwrap();
+        for hex in center.spiral(2) {
+            assert!(bounds.contains(hex));
+        }
+        for hex in center.ring(3) {
+            assert!(!bounds.contains(hex));
+        }
+    }
+
+    #[test]
+    fn it_iterates_over_every_hex_within_bounds() {
+        let bounds = bounds(vec![
+            Coordinate::at(0, 0, 0).unwrap(),
+            Coordinate::at(4, 0, -4).unwrap(),
+            Coordinate::at(0, 2, -2).unwrap(),
+        ]).unwrap();
+        // A trapezoid of rows 5, 4, and 3 hexes long.
+        let hexes: Vec<_> = bounds.iter().collect();
+        assert_eq!(hexes.len(), 5 + 4 + 3);
+        assert!(hexes.iter().all(|c| bounds.contains(*c)));
+        let mut sorted = hexes.clone();
+        sorted.sort();
+        assert_eq!(hexes, sorted);
+        let around = Coordinate::new().spiral(8);
+        assert_eq!(around.iter().filter(|c| bounds.contains(**c)).count(), hexes.len());
+    }
+
+    #[test]
+    fn it_measures_bounds_in_offset_coordinates() {
+        let hexagon = bounds(Coordinate::new().spiral(2)).unwrap();
+        assert_eq!((hexagon.width(Offset::OddR), hexagon.height(Offset::OddR)), (5, 5));
+        assert_eq!((hexagon.width(Offset::EvenQ), hexagon.height(Offset::EvenQ)), (5, 5));
+        let row = bounds(vec![Coordinate::new(), Coordinate::at(3, 0, -3).unwrap()]).unwrap();
+        assert_eq!((row.width(Offset::OddR), row.height(Offset::OddR)), (4, 1));
+        assert_eq!((row.width(Offset::OddQ), row.height(Offset::OddQ)), (4, 2));
+    }
+
+    #[test]
+    fn it_merges_bounds() {
+        let a = bounds(vec![Coordinate::at(-3, -1, 4).unwrap()]).unwrap();
+        let b = bounds(vec![Coordinate::at(2, 1, -3).unwrap()]).unwrap();
+        let merged = a.merge(b);
+        assert_eq!(merged.x(), (-3, 2));
+        assert_eq!(merged.y(), (-1, 1));
+        assert_eq!(merged.z(), (-3, 4));
+        assert_eq!(merged.merge(a), merged);
+    }
+
+    /// A few scattered hexes around `center`, for checking against brute
+    /// force.
+    fn sample_of(center: Coordinate) -> Vec<Coordinate> {
+        [(2, -3, 1), (-1, 0, 1), (3, 1, -4), (0, 2, -2)].iter()
+            .map(|&(x, y, z)| center + Coordinate::at(x, y, z).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn it_finds_bounding_hexagons() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for radius in 0..5 {
+            assert_eq!(bounding_hexagon(center.ring(radius)), Some((center, radius as u32)));
+        }
+        assert_eq!(bounding_hexagon(vec![]), None);
+        let pair = vec![center, center + Coordinate::at(1, 0, -1).unwrap()];
+        let (around, radius) = bounding_hexagon(pair.clone()).unwrap();
+        assert_eq!(radius, 1);
+        assert!(pair.iter().all(|c| around.distance_to(*c) <= 1));
+        // Of the four hexes within 1 of both, two have the least `x`.
+        let northeast = center + Direction::NE.offset();
+        let tied = bounding_hexagon(vec![center, northeast]);
+        assert_eq!(tied, Some((center + Direction::NW.offset(), 1)));
+        assert_eq!(around, center);
+        let sets = [center.ring(2), pair, center.cone(Direction::W, 3), sample_of(center)];
+        for hexes in sets.iter() {
+            let (around, radius) = bounding_hexagon(hexes.iter().cloned()).unwrap();
+            let least = center.spiral(10).into_iter()
+                .filter(|c| hexes.iter().all(|h| c.distance_to(*h) <= i64::from(radius)))
+                .min_by_key(|c| (c.x(), c.y()));
+            assert_eq!(least, Some(around));
+            let closer = center.spiral(10).into_iter()
+                .find(|c| hexes.iter().all(|h| c.distance_to(*h) < i64::from(radius)));
+            assert_eq!(closer, None);
+        }
+        let line: Vec<_> = (0..5).map(|i| center + Coordinate::at(i, -i, 0).unwrap()).collect();
+        let (around, radius) = bounding_hexagon(line.clone()).unwrap();
+        assert_eq!(radius, 2);
+        assert!(line.iter().all(|c| around.distance_to(*c) <= 2));
+    }
+
+    #[test]
+    fn it_fills_the_hull_of_a_ring() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let ring: HashSet<_> = center.ring(3).into_iter().collect();
+        let hexagon: HashSet<_> = center.spiral(3).into_iter().collect();
+        assert_eq!(convex_hull(&ring), hexagon);
+        assert_eq!(convex_hull(&HashSet::new()), HashSet::new());
+    }
+
+    #[test]
+    fn it_keeps_collinear_hulls_on_their_line() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = a + Coordinate::at(0, 5, -5).unwrap();
+        let ends: HashSet<_> = vec![a, b].into_iter().collect();
+        let line: HashSet<_> = a.line_to(b).into_iter().collect();
+        assert_eq!(convex_hull(&ends), line);
+        let mut boundary = hull_boundary(&ends);
+        boundary.sort();
+        assert_eq!(boundary, a.line_to(b));
+    }
+
+    /// The hull as its definition gives it: lines between every pair of
+    /// hexes, added until none adds anything.
+    fn naive_hull(hexes: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+        let mut hull = hexes.clone();
+        loop {
+            let mut lines = HashSet::new();
+            for a in hull.iter() {
+                for b in hull.iter() {
+                    lines.extend(a.line_iter(*b));
+                }
+            }
+            if lines.len() == hull.len() {
+                return hull;
+            }
+            hull = lines;
+        }
+    }
+
+    #[test]
+    fn it_keeps_hulls_of_off_axis_pairs_on_their_line() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        for offset in [(2, -1, -1), (-1, 2, -1), (1, 1, -2)].iter() {
+            let b = a + Coordinate::at(offset.0, offset.1, offset.2).unwrap();
+            let ends: HashSet<_> = vec![a, b].into_iter().collect();
+            let mut line = a.line_to(b);
+            assert_eq!(line.len(), 3);
+            assert_eq!(convex_hull(&ends), line.iter().cloned().collect());
+            let mut boundary = hull_boundary(&ends);
+            boundary.sort();
+            line.sort();
+            assert_eq!(boundary, line);
+        }
+    }
+
+    #[test]
+    fn it_matches_the_hull_by_definition() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let offsets: [&[(i64, i64, i64)]; 4] = [
+            &[(0, 0, 0), (5, -2, -3)],
+            &[(0, 0, 0), (-4, 1, 3)],
+            &[(0, 0, 0), (4, -2, -2), (2, 2, -4)],
+            &[(0, 0, 0), (6, -1, -5), (1, 3, -4), (2, 1, -3)],
+        ];
+        for hexes in offsets.iter() {
+            let hexes: HashSet<_> = hexes.iter()
+                .map(|&(x, y, z)| center + Coordinate::at(x, y, z).unwrap())
+                .collect();
+            let hull = convex_hull(&hexes);
+            assert_eq!(hull, naive_hull(&hexes));
+            assert!(hull.len() < bounds(hexes.iter().cloned()).unwrap().iter().count());
+            let border: HashSet<_> = hull.iter().cloned()
+                .filter(|c| c.neighbors().iter().any(|n| !hull.contains(n)))
+                .collect();
+            let boundary = hull_boundary(&hexes);
+            assert_eq!(boundary.into_iter().collect::<HashSet<_>>(), border);
+        }
+    }
+
+    #[test]
+    fn it_fills_the_hull_of_a_large_ring_quickly() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let ring: HashSet<_> = center.iter_ring(40).collect();
+        let hull = convex_hull(&ring);
+        assert_eq!(hull.len(), Coordinate::spiral_size(40));
+        assert!(center.iter_spiral(40).all(|c| hull.contains(&c)));
+    }
+
+    proptest! {
+        #[test]
+        fn it_matches_the_hull_by_definition_anywhere(
+            hexes in proptest::collection::hash_set((-5i64..6, -5i64..6), 1..6)
+        ) {
+            let hexes: HashSet<_> = hexes.into_iter().map(|(q, r)| Coordinate::from_axial(q, r)).collect();
+            prop_assert_eq!(convex_hull(&hexes), naive_hull(&hexes));
+        }
+    }
+
+    #[test]
+    fn it_contains_its_input_and_is_idempotent() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let hexes = intersect(center.ring(3), center.cone(Direction::W, 3));
+        let hull = convex_hull(&hexes);
+        assert!(hexes.iter().all(|c| hull.contains(c)));
+        assert_eq!(convex_hull(&hull), hull);
+        for a in hull.iter() {
+            for b in hull.iter() {
+                assert!(a.line_to(*b).iter().all(|c| hull.contains(c)));
+            }
+        }
+    }
+
+    #[test]
+    fn it_walks_around_the_hull_boundary() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let ring: HashSet<_> = center.ring(3).into_iter().collect();
+        let boundary = hull_boundary(&ring);
+        assert_eq!(boundary.len(), ring.len());
+        assert_eq!(boundary.iter().cloned().collect::<HashSet<_>>(), ring);
+        assert_eq!(boundary[0], *ring.iter().min().unwrap());
+        assert_eq!(boundary[1], boundary[0] + Direction::SW.offset());
+        for i in 0..boundary.len() {
+            let next = boundary[(i + 1) % boundary.len()];
+            assert_eq!(boundary[i].distance_to(next), 1);
+        }
+        let crescent = intersect(center.ring(3), center.cone(Direction::W, 3));
+        let hull = convex_hull(&crescent);
+        let border: HashSet<_> = hull.iter().cloned()
+            .filter(|c| c.neighbors().iter().any(|n| !hull.contains(n)))
+            .collect();
+        let boundary = hull_boundary(&crescent);
+        assert_eq!(boundary.len(), border.len());
+        assert_eq!(boundary.into_iter().collect::<HashSet<_>>(), border);
+        let single: HashSet<_> = vec![center].into_iter().collect();
+        assert_eq!(hull_boundary(&single), vec![center]);
+        assert_eq!(hull_boundary(&HashSet::new()), vec![]);
+    }
+
+    /// Twice the signed area of a polygon in pixel space, which is negative
+    /// when it runs counterclockwise on screen.
+    fn signed_area(points: &[(f64, f64)]) -> f64 {
+        (0..points.len())
+            .map(|i| {
+                let (a, b) = (points[i], points[(i + 1) % points.len()]);
+                a.0 * b.1 - b.0 * a.1
+            })
+            .sum()
+    }
+
+    fn internal_adjacencies(region: &HashSet<Coordinate>) -> usize {
+        let inside = region.iter()
+            .flat_map(|c| c.neighbors().to_vec())
+            .filter(|n| region.contains(n))
+            .count();
+        inside / 2
+    }
+
+    #[test]
+    fn it_finds_the_perimeter_edges_of_a_hexagon() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let hexagon: HashSet<_> = center.spiral(2).into_iter().collect();
+        let edges = perimeter_edges(&hexagon);
+        assert_eq!(edges.len(), 30);
+        assert_eq!(edges.len(), 6 * hexagon.len() - 2 * internal_adjacencies(&hexagon));
+        for edge in edges.iter() {
+            let (a, b) = edge.hexes();
+            assert!(hexagon.contains(&a) != hexagon.contains(&b));
+        }
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (0.0, 0.0));
+        let loops = perimeter_loops(&hexagon, &layout);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].len(), 30);
+        assert!(signed_area(&loops[0]) < 0.0);
+    }
+
+    #[test]
+    fn it_separates_the_outer_and_inner_loops_of_a_donut() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let donut = difference(center.spiral(3), center.spiral(1));
+        let edges = perimeter_edges(&donut);
+        assert_eq!(edges.len(), 6 * donut.len() - 2 * internal_adjacencies(&donut));
+        for orientation in [Orientation::Pointy, Orientation::Flat].iter() {
+            let layout = Layout::new(*orientation, (10.0, 10.0), (5.0, 5.0));
+            let loops = perimeter_loops(&donut, &layout);
+            assert_eq!(loops.len(), 2);
+            assert_eq!(loops[0].len(), 42);
+            assert_eq!(loops[1].len(), 18);
+            assert!(signed_area(&loops[0]) < 0.0);
+            assert!(signed_area(&loops[1]) > 0.0);
+            assert_eq!(loops[0].len() + loops[1].len(), edges.len());
+        }
+    }
+
+    /// Whether two edges meet at a corner, i.e. their hexes are three
+    /// mutually adjacent hexes.
+    fn meet(a: Edge, b: Edge) -> bool {
+        let (a0, a1) = a.hexes();
+        let (b0, b1) = b.hexes();
+        let mut hexes = vec![a0, a1, b0, b1];
+        hexes.sort();
+        hexes.dedup();
+        hexes.len() == 3 && hexes.iter().all(|h| hexes.iter().all(|k| h.distance_to(*k) <= 1))
+    }
+
+    #[test]
+    fn it_orders_boundary_edges_around_regions() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let single: HashSet<_> = vec![center].into_iter().collect();
+        let edges = boundary_edges(&single);
+        assert_eq!(edges.len(), 6);
+        assert_eq!(edges.iter().cloned().collect::<HashSet<_>>().len(), 6);
+        let hexagon: HashSet<_> = center.spiral(1).into_iter().collect();
+        let edges = boundary_edges(&hexagon);
+        assert_eq!(edges.len(), 18);
+        let expected: HashSet<_> = perimeter_edges(&hexagon).into_iter().collect();
+        assert_eq!(edges.iter().cloned().collect::<HashSet<_>>(), expected);
+        for i in 0..edges.len() {
+            assert!(meet(edges[i], edges[(i + 1) % edges.len()]));
+        }
+        let donut = difference(center.spiral(3), center.spiral(1));
+        let edges = boundary_edges(&donut);
+        assert_eq!(edges.len(), 42 + 18);
+        assert!(edges[..42].windows(2).chain(edges[42..].windows(2)).all(|w| meet(w[0], w[1])));
+        assert!(!meet(edges[41], edges[42]));
+    }
+
+    #[test]
+    fn it_keeps_diagonal_hexes_in_separate_loops() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let region: HashSet<_> = vec![a, a + Coordinate::at(2, -1, -1).unwrap()].into_iter().collect();
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (0.0, 0.0));
+        let loops = perimeter_loops(&region, &layout);
+        assert_eq!(loops.len(), 2);
+        for corners in loops.iter() {
+            assert_eq!(corners.len(), 6);
+            for i in 0..6 {
+                let (p, q) = (corners[i], corners[(i + 1) % 6]);
+                let length = ((p.0 - q.0).powi(2) + (p.1 - q.1).powi(2)).sqrt();
+                assert!((length - 10.0).abs() < 1e-9);
+            }
+        }
+        assert_eq!(perimeter_loops(&HashSet::new(), &layout), Vec::<Vec<(f64, f64)>>::new());
+    }
+
+    #[test]
+    fn it_splits_a_strip_between_two_seeds() {
+        let strip: Vec<_> = (0..7).map(|x| Coordinate::from_axial(x, 0)).collect();
+        let seeds = [strip[0], strip[6]];
+        let cells = voronoi(strip.clone(), &seeds).unwrap();
+        let owners: Vec<_> = strip.iter().map(|c| cells[c]).collect();
+        assert_eq!(owners, vec![0, 0, 0, 0, 1, 1, 1]);
+        let weighted = voronoi_weighted(strip.clone(), &seeds, |_| Some(1)).unwrap();
+        assert_eq!(weighted, cells);
+        assert_eq!(voronoi(strip.clone(), &[]), Err(HexError::EmptyInput));
+        assert_eq!(voronoi_weighted(strip, &[], |_| Some(1)), Err(HexError::EmptyInput));
+    }
+
+    #[test]
+    fn it_keeps_voronoi_cells_behind_barriers() {
+        let center = Coordinate::new();
+        let hexes = center.spiral(4);
+        let seeds = [Coordinate::from_axial(-3, 0), Coordinate::from_axial(3, 0)];
+        // A wall along x == 1 with no gaps, so the western seed can't reach
+        // past it and the eastern seed claims everything east of it.
+        let cells = voronoi_weighted(hexes.clone(), &seeds, |c| {
+            if c.x() == 1 { None } else { Some(1) }
+        }).unwrap();
+        for hex in hexes.iter() {
+            match hex.x() {
+                x if x < 1 => assert_eq!(cells.get(hex), Some(&0)),
+                1 => assert_eq!(cells.get(hex), None),
+                _ => assert_eq!(cells.get(hex), Some(&1)),
+            }
+        }
+        let straight = voronoi(hexes, &seeds).unwrap();
+        assert_eq!(straight[&Coordinate::from_axial(0, 0)], 0);
+    }
+
+    proptest! {
+        #[test]
+        fn it_matches_brute_force_voronoi(
+            seeds in proptest::collection::vec((-3i64..4, -3i64..4), 1..6),
+        ) {
+            let seeds: Vec<_> = seeds.into_iter().map(|(q, r)| Coordinate::from_axial(q, r)).collect();
+            let hexes = Coordinate::new().spiral(6);
+            let cells = voronoi(hexes.clone(), &seeds).unwrap();
+            let weighted = voronoi_weighted(hexes.clone(), &seeds, |_| Some(1)).unwrap();
+            for hex in hexes.iter() {
+                let nearest = seeds.iter().map(|s| hex.distance_to(*s)).min().unwrap();
+                let expected = seeds.iter().position(|s| hex.distance_to(*s) == nearest).unwrap();
+                prop_assert_eq!(cells[hex], expected);
+                prop_assert_eq!(weighted[hex], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn it_measures_distances_to_a_single_feature() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let feature = center + Coordinate::at(2, -1, -1).unwrap();
+        let distances = distance_transform(center.spiral(4), |c| c == feature);
+        assert_eq!(distances.len(), Coordinate::spiral_size(4));
+        for (c, distance) in distances.iter() {
+            assert_eq!(*distance, Some(c.distance_to(feature) as u32));
+        }
+    }
+
+    #[test]
+    fn it_only_lowers_distances_with_more_features() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let a = center + Coordinate::at(2, -1, -1).unwrap();
+        let b = center + Coordinate::at(-3, 3, 0).unwrap();
+        let one = distance_transform(center.spiral(4), |c| c == a);
+        let two = distance_transform(center.spiral(4), |c| c == a || c == b);
+        for (c, distance) in two.iter() {
+            assert!(distance.unwrap() <= one.get(c).unwrap().unwrap());
+            let nearest = c.distance_to(a).min(c.distance_to(b));
+            assert_eq!(*distance, Some(nearest as u32));
+        }
+        assert_eq!(two.get(b), Some(&Some(0)));
+    }
+
+    #[test]
+    fn it_leaves_hexes_without_features_unmeasured() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let distances = distance_transform(center.spiral(3), |_| false);
+        assert_eq!(distances.len(), Coordinate::spiral_size(3));
+        assert!(distances.iter().all(|(_, distance)| distance.is_none()));
+        // Hexes cut off from the only feature by a gap in the shape are
+        // unreachable too.
+        let islands = center.spiral(1).into_iter().chain(center.ring(3));
+        let distances = distance_transform(islands, |c| c == center);
+        assert_eq!(distances.get(center + Coordinate::at(3, 0, -3).unwrap()), Some(&None));
+        assert_eq!(distances.get(center + Coordinate::at(1, 0, -1).unwrap()), Some(&Some(1)));
+    }
+
+    #[test]
+    fn it_centers_symmetric_shapes_exactly() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for radius in 0..5 {
+            assert_eq!(centroid(center.spiral(radius)), Some(center));
+            assert_eq!(centroid_in_set(center.spiral(radius)), Some(center));
+        }
+        for radius in 1..5 {
+            assert_eq!(centroid(center.ring(radius)), Some(center));
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_center_of_a_crescent_inside_it() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        // The western third of a ring, which curves around its center.
+        let crescent: Vec<_> = center.ring(3).into_iter()
+            .filter(|c| center.cone(Direction::W, 3).contains(c))
+            .collect();
+        assert_eq!(crescent.len(), 7);
+        let outside = centroid(crescent.clone()).unwrap();
+        assert_eq!(outside, center + Coordinate::at(-2, 0, 2).unwrap());
+        assert!(!crescent.contains(&outside));
+        let inside = centroid_in_set(crescent.clone()).unwrap();
+        assert_eq!(inside, center + Coordinate::at(-3, 0, 3).unwrap());
+    }
+
+    #[test]
+    fn it_weights_centers_toward_heavy_hexes() {
+        let a = Coordinate::new();
+        let b = Coordinate::at(6, 0, -6).unwrap();
+        assert_eq!(weighted_centroid(vec![(a, 1.0), (b, 1.0)]), Some(Coordinate::at(3, 0, -3).unwrap()));
+        assert_eq!(weighted_centroid(vec![(a, 2.0), (b, 1.0)]), Some(Coordinate::at(2, 0, -2).unwrap()));
+        assert_eq!(weighted_centroid(vec![(a, 1.0), (b, 5.0)]), Some(Coordinate::at(5, 0, -5).unwrap()));
+    }
+}
@@ -0,0 +1,55 @@
+//! `proptest` strategies for the crate's types, enabled by the `proptest`
+//! feature.
+//!
+//! Generated coordinates always satisfy the zero-sum invariant, and shrink
+//! toward the origin.
+use proptest::arbitrary::Arbitrary;
+use proptest::sample::select;
+use proptest::strategy::{BoxedStrategy, Strategy};
+
+use {Coordinate, Direction};
+
+/// The largest magnitude generated for the free components of a Coordinate.
+///
+/// This keeps the derived component, and sums of a few coordinates, well
+/// clear of overflow.
+const MAX_COMPONENT: i64 = 1 << 40;
+
+impl Arbitrary for Coordinate {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Coordinate>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let component = -MAX_COMPONENT..=MAX_COMPONENT;
+        (component.clone(), component)
+            .prop_map(|(x, y)| Coordinate::at_unchecked(x, y, -x - y))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Direction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Direction>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        select(&Direction::ALL[..]).boxed()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn it_generates_valid_coordinates(coord: Coordinate) {
+            prop_assert!(Coordinate::at(coord.x, coord.y, coord.z).is_ok());
+        }
+
+        #[test]
+        fn it_generates_every_direction(dir: Direction) {
+            prop_assert!(Direction::ALL.contains(&dir));
+        }
+    }
+}
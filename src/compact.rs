@@ -0,0 +1,195 @@
+//! A memory-compact coordinate for large collections.
+use core::convert::TryFrom;
+use core::ops::{Add, Sub};
+
+use {Coordinate, HexError};
+
+
+/// A hex coordinate stored as axial `q`/`r` components in 8 bytes.
+///
+/// `q` corresponds to a `Coordinate`'s `x` and `r` to its `y`; the third cube
+/// component is implied. Converting from a `Coordinate` is checked, since
+/// compact coordinates can only represent components within `i32` range.
+/// Converting back is always lossless.
+///
+/// `neighbors`, `+`, and `-` overflow as `i32` arithmetic does, so they are
+/// only valid while the results stay within range: `neighbors` needs each
+/// component strictly between `i32::MIN` and `i32::MAX`. Near the limits,
+/// `checked_add` and `checked_sub` return `None` instead.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct CompactCoordinate {
+    q: i32,
+    r: i32,
+}
+
+impl CompactCoordinate {
+    /// Create a new CompactCoordinate at the given axial location.
+    pub fn new(q: i32, r: i32) -> Self {
+        CompactCoordinate { q, r }
+    }
+
+    /// Get the six neighbors of a given CompactCoordinate, in the same order
+    /// as `Coordinate::neighbors`.
+    pub fn neighbors(&self) -> [CompactCoordinate; 6] {
+        [
+            CompactCoordinate::new(self.q + 1, self.r),
+            CompactCoordinate::new(self.q + 1, self.r - 1),
+            CompactCoordinate::new(self.q, self.r - 1),
+            CompactCoordinate::new(self.q, self.r + 1),
+            CompactCoordinate::new(self.q - 1, self.r),
+            CompactCoordinate::new(self.q - 1, self.r + 1),
+        ]
+    }
+
+    /// Add two coordinates, returning `None` if either component overflows.
+    pub fn checked_add(self, other: CompactCoordinate) -> Option<CompactCoordinate> {
+        Some(CompactCoordinate::new(self.q.checked_add(other.q)?, self.r.checked_add(other.r)?))
+    }
+
+    /// Subtract two coordinates, returning `None` if either component
+    /// overflows.
+    pub fn checked_sub(self, other: CompactCoordinate) -> Option<CompactCoordinate> {
+        Some(CompactCoordinate::new(self.q.checked_sub(other.q)?, self.r.checked_sub(other.r)?))
+    }
+
+    /// Get the distance between two coordinates, in grid tiles.
+    ///
+    /// This is computed in `i64`, so it cannot overflow.
+    pub fn distance_to(&self, other: CompactCoordinate) -> i64 {
+        let dq = i64::from(self.q) - i64::from(other.q);
+        let dr = i64::from(self.r) - i64::from(other.r);
+        (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+    }
+}
+
+impl Add for CompactCoordinate {
+    type Output = CompactCoordinate;
+
+    fn add(self, other: CompactCoordinate) -> CompactCoordinate {
+        CompactCoordinate::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl Sub for CompactCoordinate {
+    type Output = CompactCoordinate;
+
+    fn sub(self, other: CompactCoordinate) -> CompactCoordinate {
+        CompactCoordinate::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl From<CompactCoordinate> for Coordinate {
+    fn from(compact: CompactCoordinate) -> Coordinate {
+        let q = i64::from(compact.q);
+        let r = i64::from(compact.r);
+        Coordinate::at_unchecked(q, r, -q - r)
+    }
+}
+
+impl TryFrom<Coordinate> for CompactCoordinate {
+    type Error = HexError;
+
+    fn try_from(coord: Coordinate) -> Result<Self, Self::Error> {
+        match (i32::try_from(coord.x), i32::try_from(coord.y)) {
+            (Ok(q), Ok(r)) => Ok(CompactCoordinate::new(q, r)),
+            _ => Err(HexError::OutOfRange),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn it_fits_in_eight_bytes() {
+        assert_eq!(size_of::<CompactCoordinate>(), 8);
+    }
+
+    #[test]
+    fn it_round_trips_through_coordinate() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let compact = CompactCoordinate::try_from(coord).unwrap();
+        assert_eq!(Coordinate::from(compact), coord);
+    }
+
+    #[test]
+    fn it_rejects_coordinates_out_of_range() {
+        let big = i64::from(i32::MAX) + 1;
+        let coord = Coordinate::at(big, -big, 0).unwrap();
+        let expected = Err(HexError::OutOfRange);
+        assert_eq!(CompactCoordinate::try_from(coord), expected);
+    }
+
+    #[test]
+    fn it_matches_coordinate_distances() {
+        let coord_a = Coordinate::at(-3, -1, 4).unwrap();
+        let coord_b = Coordinate::at(2, 7, -9).unwrap();
+        let compact_a = CompactCoordinate::try_from(coord_a).unwrap();
+        let compact_b = CompactCoordinate::try_from(coord_b).unwrap();
+        assert_eq!(compact_a.distance_to(compact_b), coord_a.distance_to(coord_b));
+    }
+
+    #[test]
+    fn it_matches_coordinate_neighbors() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let compact = CompactCoordinate::try_from(coord).unwrap();
+        let neighbors: Vec<Coordinate> = compact.neighbors()
+            .iter().cloned()
+            .map(Coordinate::from)
+            .collect();
+        assert_eq!(neighbors, coord.neighbors());
+    }
+
+    #[test]
+    fn it_supports_arithmetic() {
+        let a = CompactCoordinate::new(-3, -1);
+        let b = CompactCoordinate::new(2, 7);
+        assert_eq!(a + b, CompactCoordinate::new(-1, 6));
+        assert_eq!(a - b, CompactCoordinate::new(-5, -8));
+    }
+
+    #[test]
+    fn it_detects_overflow_at_the_edges_of_its_range() {
+        let step = CompactCoordinate::new(1, -1);
+        let top = CompactCoordinate::new(i32::MAX, 0);
+        let bottom = CompactCoordinate::new(0, i32::MIN);
+        assert_eq!(top.checked_add(step), None);
+        assert_eq!(bottom.checked_add(step), None);
+        assert_eq!(top.checked_sub(step), Some(CompactCoordinate::new(i32::MAX - 1, 1)));
+        assert_eq!(bottom.checked_sub(step), Some(CompactCoordinate::new(-1, i32::MIN + 1)));
+        assert_eq!(CompactCoordinate::new(i32::MIN, 0).checked_sub(step), None);
+        // The neighbors of the hexes just inside the range are all in range,
+        // and match those of the equivalent `Coordinate`s.
+        for &(q, r) in [(i32::MAX - 1, i32::MIN + 1), (i32::MIN + 1, i32::MAX - 1)].iter() {
+            let compact = CompactCoordinate::new(q, r);
+            let neighbors: Vec<Coordinate> = compact.neighbors()
+                .iter().cloned()
+                .map(Coordinate::from)
+                .collect();
+            assert_eq!(neighbors, Coordinate::from(compact).neighbors());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn it_round_trips_arbitrary_coordinates(coord: Coordinate) {
+            let compact = CompactCoordinate::try_from(coord);
+            let fits = i32::try_from(coord.x).is_ok() && i32::try_from(coord.y).is_ok();
+            prop_assert_eq!(compact.is_ok(), fits);
+            if let Ok(compact) = compact {
+                prop_assert_eq!(Coordinate::from(compact), coord);
+            }
+        }
+
+        #[test]
+        fn it_matches_arbitrary_distances(a: (i32, i32), b: (i32, i32)) {
+            let a = CompactCoordinate::new(a.0 / 2, a.1 / 2);
+            let b = CompactCoordinate::new(b.0 / 2, b.1 / 2);
+            let expected = Coordinate::from(a).distance_to(Coordinate::from(b));
+            prop_assert_eq!(a.distance_to(b), expected);
+        }
+    }
+}
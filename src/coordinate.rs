@@ -0,0 +1,1398 @@
+//! Cube coordinates, generic over their integer type.
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::iter;
+use core::ops::{Add, Div, Neg, Sub};
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use {Direction, HexError};
+
+
+/// An integer type that can be used for the components of a `Coordinate`.
+///
+/// This is implemented for the signed primitive integers.
+pub trait Integer:
+    Copy
+    + Debug
+    + Eq
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// The additive identity.
+    const ZERO: Self;
+    /// The multiplicative identity.
+    const ONE: Self;
+    /// The additive inverse of `ONE`.
+    const NEG_ONE: Self;
+
+    /// Get the absolute value.
+    fn abs(self) -> Self;
+
+    /// Add, returning `None` on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Subtract, returning `None` on overflow.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// Add, clamping at the numeric bounds.
+    fn saturating_add(self, other: Self) -> Self;
+
+    /// Subtract, clamping at the numeric bounds.
+    fn saturating_sub(self, other: Self) -> Self;
+
+    /// Negate, clamping at the numeric bounds.
+    fn saturating_neg(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const NEG_ONE: Self = -1;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                fn saturating_add(self, other: Self) -> Self {
+                    <$t>::saturating_add(self, other)
+                }
+
+                fn saturating_sub(self, other: Self) -> Self {
+                    <$t>::saturating_sub(self, other)
+                }
+
+                fn saturating_neg(self) -> Self {
+                    <$t>::saturating_neg(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, i128);
+
+/// Iterate over every integer in `[lo, hi]`, without overflowing at `hi`.
+fn span<T: Integer>(lo: T, hi: T) -> impl Iterator<Item = T> {
+    let first = if lo <= hi { Some(lo) } else { None };
+    iter::successors(first, move |&v| if v < hi { Some(v + T::ONE) } else { None })
+}
+
+
+/// A hex coordinate using a cubic coordinate scheme.
+///
+/// See http://www.redblobgames.com/grids/hexagons/#coordinates for more detail.
+///
+/// All three coordinates must sum to zero.
+///
+/// The components default to `i64`, but any `Integer` can be used to save
+/// space, e.g. `Coordinate<i16>`.
+///
+/// Coordinates are ordered by `y` and then `x`, i.e. by axial `r` and then
+/// `q`. In a pointy-top layout that's reading order: top to bottom, then left
+/// to right. This says nothing about distance; use `cmp_spiral` to order
+/// hexes outward from a center.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct Coordinate<T = i64> {
+    pub(crate) x: T,
+    pub(crate) y: T,
+    pub(crate) z: T,
+}
+
+/// A Coordinate with `i64` components, the default.
+pub type CoordinateI64 = Coordinate<i64>;
+
+/// The Coordinate at 0, 0, 0.
+pub const ORIGIN: Coordinate = Coordinate::at_const(0, 0, 0);
+
+/// The offsets from a hex to each of its six neighbors, in the order returned
+/// by `Coordinate::neighbors`.
+pub const DIRECTIONS: [Coordinate; 6] = Coordinate::<i64>::DIRECTIONS;
+
+/// The offsets from a hex to each of the six hexes diagonal to it, i.e. at
+/// distance two between a pair of neighbors. The diagonal at index `i` lies
+/// counterclockwise of `DIRECTIONS[i]` when viewed in a pointy-top layout.
+pub const DIAGONALS: [Coordinate; 6] = [
+    Coordinate::at_const(2, -1, -1),
+    Coordinate::at_const(1, -2, 1),
+    Coordinate::at_const(-1, -1, 2),
+    Coordinate::at_const(1, 1, -2),
+    Coordinate::at_const(-2, 1, 1),
+    Coordinate::at_const(-1, 2, -1),
+];
+
+/// One of the three cube axes, named for the component that a reflection
+/// across it keeps.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Coordinate {
+    /// Create a new Coordinate at the specified location in a `const`
+    /// context.
+    ///
+    /// This panics if the location is invalid, which in a `const` item is a
+    /// compile-time error. Use `at` to handle invalid locations at runtime.
+    pub const fn at_const(x: i64, y: i64, z: i64) -> Self {
+        assert!(x + y + z == 0, "Invalid cubic coordinates");
+        Coordinate { x, y, z }
+    }
+
+    /// Get the distance between two hexes given as axial `(q, r)` pairs,
+    /// computed directly from the axial components.
+    ///
+    /// This always agrees with converting both with `from_axial` and using
+    /// `distance_to`, and is meant for checking axial conversions.
+    pub fn axial_distance(a: (i64, i64), b: (i64, i64)) -> i64 {
+        let (dq, dr) = (a.0 - b.0, a.1 - b.1);
+        (dq.abs() + (dq + dr).abs() + dr.abs()) / 2
+    }
+
+    /// Get the number of hexes in a `ring` of `radius`, without enumerating
+    /// them: `6 * radius`, except that a ring of radius 0 has one hex and a
+    /// negative radius none.
+    pub fn ring_size(radius: i64) -> usize {
+        match radius {
+            r if r < 0 => 0,
+            0 => 1,
+            r => 6 * r as usize,
+        }
+    }
+
+    /// Get the number of hexes in a `spiral` of `radius`, without
+    /// enumerating them: `1 + 3 * radius * (radius + 1)`, or none for a
+    /// negative radius.
+    pub fn spiral_size(radius: i64) -> usize {
+        if radius < 0 {
+            return 0;
+        }
+        let r = radius as usize;
+        1 + 3 * r * (r + 1)
+    }
+
+    /// Get this hex's position in the `spiral` around `center`, counting
+    /// from 0 at `center` itself.
+    ///
+    /// This is computed directly rather than by walking the spiral, and
+    /// `from_spiral_index` undoes it. Indexes overflow for hexes more than
+    /// about 1.7 billion steps from `center`.
+    pub fn to_spiral_index(&self, center: Coordinate) -> u64 {
+        let (k, side, along) = self.spiral_key(center);
+        if k == 0 {
+            return 0;
+        }
+        let k = k as u64;
+        3 * k * (k - 1) + 1 + u64::from(side) * k + along as u64
+    }
+
+    /// Get the hex at position `index` in the `spiral` around `center`.
+    ///
+    /// This is computed directly, by finding the ring that holds `index` and
+    /// then the side of the ring, and undoes `to_spiral_index`.
+    pub fn from_spiral_index(center: Coordinate, index: u64) -> Coordinate {
+        if index == 0 {
+            return center;
+        }
+        // Ring `k` starts at index `3k(k - 1) + 1`, so `k` is the greatest
+        // with `(6k - 3)^2 <= 12(index - 1) + 9`.
+        let k = ((12 * u128::from(index - 1) + 9).isqrt() as u64 + 3) / 6;
+        let offset = index - (3 * k * (k - 1) + 1);
+        let (side, along) = ((offset / k) as usize, (offset % k) as i64);
+        let k = k as i64;
+        let corner = Direction::ALL[side].offset();
+        let step = Direction::ALL[(side + 2) % 6].offset();
+        let (x, y) = (corner.x * k + step.x * along, corner.y * k + step.y * along);
+        center + Coordinate::from_axial(x, y)
+    }
+
+    /// Get the hex on the `ring` of `radius` around `center` that is closest
+    /// to `toward`, or `None` if `radius` is negative.
+    ///
+    /// Several hexes on the ring can be the same `distance_to` `toward`, in
+    /// which case the one nearest in a straight line is chosen, i.e. with
+    /// the least sum of squared differences in cube components. Any
+    /// remaining tie goes to the hex that comes first in `ring` order.
+    pub fn closest_on_ring(center: Coordinate, radius: i64, toward: Coordinate) -> Option<Coordinate> {
+        let squared = |c: Coordinate| {
+            let d = c - toward;
+            [d.x, d.y, d.z].iter().map(|&v| i128::from(v) * i128::from(v)).sum::<i128>()
+        };
+        center.iter_ring(radius)
+            .enumerate()
+            .min_by_key(|&(i, c)| (c.distance_to(toward), squared(c), i))
+            .map(|(_, c)| c)
+    }
+}
+
+impl<T: Integer> Coordinate<T> {
+    const DIRECTIONS: [Coordinate<T>; 6] = [
+        Coordinate { x: T::ONE, y: T::ZERO, z: T::NEG_ONE },
+        Coordinate { x: T::ONE, y: T::NEG_ONE, z: T::ZERO },
+        Coordinate { x: T::ZERO, y: T::NEG_ONE, z: T::ONE },
+        Coordinate { x: T::ZERO, y: T::ONE, z: T::NEG_ONE },
+        Coordinate { x: T::NEG_ONE, y: T::ZERO, z: T::ONE },
+        Coordinate { x: T::NEG_ONE, y: T::ONE, z: T::ZERO },
+    ];
+
+    /// Create a new Coordinate at 0, 0, 0.
+    pub const fn new() -> Self {
+        Coordinate { x: T::ZERO, y: T::ZERO, z: T::ZERO }
+    }
+
+    /// Get the x component.
+    pub const fn x(&self) -> T {
+        self.x
+    }
+
+    /// Get the y component.
+    pub const fn y(&self) -> T {
+        self.y
+    }
+
+    /// Get the z component.
+    pub const fn z(&self) -> T {
+        self.z
+    }
+
+    /// Create a new Coordinate from axial coordinates, where `q` is the x
+    /// component and `r` is the y component.
+    ///
+    /// Every pair is valid, so this always succeeds.
+    pub fn from_axial(q: T, r: T) -> Self {
+        Coordinate { x: q, y: r, z: -q - r }
+    }
+
+    /// Create a new Coordinate from its `x` and `y` components, with `z`
+    /// chosen so that they sum to zero. This is the same as `from_axial`.
+    pub fn from_xy(x: T, y: T) -> Self {
+        Coordinate { x, y, z: -x - y }
+    }
+
+    /// Create a new Coordinate from its `x` and `z` components, with `y`
+    /// chosen so that they sum to zero.
+    pub fn from_xz(x: T, z: T) -> Self {
+        Coordinate { x, y: -x - z, z }
+    }
+
+    /// Create a new Coordinate from its `y` and `z` components, with `x`
+    /// chosen so that they sum to zero.
+    pub fn from_yz(y: T, z: T) -> Self {
+        Coordinate { x: -y - z, y, z }
+    }
+
+    /// Convert to axial `(q, r)` coordinates; see `from_axial`.
+    pub fn to_axial(&self) -> (T, T) {
+        (self.x, self.y)
+    }
+
+    /// Create a new Coordinate at the specified location, if that location is
+    /// valid.
+    pub fn at(x: T, y: T, z: T) -> Result<Self, HexError> {
+        if x + y + z == T::ZERO {
+            Ok(Coordinate { x, y, z })
+        } else {
+            Err(HexError::InvalidCoordinate)
+        }
+    }
+
+    /// Create a new Coordinate at the specified location without checking
+    /// that it is valid.
+    ///
+    /// This is a fast path for code that already knows its components sum to
+    /// zero. The invariant is only checked in debug builds; passing invalid
+    /// components produces a Coordinate that breaks every other method.
+    pub fn at_unchecked(x: T, y: T, z: T) -> Self {
+        debug_assert!(x + y + z == T::ZERO, "Invalid cubic coordinates");
+        Coordinate { x, y, z }
+    }
+
+    /// Get the six neighbors of a given Coordinate.
+    pub fn neighbors(&self) -> [Coordinate<T>; 6] {
+        let mut neighbors = Self::DIRECTIONS;
+        for neighbor in neighbors.iter_mut() {
+            *neighbor = *self + *neighbor;
+        }
+        neighbors
+    }
+
+    /// Iterate lazily over the same hexes as `neighbors`, in the same order.
+    pub fn iter_neighbors(&self) -> impl Iterator<Item = Coordinate<T>> {
+        let center = *self;
+        (0..6).map(move |i| center + Self::DIRECTIONS[i])
+    }
+
+    /// Get the neighbors that are `passable`, in the order of `neighbors`.
+    #[cfg(feature = "alloc")]
+    pub fn passable_neighbors<F: Fn(Coordinate<T>) -> bool>(&self, passable: F) -> Vec<Coordinate<T>> {
+        self.iter_neighbors().filter(|c| passable(*c)).collect()
+    }
+
+    /// Get the hexes adjacent to both this hex and `other`, in the order of
+    /// `neighbors`.
+    ///
+    /// Adjacent hexes share two neighbors. Hexes two apart share one if they
+    /// are in a straight line and two otherwise, and hexes further apart
+    /// share none. A hex shares all six of its neighbors with itself.
+    #[cfg(feature = "alloc")]
+    pub fn shared_neighbors(&self, other: Coordinate<T>) -> Vec<Coordinate<T>> {
+        self.iter_neighbors()
+            .filter(|c| other.iter_neighbors().any(|n| n == *c))
+            .collect()
+    }
+
+    /// Get the neighbor in `direction`. Valid directions are in [0, 5].
+    pub fn neighbor(&self, direction: usize) -> Result<Coordinate<T>, HexError> {
+        match self.neighbors().get(direction) {
+            Some(val) => Ok(*val),
+            None => Err(HexError::InvalidDirection),
+        }
+    }
+
+    /// Follow `path` from this Coordinate one step at a time, returning the
+    /// Coordinate it ends at.
+    pub fn walk(&self, path: &[Direction]) -> Coordinate<T> {
+        path.iter().fold(*self, |c, dir| c + Self::DIRECTIONS[*dir as usize])
+    }
+
+    /// Follow `path` like `walk`, returning every Coordinate visited. This
+    /// starts with this Coordinate, so it has one more entry than `path`.
+    #[cfg(feature = "alloc")]
+    pub fn walk_collect(&self, path: &[Direction]) -> Vec<Coordinate<T>> {
+        let mut visited = Vec::with_capacity(path.len() + 1);
+        visited.push(*self);
+        for dir in path {
+            let next = visited[visited.len() - 1] + Self::DIRECTIONS[*dir as usize];
+            visited.push(next);
+        }
+        visited
+    }
+
+    /// Get the neighbor one step closer to `target`, or this Coordinate if
+    /// it is the target.
+    ///
+    /// When several neighbors are equally close, the first in
+    /// `Direction::ALL` order wins, i.e. the first counterclockwise from
+    /// east, so following these steps always takes the same path.
+    pub fn step_toward(&self, target: Coordinate<T>) -> Coordinate<T> {
+        self.step_toward_avoiding(target, |_| false).unwrap_or(*self)
+    }
+
+    /// Get the neighbor one step closer to `target` for which `is_blocked`
+    /// is false, breaking ties as for `step_toward`, or this Coordinate if
+    /// it is the target.
+    ///
+    /// This never steps sideways or away from the target, so it returns
+    /// `None` when every neighbor that is closer is blocked.
+    pub fn step_toward_avoiding<F>(&self, target: Coordinate<T>, is_blocked: F) -> Option<Coordinate<T>>
+        where F: Fn(Coordinate<T>) -> bool
+    {
+        if *self == target {
+            return Some(*self);
+        }
+        let distance = self.distance_to(target);
+        Direction::ALL.iter()
+            .map(|dir| *self + Self::DIRECTIONS[*dir as usize])
+            .find(|c| c.distance_to(target) < distance && !is_blocked(*c))
+    }
+
+    /// Get the distance between two coordinates, in grid tiles.
+    pub fn distance_to(&self, other: Coordinate<T>) -> T {
+        (
+            (self.x - other.x).abs()
+            + (self.y - other.y).abs()
+            + (self.z - other.z).abs()
+        ) / (T::ONE + T::ONE)
+    }
+
+    /// Reflect this Coordinate through `center`, i.e. rotate it 180 degrees
+    /// around `center`, giving `center + (center - self)`.
+    ///
+    /// The result is the same distance from `center` as this Coordinate.
+    pub fn reflect_across(&self, center: Coordinate<T>) -> Coordinate<T> {
+        center + (center - *self)
+    }
+
+    /// Rotate this Coordinate 60 degrees clockwise about the origin, as
+    /// viewed in a pointy-top layout.
+    pub fn rotate_right(&self) -> Coordinate<T> {
+        Coordinate { x: -self.y, y: -self.z, z: -self.x }
+    }
+
+    /// Rotate this Coordinate 60 degrees counterclockwise about the origin,
+    /// as viewed in a pointy-top layout.
+    pub fn rotate_left(&self) -> Coordinate<T> {
+        Coordinate { x: -self.z, y: -self.x, z: -self.y }
+    }
+
+    /// Rotate this Coordinate about the origin by `steps` sixths of a turn,
+    /// clockwise as for `rotate_right`. Negative `steps` rotate
+    /// counterclockwise, and any multiple of 6 leaves it unchanged.
+    pub fn rotate(&self, steps: i64) -> Coordinate<T> {
+        (0..steps.rem_euclid(6)).fold(*self, |c, _| c.rotate_right())
+    }
+
+    /// Reflect this Coordinate across the line through the origin where
+    /// `y == z`, keeping `x` and swapping the other two components.
+    pub fn reflect_x(&self) -> Coordinate<T> {
+        Coordinate { x: self.x, y: self.z, z: self.y }
+    }
+
+    /// Reflect this Coordinate across the line through the origin where
+    /// `x == z`, keeping `y` and swapping the other two components.
+    pub fn reflect_y(&self) -> Coordinate<T> {
+        Coordinate { x: self.z, y: self.y, z: self.x }
+    }
+
+    /// Reflect this Coordinate across the line through the origin where
+    /// `x == y`, keeping `z` and swapping the other two components.
+    pub fn reflect_z(&self) -> Coordinate<T> {
+        Coordinate { x: self.y, y: self.x, z: self.z }
+    }
+
+    /// Reflect this Coordinate across `axis`, with `reflect_x`, `reflect_y`,
+    /// or `reflect_z`.
+    pub fn reflect(&self, axis: Axis) -> Coordinate<T> {
+        match axis {
+            Axis::X => self.reflect_x(),
+            Axis::Y => self.reflect_y(),
+            Axis::Z => self.reflect_z(),
+        }
+    }
+
+    /// Get the hexes within `range` of this Coordinate that lie in the
+    /// 120-degree cone facing `dir`.
+    ///
+    /// The cone is bounded by the rays through the two directions adjacent to
+    /// `dir` (`dir.clockwise()` and `dir.counter_clockwise()`), and hexes on
+    /// those rays are included. This Coordinate itself is not.
+    #[cfg(feature = "alloc")]
+    pub fn cone(&self, dir: Direction, range: T) -> Vec<Coordinate<T>> {
+        self.iter_cone(dir, range).collect()
+    }
+
+    /// Iterate over the same hexes as `cone`, without allocating.
+    pub fn iter_cone(&self, dir: Direction, range: T) -> impl Iterator<Item = Coordinate<T>> {
+        let center = *self;
+        let facing = dir.offset();
+        // A component is on the facing side if it shares the sign of the
+        // facing direction's component, or that component is zero.
+        let facing_side = |d: T, sign: i64| match sign {
+            1 => d >= T::ZERO,
+            -1 => d <= T::ZERO,
+            _ => true,
+        };
+        span(-range, range)
+            .flat_map(move |dx| {
+                let dys = span((-range).max(-dx - range), range.min(-dx + range));
+                dys.map(move |dy| (dx, dy))
+            })
+            .filter_map(move |(dx, dy)| {
+                let dz = -dx - dy;
+                let inside = facing_side(dx, facing.x)
+                    && facing_side(dy, facing.y)
+                    && facing_side(dz, facing.z);
+                if inside && (dx, dy) != (T::ZERO, T::ZERO) {
+                    Some(Coordinate::at_unchecked(
+                        center.x + dx,
+                        center.y + dy,
+                        center.z + dz,
+                    ))
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Get the hexes at exactly `radius` from this Coordinate.
+    ///
+    /// The ring starts `radius` steps east of this Coordinate and runs
+    /// counterclockwise, as viewed in a pointy-top layout. A ring of radius 0
+    /// is just this Coordinate, and a negative radius gives an empty ring.
+    #[cfg(feature = "alloc")]
+    pub fn ring(&self, radius: T) -> Vec<Coordinate<T>> {
+        self.iter_ring(radius).collect()
+    }
+
+    /// Get every hex whose `distance_to` this Coordinate is exactly `n`.
+    ///
+    /// This is the same as `ring(n)`: empty for negative `n`, and just this
+    /// Coordinate for `n == 0`.
+    #[cfg(feature = "alloc")]
+    pub fn neighbors_at_distance(&self, n: T) -> Vec<Coordinate<T>> {
+        self.ring(n)
+    }
+
+    /// Iterate over the same hexes as `ring`, without allocating.
+    pub fn iter_ring(&self, radius: T) -> impl Iterator<Item = Coordinate<T>> {
+        let center = *self;
+        // Each state is a hex, the side of the ring it's on, and how many
+        // hexes of that side have been visited including it.
+        let first = match radius.cmp(&T::ZERO) {
+            Ordering::Less => None,
+            Ordering::Equal => Some((center, 6, T::ONE)),
+            Ordering::Greater => {
+                let corner = Coordinate { x: center.x + radius, y: center.y, z: center.z - radius };
+                Some((corner, 0, T::ONE))
+            }
+        };
+        iter::successors(first, move |&(c, side, visited)| {
+            if side == 6 {
+                return None;
+            }
+            let next = c + Self::DIRECTIONS[Direction::ALL[(side + 2) % 6] as usize];
+            if visited < radius {
+                Some((next, side, visited + T::ONE))
+            } else if side < 5 {
+                Some((next, side + 1, T::ONE))
+            } else {
+                None
+            }
+        }).map(|(c, _, _)| c)
+    }
+
+    /// Get the hexes within `radius` of this Coordinate, ring by ring.
+    ///
+    /// This Coordinate comes first, followed by each ring in increasing
+    /// radius, each in the order given by `ring`.
+    #[cfg(feature = "alloc")]
+    pub fn spiral(&self, radius: T) -> Vec<Coordinate<T>> {
+        self.iter_spiral(radius).collect()
+    }
+
+    /// Iterate over the same hexes as `spiral`, without allocating.
+    pub fn iter_spiral(&self, radius: T) -> impl Iterator<Item = Coordinate<T>> {
+        let center = *self;
+        span(T::ZERO, radius).flat_map(move |k| center.iter_ring(k))
+    }
+
+    /// Get the hexes from `inner` to `outer` steps from this Coordinate,
+    /// inclusive, ring by ring as for `spiral`.
+    ///
+    /// An `inner` of 0 or less gives the whole `spiral(outer)`, and an
+    /// `inner` beyond `outer` gives no hexes.
+    #[cfg(feature = "alloc")]
+    pub fn annulus(&self, inner: T, outer: T) -> Vec<Coordinate<T>> {
+        let center = *self;
+        let inner = if inner < T::ZERO { T::ZERO } else { inner };
+        span(inner, outer).flat_map(|k| center.iter_ring(k)).collect()
+    }
+
+    /// Get a comparator that orders hexes the way `spiral` visits them
+    /// around `center`: by distance from `center`, then counterclockwise
+    /// starting from due east.
+    ///
+    /// ```
+    /// # use hexgrid::Coordinate;
+    /// let center = Coordinate::at(-3, -1, 4).unwrap();
+    /// let spiral: Vec<_> = center.iter_spiral(2).collect();
+    /// let mut hexes = spiral.clone();
+    /// hexes.reverse();
+    /// hexes.sort_by(Coordinate::cmp_spiral(center));
+    /// assert_eq!(hexes, spiral);
+    /// ```
+    pub fn cmp_spiral(center: Coordinate<T>) -> impl Fn(&Coordinate<T>, &Coordinate<T>) -> Ordering {
+        move |a, b| a.spiral_key(center).cmp(&b.spiral_key(center))
+    }
+
+    /// Get the distance from `center`, the side of the ring this Coordinate
+    /// is on, and its position along that side, as visited by `iter_ring`.
+    pub(crate) fn spiral_key(&self, center: Coordinate<T>) -> (T, u8, T) {
+        let d = *self - center;
+        let k = center.distance_to(*self);
+        if d.x == k && d.y > -k {
+            (k, 0, -d.y)
+        } else if d.y == -k && d.z < k {
+            (k, 1, d.z)
+        } else if d.z == k && d.x > -k {
+            (k, 2, -d.x)
+        } else if d.x == -k && d.y < k {
+            (k, 3, d.y)
+        } else if d.y == k && d.z > -k {
+            (k, 4, -d.z)
+        } else {
+            (k, 5, d.x)
+        }
+    }
+}
+
+impl<T: Integer> Coordinate<T> {
+    /// Add two coordinates, returning `None` if any component overflows.
+    pub fn checked_add(self, other: Coordinate<T>) -> Option<Coordinate<T>> {
+        Some(Coordinate {
+            x: self.x.checked_add(other.x)?,
+            y: self.y.checked_add(other.y)?,
+            z: self.z.checked_add(other.z)?,
+        })
+    }
+
+    /// Subtract two coordinates, returning `None` if any component overflows.
+    pub fn checked_sub(self, other: Coordinate<T>) -> Option<Coordinate<T>> {
+        Some(Coordinate {
+            x: self.x.checked_sub(other.x)?,
+            y: self.y.checked_sub(other.y)?,
+            z: self.z.checked_sub(other.z)?,
+        })
+    }
+
+    /// Add two coordinates, clamping components that overflow.
+    ///
+    /// See `saturated` for how the result is kept valid.
+    pub fn saturating_add(self, other: Coordinate<T>) -> Coordinate<T> {
+        Coordinate::saturated(
+            self.x.saturating_add(other.x),
+            self.y.saturating_add(other.y),
+            self.z.saturating_add(other.z),
+        )
+    }
+
+    /// Subtract two coordinates, clamping components that overflow.
+    ///
+    /// See `saturated` for how the result is kept valid.
+    pub fn saturating_sub(self, other: Coordinate<T>) -> Coordinate<T> {
+        Coordinate::saturated(
+            self.x.saturating_sub(other.x),
+            self.y.saturating_sub(other.y),
+            self.z.saturating_sub(other.z),
+        )
+    }
+
+    /// Build a valid Coordinate from independently clamped components.
+    ///
+    /// Clamping can break the invariant that the components sum to zero, so
+    /// the component nearest zero (which can never have overflowed) is
+    /// recomputed from the other two.
+    fn saturated(x: T, y: T, z: T) -> Coordinate<T> {
+        let magnitude = |v: T| v.max(v.saturating_neg());
+        let derive = |a: T, b: T| a.saturating_add(b).saturating_neg();
+        if magnitude(x) <= magnitude(y) && magnitude(x) <= magnitude(z) {
+            Coordinate { x: derive(y, z), y, z }
+        } else if magnitude(y) <= magnitude(z) {
+            Coordinate { x, y: derive(x, z), z }
+        } else {
+            Coordinate { x, y, z: derive(x, y) }
+        }
+    }
+}
+
+impl<T: Integer> Default for Coordinate<T> {
+    /// The Coordinate at 0, 0, 0.
+    fn default() -> Self {
+        Coordinate::new()
+    }
+}
+
+impl<T: Integer> Ord for Coordinate<T> {
+    fn cmp(&self, other: &Coordinate<T>) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl<T: Integer> PartialOrd for Coordinate<T> {
+    fn partial_cmp(&self, other: &Coordinate<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Integer> Add for Coordinate<T> {
+    type Output = Coordinate<T>;
+
+    fn add(self, other: Coordinate<T>) -> Coordinate<T> {
+        Coordinate {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
+impl<T: Integer> Sub for Coordinate<T> {
+    type Output = Coordinate<T>;
+
+    fn sub(self, other: Coordinate<T>) -> Coordinate<T> {
+        Coordinate {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        }
+    }
+}
+
+impl<T: Integer> Add<&Coordinate<T>> for &Coordinate<T> {
+    type Output = Coordinate<T>;
+
+    fn add(self, other: &Coordinate<T>) -> Coordinate<T> {
+        *self + *other
+    }
+}
+
+impl<T: Integer> Sub<&Coordinate<T>> for &Coordinate<T> {
+    type Output = Coordinate<T>;
+
+    fn sub(self, other: &Coordinate<T>) -> Coordinate<T> {
+        *self - *other
+    }
+}
+
+/// Coordinates convert to `[x, y, z]` arrays.
+impl<T: Integer> From<Coordinate<T>> for [T; 3] {
+    fn from(coord: Coordinate<T>) -> [T; 3] {
+        [coord.x, coord.y, coord.z]
+    }
+}
+
+/// Arrays convert back from `[x, y, z]`, failing as for `Coordinate::at` if
+/// the components don't sum to zero.
+impl<T: Integer> TryFrom<[T; 3]> for Coordinate<T> {
+    type Error = HexError;
+
+    fn try_from(array: [T; 3]) -> Result<Self, Self::Error> {
+        Coordinate::at(array[0], array[1], array[2])
+    }
+}
+
+/// Coordinates serialize as an `(x, y, z)` tuple.
+#[cfg(feature = "serde")]
+impl<T: Integer + Serialize> Serialize for Coordinate<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.x, self.y, self.z).serialize(serializer)
+    }
+}
+
+/// Coordinates deserialize from an `(x, y, z)` tuple, which must be a valid
+/// location.
+#[cfg(feature = "serde")]
+impl<'de, T: Integer + Deserialize<'de>> Deserialize<'de> for Coordinate<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (x, y, z) = <(T, T, T)>::deserialize(deserializer)?;
+        Coordinate::at(x, y, z).map_err(de::Error::custom)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::{Just, Strategy};
+
+    #[test]
+    fn it_makes_a_new_one() {
+        let coord: Coordinate = Coordinate::new();
+        assert_eq!(coord.x, 0);
+        assert_eq!(coord.y, 0);
+        assert_eq!(coord.z, 0);
+    }
+
+    #[test]
+    fn it_defaults_to_the_origin() {
+        assert_eq!(Coordinate::default(), ORIGIN);
+        assert_eq!(Coordinate::<i16>::default(), Coordinate::new());
+    }
+
+    #[test]
+    fn it_accepts_args_in_constructor() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(coord.x, -3);
+        assert_eq!(coord.y, -1);
+        assert_eq!(coord.z, 4);
+    }
+
+    const SPAWN: Coordinate = Coordinate::at_const(3, -1, -2);
+
+    #[test]
+    fn it_constructs_coordinates_at_compile_time() {
+        const X: i64 = SPAWN.x();
+        assert_eq!(X, 3);
+        assert_eq!(SPAWN, Coordinate::at(3, -1, -2).unwrap());
+        assert_eq!(ORIGIN, Coordinate::new());
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_panics_on_invalid_const_construction_at_runtime() {
+        Coordinate::at_const(3, 1, 4);
+    }
+
+    #[test]
+    fn it_uses_the_directions_table_for_neighbors() {
+        let neighbors = SPAWN.neighbors();
+        for (neighbor, direction) in neighbors.iter().zip(DIRECTIONS.iter()) {
+            assert_eq!(*neighbor, SPAWN + *direction);
+        }
+    }
+
+    #[test]
+    fn it_places_diagonals_between_neighbors() {
+        for dir in Direction::ALL.iter() {
+            let diagonal = DIAGONALS[*dir as usize];
+            assert_eq!(ORIGIN.distance_to(diagonal), 2);
+            let expected = dir.offset() + dir.counter_clockwise().offset();
+            assert_eq!(diagonal, expected);
+        }
+    }
+
+    #[test]
+    fn it_converts_to_and_from_axial_coordinates() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(coord.to_axial(), (-3, -1));
+        assert_eq!(Coordinate::from_axial(-3, -1), coord);
+    }
+
+    #[test]
+    fn it_converts_to_and_from_arrays() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let array: [i64; 3] = coord.into();
+        assert_eq!(array, [-3, -1, 4]);
+        assert_eq!(Coordinate::try_from(array), Ok(coord));
+        assert_eq!(Coordinate::try_from([1i64, 1, 1]), Err(HexError::InvalidCoordinate));
+        let small: Coordinate<i8> = Coordinate::try_from([2i8, -5, 3]).unwrap();
+        assert_eq!(<[i8; 3]>::from(small), [2, -5, 3]);
+    }
+
+    #[test]
+    fn it_infers_the_third_component() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(Coordinate::from_xy(-3, -1), coord);
+        assert_eq!(Coordinate::from_xz(-3, 4), coord);
+        assert_eq!(Coordinate::from_yz(-1, 4), coord);
+        assert_eq!(Coordinate::from_xy(5, 2).z(), -7);
+        assert_eq!(Coordinate::from_xz(5, 2).y(), -7);
+        assert_eq!(Coordinate::from_yz(5, 2).x(), -7);
+    }
+
+    #[test]
+    fn it_rejects_invalid_cube_coordinates() {
+        let coord = Coordinate::at(3, 1, 4);
+        assert!(coord.is_err());
+    }
+
+    #[test]
+    fn it_matches_checked_construction_when_unchecked() {
+        let coord = Coordinate::at_unchecked(-3, -1, 4);
+        assert_eq!(coord, Coordinate::at(-3, -1, 4).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn it_asserts_validity_when_unchecked_in_debug() {
+        Coordinate::at_unchecked(3, 1, 4);
+    }
+
+    #[test]
+    fn it_generates_a_list_of_neighbors() {
+        let coord: Coordinate = Coordinate::new();
+        let expected = vec![
+            Coordinate::at(1, 0, -1).unwrap(),
+            Coordinate::at(1, -1, 0).unwrap(),
+            Coordinate::at(0, -1, 1).unwrap(),
+            Coordinate::at(0, 1, -1).unwrap(),
+            Coordinate::at(-1, 0, 1).unwrap(),
+            Coordinate::at(-1, 1, 0).unwrap(),
+        ];
+        assert_eq!(expected, coord.neighbors());
+    }
+
+    #[test]
+    fn it_iterates_over_neighbors_lazily() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let neighbors: Vec<_> = coord.iter_neighbors().collect();
+        assert_eq!(neighbors, coord.neighbors().to_vec());
+        let east = coord.iter_neighbors().find(|c| c.x() > coord.x() && c.y() == coord.y());
+        assert_eq!(east, Some(coord + Direction::E.offset()));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_filters_neighbors_by_passability() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let blocked = [Direction::E, Direction::NW, Direction::SW];
+        let walls: Vec<_> = blocked.iter().map(|dir| coord + dir.offset()).collect();
+        let open = coord.passable_neighbors(|c| !walls.contains(&c));
+        let expected: Vec<_> = [Direction::NE, Direction::W, Direction::SE].iter()
+            .map(|dir| coord + dir.offset())
+            .collect();
+        assert_eq!(open.len(), 3);
+        assert!(expected.iter().all(|c| open.contains(c)));
+        assert_eq!(coord.passable_neighbors(|_| true), coord.neighbors().to_vec());
+        assert_eq!(coord.passable_neighbors(|_| false), vec![]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_finds_shared_neighbors() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let east = coord + Direction::E.offset();
+        let shared = coord.shared_neighbors(east);
+        assert_eq!(shared.len(), 2);
+        assert!(shared.iter().all(|c| c.distance_to(coord) == 1 && c.distance_to(east) == 1));
+        let across = east + Direction::E.offset();
+        assert_eq!(coord.shared_neighbors(across), vec![east]);
+        let between = east + Direction::NE.offset();
+        assert_eq!(coord.shared_neighbors(between).len(), 2);
+        assert_eq!(coord.shared_neighbors(across + Direction::E.offset()), vec![]);
+        assert_eq!(coord.shared_neighbors(coord), coord.neighbors().to_vec());
+    }
+
+    #[test]
+    fn it_walks_a_path_of_directions() {
+        let origin: Coordinate = Coordinate::new();
+        let path = [Direction::E, Direction::E, Direction::W];
+        assert_eq!(origin.walk(&path), Direction::E.offset());
+        assert_eq!(origin.walk(&[]), origin);
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(coord.walk(&[Direction::NE, Direction::SW]), coord);
+    }
+
+    #[test]
+    fn it_steps_straight_to_targets() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        for target in start.iter_spiral(6) {
+            let mut c = start;
+            for steps in 1..start.distance_to(target) + 1 {
+                let next = c.step_toward(target);
+                assert_eq!(next.distance_to(c), 1);
+                assert_eq!(next.distance_to(target), start.distance_to(target) - steps);
+                c = next;
+            }
+            assert_eq!(c, target);
+            assert_eq!(c.step_toward(target), target);
+        }
+    }
+
+    #[test]
+    fn it_breaks_step_ties_counterclockwise_from_east() {
+        let origin: Coordinate = Coordinate::new();
+        let between = |a: Direction, b: Direction| a.offset() + b.offset();
+        assert_eq!(origin.step_toward(between(Direction::E, Direction::NE)), Direction::E.offset());
+        assert_eq!(origin.step_toward(between(Direction::W, Direction::SW)), Direction::W.offset());
+        assert_eq!(origin.step_toward(between(Direction::SE, Direction::E)), Direction::E.offset());
+        let target = between(Direction::E, Direction::NE);
+        let detour = origin.step_toward_avoiding(target, |c| c == Direction::E.offset());
+        assert_eq!(detour, Some(Direction::NE.offset()));
+    }
+
+    #[test]
+    fn it_gets_stuck_in_dead_ends() {
+        // A wall of three hexes east of the origin, curving around it.
+        let origin: Coordinate = Coordinate::new();
+        let wall = [Direction::NE.offset(), Direction::E.offset(), Direction::SE.offset()];
+        let blocked = |c: Coordinate| wall.contains(&c);
+        let target = Coordinate::at(4, 0, -4).unwrap();
+        assert_eq!(origin.step_toward_avoiding(target, blocked), None);
+        assert_eq!(origin.step_toward(target), Direction::E.offset());
+        let above = Coordinate::at(2, -2, 0).unwrap();
+        let around = above.step_toward_avoiding(target, blocked);
+        assert_eq!(around, Some(above + Direction::E.offset()));
+        assert_eq!(target.step_toward_avoiding(target, |_| true), Some(target));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_collects_every_hex_walked() {
+        let origin: Coordinate = Coordinate::new();
+        let path = [Direction::E, Direction::E, Direction::W];
+        let east = Direction::E.offset();
+        assert_eq!(origin.walk_collect(&path), vec![origin, east, east + east, east]);
+        assert_eq!(origin.walk_collect(&[]), vec![origin]);
+    }
+
+    #[test]
+    fn it_calcuates_distances() {
+        let coord_a = Coordinate::at(-3, -1, 4).unwrap();
+        let coord_b = Coordinate::at(2, 7, -9).unwrap();
+        assert_eq!(coord_a.distance_to(coord_b), 13);
+    }
+
+    #[test]
+    fn it_reflects_through_a_center() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let center = Coordinate::at(2, 7, -9).unwrap();
+        assert_eq!(coord.reflect_across(Coordinate::new()), Coordinate::at(3, 1, -4).unwrap());
+        assert_eq!(coord.reflect_across(center), Coordinate::at(7, 15, -22).unwrap());
+        assert_eq!(center.reflect_across(center), center);
+    }
+
+    #[test]
+    fn it_matches_axial_and_cube_distances() {
+        let pairs = [((0, 0), (0, 0)), ((-3, -1), (2, 7)), ((5, -5), (-5, 5)), ((1, 2), (3, -4))];
+        for &(a, b) in pairs.iter() {
+            let (hex_a, hex_b) = (Coordinate::from_axial(a.0, a.1), Coordinate::from_axial(b.0, b.1));
+            assert_eq!(Coordinate::axial_distance(a, b), hex_a.distance_to(hex_b));
+        }
+        assert_eq!(Coordinate::axial_distance((-3, -1), (2, 7)), 13);
+    }
+
+    #[test]
+    fn it_rotates_by_any_number_of_steps() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(coord.rotate(1), coord.rotate_right());
+        assert_eq!(coord.rotate(-1), coord.rotate_left());
+        assert_eq!(coord.rotate(0), coord);
+        assert_eq!(coord.rotate(6), coord);
+        assert_eq!(coord.rotate(-12), coord);
+        assert_eq!(coord.rotate(7), coord.rotate(1));
+        assert_eq!(coord.rotate(3), Coordinate::new() - coord);
+        assert_eq!(coord.rotate(i64::MIN), coord.rotate(i64::MIN.rem_euclid(6)));
+    }
+
+    #[test]
+    fn it_rotates_about_the_origin() {
+        for (i, dir) in Direction::ALL.iter().enumerate() {
+            let ccw = Direction::ALL[(i + 1) % 6];
+            assert_eq!(dir.offset().rotate_left(), ccw.offset());
+            assert_eq!(ccw.offset().rotate_right(), dir.offset());
+        }
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let mut rotated = coord;
+        for _ in 0..6 {
+            rotated = rotated.rotate_right();
+            assert_eq!(rotated.distance_to(Coordinate::new()), 4);
+        }
+        assert_eq!(rotated, coord);
+    }
+
+    #[test]
+    fn it_reflects_across_axes() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(coord.reflect_x(), Coordinate::at(-3, 4, -1).unwrap());
+        assert_eq!(coord.reflect_y(), Coordinate::at(4, -1, -3).unwrap());
+        assert_eq!(coord.reflect_z(), Coordinate::at(-1, -3, 4).unwrap());
+        for axis in [Axis::X, Axis::Y, Axis::Z].iter() {
+            assert_eq!(coord.reflect(*axis).reflect(*axis), coord);
+        }
+        // Hexes along the row through the origin keep `y` and swap sides.
+        assert_eq!(Direction::E.offset().reflect_y(), Direction::W.offset());
+    }
+
+    #[test]
+    fn it_supports_addition() {
+        let coord_a = Coordinate::at(-3, -1, 4).unwrap();
+        let coord_b = Coordinate::at(2, 7, -9).unwrap();
+        let expected = Coordinate::at(-1, 6, -5).unwrap();
+        assert_eq!(coord_a + coord_b, expected);
+    }
+
+    #[test]
+    fn it_supports_subtraction() {
+        let coord_a = Coordinate::at(-3, -1, 4).unwrap();
+        let coord_b = Coordinate::at(2, 7, -9).unwrap();
+        let expected = Coordinate::at(-5, -8, 13).unwrap();
+        assert_eq!(coord_a - coord_b, expected);
+    }
+
+    #[test]
+    fn it_supports_arithmetic_on_references() {
+        let coords = [Coordinate::at(-3, -1, 4).unwrap(), Coordinate::at(2, 7, -9).unwrap()];
+        let (a, b) = (&coords[0], &coords[1]);
+        assert_eq!(a + b, coords[0] + coords[1]);
+        assert_eq!(a - b, coords[0] - coords[1]);
+        let total = coords.iter().fold(Coordinate::new(), |sum, c| &sum + c);
+        assert_eq!(total, Coordinate::at(-1, 6, -5).unwrap());
+        assert_eq!(b - a, Coordinate::at(5, 8, -13).unwrap());
+    }
+
+    #[test]
+    fn it_detects_overflow_in_checked_arithmetic() {
+        let big = Coordinate::at(i64::MAX - 5, 0, -(i64::MAX - 5)).unwrap();
+        let step = Coordinate::at(10, -5, -5).unwrap();
+        assert_eq!(big.checked_add(step), None);
+        let negative = Coordinate::at(-big.x, 0, big.x).unwrap();
+        assert_eq!(negative.checked_sub(step), None);
+        assert_eq!(step.checked_add(step), Some(step + step));
+        assert_eq!(step.checked_sub(step), Some(step - step));
+    }
+
+    #[test]
+    fn it_clamps_in_saturating_arithmetic() {
+        let big = Coordinate::at(i64::MAX - 5, 0, -(i64::MAX - 5)).unwrap();
+        let step = Coordinate::at(10, -5, -5).unwrap();
+        let expected = Coordinate::at(i64::MAX, 0, -i64::MAX).unwrap();
+        assert_eq!(big.saturating_add(step), expected);
+        let negative = Coordinate::at(-big.x, 0, big.x).unwrap();
+        let expected = Coordinate::at(i64::MIN, 1, i64::MAX).unwrap();
+        assert_eq!(negative.saturating_sub(step), expected);
+        assert_eq!(step.saturating_add(step), step + step);
+    }
+
+    #[test]
+    fn it_produces_neighbors_given_a_direction() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let expected = Ok(Coordinate::at(-2, -1, 3).unwrap());
+        assert_eq!(coord.neighbor(0), expected);
+    }
+
+    #[test]
+    fn it_produces_no_neighbors_given_a_bad_direction() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let expected = Err(HexError::InvalidDirection);
+        assert_eq!(coord.neighbor(6), expected);
+    }
+
+    #[test]
+    fn it_supports_narrower_integer_types() {
+        let coord_a: Coordinate<i32> = Coordinate::at(-3, -1, 4).unwrap();
+        let coord_b: Coordinate<i32> = Coordinate::at(2, 7, -9).unwrap();
+        assert_eq!(coord_a.distance_to(coord_b), 13i32);
+        assert_eq!(coord_a.neighbor(0), Ok(Coordinate::at(-2, -1, 3).unwrap()));
+        assert_eq!(coord_a.iter_cone(Direction::E, 2).count(), 3 + 5);
+    }
+
+    #[test]
+    fn it_supports_i64_through_the_alias() {
+        let coord_a: CoordinateI64 = Coordinate::at(-3, -1, 4).unwrap();
+        let coord_b: CoordinateI64 = Coordinate::at(2, 7, -9).unwrap();
+        assert_eq!(coord_a.distance_to(coord_b), 13i64);
+        assert_eq!(coord_a.neighbor(0), Ok(Coordinate::at(-2, -1, 3).unwrap()));
+        assert_eq!(coord_a.iter_cone(Direction::E, 2).count(), 3 + 5);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_produces_a_cone_of_adjacent_neighbors() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let cone = coord.cone(Direction::E, 1);
+        assert_eq!(cone.len(), 3);
+        for dir in [Direction::E, Direction::NE, Direction::SE].iter() {
+            assert!(cone.contains(&(coord + dir.offset())));
+        }
+    }
+
+    #[test]
+    fn it_keeps_cones_within_range() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        for dir in Direction::ALL.iter() {
+            assert_eq!(coord.iter_cone(*dir, 3).count(), 3 + 5 + 7);
+            for hex in coord.iter_cone(*dir, 3) {
+                let distance = coord.distance_to(hex);
+                assert!((1..=3).contains(&distance));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_walks_rings_counterclockwise_from_east() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let offsets: Vec<_> = center.ring(2).into_iter().map(|c| (c - center).to_axial()).collect();
+        let expected = vec![
+            (2, 0), (2, -1), (2, -2), (1, -2), (0, -2), (-1, -1),
+            (-2, 0), (-2, 1), (-2, 2), (-1, 2), (0, 2), (1, 1),
+        ];
+        assert_eq!(offsets, expected);
+        assert_eq!(center.ring(0), vec![center]);
+        assert!(center.ring(-1).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_finds_neighbors_at_a_distance() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let mut adjacent = coord.neighbors_at_distance(1);
+        let mut neighbors = coord.neighbors().to_vec();
+        adjacent.sort();
+        neighbors.sort();
+        assert_eq!(adjacent, neighbors);
+        for n in 0..5 {
+            let hexes = coord.neighbors_at_distance(n);
+            assert_eq!(hexes.len(), if n == 0 { 1 } else { 6 * n as usize });
+            assert!(hexes.iter().all(|hex| coord.distance_to(*hex) == n));
+        }
+        assert_eq!(coord.neighbors_at_distance(0), vec![coord]);
+        assert!(coord.neighbors_at_distance(-2).is_empty());
+    }
+
+    #[test]
+    fn it_spirals_outward_ring_by_ring() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let distances: Vec<_> = center.iter_spiral(2).map(|c| center.distance_to(c)).collect();
+        let mut expected = vec![0];
+        expected.extend([1; 6].iter());
+        expected.extend([2; 12].iter());
+        assert_eq!(distances, expected);
+        assert_eq!(center.iter_spiral(-1).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_cuts_the_middle_out_of_spirals() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let donut = center.annulus(1, 2);
+        assert_eq!(donut.len(), 18);
+        assert!(!donut.contains(&center));
+        assert!(center.ring(1).iter().chain(center.ring(2).iter()).all(|c| donut.contains(c)));
+        assert!(donut.iter().all(|c| (1..3).contains(&center.distance_to(*c))));
+        assert_eq!(center.annulus(0, 3), center.spiral(3));
+        assert_eq!(center.annulus(-2, 1), center.spiral(1));
+        assert_eq!(center.annulus(4, 4), center.ring(4));
+        assert!(center.annulus(3, 2).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_counts_rings_and_spirals_without_enumerating() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for radius in -1..5 {
+            assert_eq!(Coordinate::ring_size(radius), center.ring(radius).len());
+            assert_eq!(Coordinate::spiral_size(radius), center.spiral(radius).len());
+        }
+    }
+
+    #[test]
+    fn it_snaps_to_the_closest_hex_on_a_ring() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let scaled = |d: Coordinate, n: i64| Coordinate::from_axial(d.x * n, d.y * n);
+        for dir in Direction::ALL.iter() {
+            let far = center + scaled(dir.offset(), 100);
+            let expected = center + scaled(dir.offset(), 3);
+            assert_eq!(Coordinate::closest_on_ring(center, 3, far), Some(expected));
+        }
+        // Three ring hexes are 2 steps from `inside`, and the two beside the
+        // eastern corner are nearer than it in a straight line.
+        let inside = center + Coordinate::at(1, 0, -1).unwrap();
+        let expected = center + Coordinate::at(3, -1, -2).unwrap();
+        assert_eq!(Coordinate::closest_on_ring(center, 3, inside), Some(expected));
+        assert_eq!(Coordinate::closest_on_ring(center, 0, inside), Some(center));
+        assert_eq!(Coordinate::closest_on_ring(center, -1, inside), None);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_breaks_ring_snapping_ties_deterministically() {
+        // Every hex on the ring is 2 steps from the center, and the six in
+        // the middle of each side are nearest in a straight line.
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let ring = center.ring(2);
+        assert_eq!(Coordinate::closest_on_ring(center, 2, center), Some(ring[1]));
+        assert_eq!(ring[1] - center, Coordinate::at(2, -1, -1).unwrap());
+    }
+
+    #[test]
+    fn it_orders_by_row_then_column() {
+        let origin: Coordinate = Coordinate::new();
+        let mut hexes = origin.neighbors();
+        hexes.sort();
+        let expected = [(0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+        for (hex, axial) in hexes.iter().zip(expected.iter()) {
+            assert_eq!(hex.to_axial(), *axial);
+        }
+        assert!(Coordinate::at(5, -1, -4).unwrap() < Coordinate::at(-5, 0, 5).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_numbers_hexes_in_spiral_order() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for (i, c) in center.iter_spiral(20).enumerate() {
+            assert_eq!(c.to_spiral_index(center), i as u64);
+            assert_eq!(Coordinate::from_spiral_index(center, i as u64), c);
+        }
+        assert_eq!(center.to_spiral_index(center), 0);
+        assert_eq!(Coordinate::from_spiral_index(center, 0), center);
+        // The last hex of each ring is followed by the first of the next.
+        for radius in 1..50 {
+            let first = Coordinate::spiral_size(radius - 1) as u64;
+            let last = Coordinate::spiral_size(radius) as u64 - 1;
+            let ring = center.ring(radius);
+            assert_eq!(Coordinate::from_spiral_index(center, first), ring[0]);
+            assert_eq!(Coordinate::from_spiral_index(center, last), ring[ring.len() - 1]);
+            assert_eq!(ring[ring.len() - 1].to_spiral_index(center), last);
+        }
+    }
+
+    #[test]
+    fn it_round_trips_spiral_indexes() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for index in 0..100_000 {
+            let c = Coordinate::from_spiral_index(center, index);
+            assert_eq!(c.to_spiral_index(center), index);
+        }
+        let far = Coordinate::at(1_000_000, -3, -999_997).unwrap();
+        assert_eq!(Coordinate::from_spiral_index(center, far.to_spiral_index(center)), far);
+    }
+
+    #[test]
+    fn it_sorts_shuffled_spirals_back_into_spiral_order() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let spiral: Vec<_> = center.iter_spiral(3).collect();
+        let mut shuffled = spiral.clone();
+        shuffled.reverse();
+        shuffled.swap(0, 20);
+        shuffled.sort();
+        assert_ne!(shuffled, spiral);
+        shuffled.sort_by(Coordinate::cmp_spiral(center));
+        assert_eq!(shuffled, spiral);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn it_serializes_as_a_tuple() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let json = ::serde_json::to_string(&coord).unwrap();
+        assert_eq!(json, "[-3,-1,4]");
+        assert_eq!(::serde_json::from_str::<Coordinate>(&json).unwrap(), coord);
+        assert!(::serde_json::from_str::<Coordinate>("[3,1,4]").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn it_sorts_spirals_of_any_order(
+            mut shuffled in Just(ORIGIN.iter_spiral(3).collect::<Vec<_>>()).prop_shuffle()
+        ) {
+            shuffled.sort_by(Coordinate::cmp_spiral(ORIGIN));
+            prop_assert_eq!(shuffled, ORIGIN.iter_spiral(3).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn it_undoes_reflections_and_keeps_distances(coord: Coordinate, center: Coordinate) {
+            let reflected = coord.reflect_across(center);
+            prop_assert_eq!(reflected.reflect_across(center), coord);
+            prop_assert_eq!(reflected.distance_to(center), coord.distance_to(center));
+            prop_assert_eq!(coord.reflect_across(Coordinate::new()), Coordinate::new() - coord);
+        }
+
+        #[test]
+        fn it_has_symmetric_distances(a: Coordinate, b: Coordinate) {
+            prop_assert_eq!(a.distance_to(b), b.distance_to(a));
+        }
+
+        #[test]
+        fn it_satisfies_the_triangle_inequality(a: Coordinate, b: Coordinate, c: Coordinate) {
+            prop_assert!(a.distance_to(c) <= a.distance_to(b) + b.distance_to(c));
+        }
+
+        #[test]
+        fn it_inverts_addition_with_subtraction(a: Coordinate, b: Coordinate) {
+            prop_assert_eq!(a + b - b, a);
+        }
+
+        #[test]
+        fn it_surrounds_every_hex_with_adjacent_neighbors(coord: Coordinate) {
+            for neighbor in coord.neighbors().iter() {
+                prop_assert_eq!(coord.distance_to(*neighbor), 1);
+            }
+        }
+    }
+}
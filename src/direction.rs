@@ -0,0 +1,120 @@
+//! The six directions of a hex grid.
+use coordinate::DIRECTIONS;
+use Coordinate;
+
+
+/// One of the six directions from a hex to its neighbors.
+///
+/// Directions are named for a pointy-top layout, and are declared in
+/// counterclockwise order starting from east. The discriminant of each
+/// direction is its index into `Coordinate::neighbors`, so
+/// `coord.neighbor(dir as usize)` is always valid.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub enum Direction {
+    E = 0,
+    NE = 1,
+    NW = 2,
+    W = 4,
+    SW = 5,
+    SE = 3,
+}
+
+impl Direction {
+    /// All six directions, in counterclockwise order starting from east.
+    pub const ALL: [Direction; 6] = [
+        Direction::E,
+        Direction::NE,
+        Direction::NW,
+        Direction::W,
+        Direction::SW,
+        Direction::SE,
+    ];
+
+    /// Get the unit offset for this direction, i.e. the neighbor of the
+    /// origin in this direction.
+    pub fn offset(self) -> Coordinate {
+        DIRECTIONS[self as usize]
+    }
+
+    /// Get the direction that points the opposite way from this one.
+    pub fn opposite(self) -> Direction {
+        self.clockwise().clockwise().clockwise()
+    }
+
+    /// Get the direction whose offset is `offset`, if there is one.
+    pub fn from_offset(offset: Coordinate) -> Option<Direction> {
+        Direction::ALL.iter().cloned().find(|dir| dir.offset() == offset)
+    }
+
+    /// Get the next direction clockwise from this one.
+    pub fn clockwise(self) -> Direction {
+        match self {
+            Direction::E => Direction::SE,
+            Direction::SE => Direction::SW,
+            Direction::SW => Direction::W,
+            Direction::W => Direction::NW,
+            Direction::NW => Direction::NE,
+            Direction::NE => Direction::E,
+        }
+    }
+
+    /// Get the next direction counterclockwise from this one.
+    pub fn counter_clockwise(self) -> Direction {
+        match self {
+            Direction::E => Direction::NE,
+            Direction::NE => Direction::NW,
+            Direction::NW => Direction::W,
+            Direction::W => Direction::SW,
+            Direction::SW => Direction::SE,
+            Direction::SE => Direction::E,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_matches_neighbor_indices() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        for dir in Direction::ALL.iter() {
+            assert_eq!(coord.neighbor(*dir as usize), Ok(coord + dir.offset()));
+        }
+    }
+
+    #[test]
+    fn it_finds_directions_from_offsets() {
+        for dir in Direction::ALL.iter() {
+            assert_eq!(Direction::from_offset(dir.offset()), Some(*dir));
+            assert_eq!(dir.opposite().offset() + dir.offset(), Coordinate::new());
+        }
+        assert_eq!(Direction::from_offset(Coordinate::new()), None);
+    }
+
+    #[test]
+    fn it_rotates_through_all_directions() {
+        for (i, dir) in Direction::ALL.iter().enumerate() {
+            assert_eq!(dir.counter_clockwise(), Direction::ALL[(i + 1) % 6]);
+            assert_eq!(dir.clockwise(), Direction::ALL[(i + 5) % 6]);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn it_undoes_rotations(dir: Direction) {
+            prop_assert_eq!(dir.clockwise().counter_clockwise(), dir);
+            prop_assert_eq!(dir.counter_clockwise().clockwise(), dir);
+        }
+
+        #[test]
+        fn it_returns_after_six_rotations(dir: Direction) {
+            let mut rotated = dir;
+            for _ in 0..6 {
+                rotated = rotated.clockwise();
+            }
+            prop_assert_eq!(rotated, dir);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! The boundaries between adjacent hexes.
+use {Coordinate, Direction};
+
+
+/// The edge shared by two adjacent hexes.
+///
+/// An Edge is stored as one of its hexes plus the direction to the other,
+/// normalized so that the direction is always `E`, `NE`, or `NW`. The same
+/// pair of hexes therefore always produces the same Edge, whichever order
+/// they are given in.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct Edge {
+    hex: Coordinate,
+    dir: Direction,
+}
+
+impl Edge {
+    /// Create the Edge between two hexes, if they are adjacent.
+    pub fn new(a: Coordinate, b: Coordinate) -> Option<Edge> {
+        Direction::from_offset(b - a).map(|dir| Edge::from_direction(a, dir))
+    }
+
+    /// Create the Edge between a hex and its neighbor in `dir`.
+    pub fn from_direction(hex: Coordinate, dir: Direction) -> Edge {
+        match dir {
+            Direction::E | Direction::NE | Direction::NW => Edge { hex, dir },
+            _ => Edge { hex: hex + dir.offset(), dir: dir.opposite() },
+        }
+    }
+
+    /// Get the two hexes on either side of this Edge.
+    pub fn hexes(&self) -> (Coordinate, Coordinate) {
+        (self.hex, self.hex + self.dir.offset())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn it_ignores_the_order_of_its_hexes() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        for neighbor in a.neighbors().iter() {
+            assert_eq!(Edge::new(a, *neighbor), Edge::new(*neighbor, a));
+            assert!(Edge::new(a, *neighbor).is_some());
+        }
+    }
+
+    #[test]
+    fn it_rejects_hexes_that_arent_adjacent() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(-1, -1, 2).unwrap();
+        assert_eq!(Edge::new(a, b), None);
+        assert_eq!(Edge::new(a, a), None);
+    }
+
+    #[test]
+    fn it_returns_its_hexes() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = a + Direction::SW.offset();
+        let (first, second) = Edge::new(a, b).unwrap().hexes();
+        assert!((first, second) == (a, b) || (first, second) == (b, a));
+    }
+
+    #[test]
+    fn it_hashes_consistently() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = a + Direction::W.offset();
+        let mut rivers = HashMap::new();
+        rivers.insert(Edge::new(a, b).unwrap(), "river");
+        assert_eq!(rivers.get(&Edge::new(b, a).unwrap()), Some(&"river"));
+        assert_eq!(rivers.get(&Edge::from_direction(b, Direction::E)), Some(&"river"));
+    }
+}
@@ -0,0 +1,50 @@
+//! The errors returned by fallible operations on hexes.
+use core::fmt::{self, Display};
+
+use Coordinate;
+
+
+/// An error from one of this crate's fallible operations.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum HexError {
+    /// Cube components that don't sum to zero.
+    InvalidCoordinate,
+    /// A direction index outside `0..6`.
+    InvalidDirection,
+    /// A corner index outside `0..6`.
+    InvalidCorner,
+    /// A coordinate too large for the requested representation.
+    OutOfRange,
+    /// A coordinate outside the shape of a map.
+    OutsideShape,
+    /// Map dimensions that can't be used, and why.
+    InvalidDimensions(&'static str),
+    /// An empty collection where at least one item is needed.
+    EmptyInput,
+    /// A string that isn't a valid hex label.
+    InvalidLabel,
+    /// Maps that don't have values at the same hexes, and the least hex
+    /// that has a value in only one of them.
+    ShapeMismatch(Coordinate),
+}
+
+impl Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HexError::InvalidCoordinate => write!(f, "Invalid cubic coordinates"),
+            HexError::InvalidDirection => write!(f, "Invalid direction"),
+            HexError::InvalidCorner => write!(f, "Invalid corner"),
+            HexError::OutOfRange => write!(f, "Coordinate out of range"),
+            HexError::OutsideShape => write!(f, "Coordinate outside map shape"),
+            HexError::InvalidDimensions(reason) => write!(f, "Invalid map dimensions: {}", reason),
+            HexError::EmptyInput => write!(f, "Empty input"),
+            HexError::InvalidLabel => write!(f, "Invalid hex label"),
+            HexError::ShapeMismatch(c) => {
+                write!(f, "Map shapes differ at ({}, {}, {})", c.x(), c.y(), c.z())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for HexError {}
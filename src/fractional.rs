@@ -0,0 +1,597 @@
+//! Cube coordinates with fractional components, for points between hexes.
+use std::f64::consts::PI;
+
+#[cfg(feature = "glam")]
+use glam::{DVec3, Vec3};
+#[cfg(feature = "mint")]
+use mint::Vector3;
+
+use {Coordinate, Direction, Vertex};
+
+
+/// A point in cube coordinates whose components need not be integers.
+///
+/// Like a `Coordinate`, the three components sum to zero. These come from
+/// converting pixels to hex space with `Layout::pixel_to_hex`, and are turned
+/// back into hexes with `round`.
+#[derive(Debug,PartialEq,Clone,Copy)]
+pub struct FractionalCoordinate {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+impl FractionalCoordinate {
+    /// Create a new FractionalCoordinate from axial coordinates, where `q` is
+    /// the x component and `r` is the y component.
+    pub fn from_axial(q: f64, r: f64) -> Self {
+        FractionalCoordinate { x: q, y: r, z: -q - r }
+    }
+
+    /// Get the x component.
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    /// Get the y component.
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    /// Get the z component.
+    pub fn z(&self) -> f64 {
+        self.z
+    }
+
+    /// Get the distance between two points, in grid tiles.
+    pub fn distance_to(&self, other: FractionalCoordinate) -> f64 {
+        ((self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()) / 2.0
+    }
+
+    /// Get the hex containing this point.
+    ///
+    /// Each component is rounded half away from zero, and then the one that
+    /// moved furthest is recomputed from the other two. Points on the border
+    /// between hexes therefore always resolve the same way: when two
+    /// components moved equally far, `z` is recomputed in preference to `y`,
+    /// and `y` in preference to `x`.
+    pub fn round(&self) -> Coordinate {
+        let (mut x, mut y, mut z) = (self.x.round(), self.y.round(), self.z.round());
+        let dx = (x - self.x).abs();
+        let dy = (y - self.y).abs();
+        let dz = (z - self.z).abs();
+        if dx > dy && dx > dz {
+            x = -y - z;
+        } else if dy > dz {
+            y = -x - z;
+        } else {
+            z = -x - y;
+        }
+        Coordinate::at_unchecked(x as i64, y as i64, z as i64)
+    }
+
+    /// Get the hex corner nearest this point.
+    ///
+    /// Each corner is nearest the points of the triangle joining the centers
+    /// of its three hexes, so this finds the hex containing the point with
+    /// `round`, and then the corner of that hex toward the point. A point on
+    /// the line between two neighboring centers is equally near two corners,
+    /// and resolves to the lower-numbered corner of the hex `round` gives.
+    pub fn round_to_vertex(&self) -> Vertex {
+        let hex = self.round();
+        let dx = self.x - hex.x() as f64;
+        let dy = self.y - hex.y() as f64;
+        let dz = self.z - hex.z() as f64;
+        let mut best = (0, f64::NEG_INFINITY);
+        for i in 0..6 {
+            let corner = Direction::ALL[i].offset() + Direction::ALL[(i + 1) % 6].offset();
+            let toward = dx * corner.x() as f64 + dy * corner.y() as f64 + dz * corner.z() as f64;
+            if toward > best.1 {
+                best = (i, toward);
+            }
+        }
+        Vertex::new(hex, best.0).expect("corners are numbered 0 to 5")
+    }
+}
+
+/// A tiny offset that moves points off the borders between hexes, so that
+/// rounding them is predictable. Its components sum to zero.
+const NUDGE: (f64, f64, f64) = (1e-6, 2e-6, -3e-6);
+
+/// Get the point at fraction `t` of the way from `a` to `b`, moved by
+/// `NUDGE` scaled by `sign`.
+fn lerp(a: Coordinate, b: Coordinate, t: f64, sign: f64) -> FractionalCoordinate {
+    let (a, b) = (FractionalCoordinate::from(a), FractionalCoordinate::from(b));
+    FractionalCoordinate {
+        x: a.x + (b.x - a.x) * t + NUDGE.0 * sign,
+        y: a.y + (b.y - a.y) * t + NUDGE.1 * sign,
+        z: a.z + (b.z - a.z) * t + NUDGE.2 * sign,
+    }
+}
+
+/// Get the points a line from `a` to `b` passes through at each step, with
+/// each point moved by `NUDGE` scaled by `sign`.
+fn line_points(a: Coordinate, b: Coordinate, sign: f64) -> impl Iterator<Item = FractionalCoordinate> {
+    let steps = a.distance_to(b);
+    (0..steps + 1).map(move |i| {
+        let t = if steps == 0 { 0.0 } else { i as f64 / steps as f64 };
+        lerp(a, b, t, sign)
+    })
+}
+
+impl Coordinate {
+    /// Get the hexes on a straight line from this Coordinate to `other`,
+    /// including both ends.
+    ///
+    /// There is one hex per step, so the line has `distance_to(other) + 1`
+    /// hexes and each is adjacent to the next. Where the ideal line runs
+    /// exactly along the border between two hexes, one of them is chosen
+    /// consistently; see `line_to_supercover` to get both.
+    pub fn line_to(&self, other: Coordinate) -> Vec<Coordinate> {
+        self.line_iter(other).collect()
+    }
+
+    /// Iterate over the hexes of `line_to(other)`, computing each only when
+    /// it is reached.
+    ///
+    /// Nothing is allocated, so scanning a line until the first blocked hex
+    /// costs only the steps taken. Iterating from the back gives the same
+    /// hexes in reverse.
+    pub fn line_iter(&self, other: Coordinate) -> impl ExactSizeIterator<Item = Coordinate> + DoubleEndedIterator {
+        let steps = self.distance_to(other);
+        LineIter { from: *self, to: other, steps, front: 0, back: steps + 1 }
+    }
+
+    /// Get every hex that a straight line from this Coordinate to `other`
+    /// touches, including both ends.
+    ///
+    /// This is `line_to`, plus the other hex wherever the ideal line runs
+    /// exactly along the border between two. The line is sampled twice, with
+    /// the sample points moved a tiny distance to either side, and the
+    /// results combined. Hexes are ordered by distance from this Coordinate,
+    /// and then by `Ord`.
+    pub fn line_to_supercover(&self, other: Coordinate) -> Vec<Coordinate> {
+        let mut hexes: Vec<Coordinate> = line_points(*self, other, 1.0)
+            .chain(line_points(*self, other, -1.0))
+            .map(|p| p.round())
+            .collect();
+        hexes.sort_by_key(|hex| (self.distance_to(*hex), *hex));
+        hexes.dedup();
+        hexes
+    }
+
+    /// Get the hex at fraction `t` of the way along a straight line from
+    /// this Coordinate to `other`.
+    ///
+    /// `t` of 0 gives this Coordinate and 1 gives `other`. The point is
+    /// nudged the same way as in `line_to`, so at each step of the line this
+    /// gives the same hex.
+    pub fn lerp_hex(&self, other: Coordinate, t: f64) -> Coordinate {
+        lerp(*self, other, t, 1.0).round()
+    }
+
+    /// Get hexes along a circular arc around `center`, from this Coordinate
+    /// to `other`, sampled at `segments + 1` evenly spaced angles.
+    ///
+    /// The angle turns the shorter way around `center`, while the distance
+    /// from it changes steadily from this Coordinate's to `other`'s, so the
+    /// arc spirals if those differ. A half-turn goes clockwise as drawn in a
+    /// pointy-top layout. Each sample point is rounded to the hex containing
+    /// it, so both ends are included, but consecutive hexes need not be
+    /// adjacent and may repeat. A `segments` of 0 is treated as 1.
+    pub fn arc_to(&self, other: Coordinate, center: Coordinate, segments: usize) -> Vec<Coordinate> {
+        let segments = segments.max(1);
+        let (from, to) = (polar(*self - center), polar(other - center));
+        let mut turn = to.1 - from.1;
+        if turn > PI {
+            turn -= 2.0 * PI;
+        } else if turn <= -PI {
+            turn += 2.0 * PI;
+        }
+        let mut hexes = Vec::with_capacity(segments + 1);
+        hexes.push(*self);
+        for i in 1..segments {
+            let t = i as f64 / segments as f64;
+            let radius = from.0 + (to.0 - from.0) * t;
+            let angle = from.1 + turn * t;
+            let (px, py) = (radius * angle.cos(), radius * angle.sin());
+            let r = py / 1.5;
+            let q = px / 3f64.sqrt() - r / 2.0;
+            let offset = FractionalCoordinate::from_axial(q, r).round();
+            hexes.push(center + offset);
+        }
+        hexes.push(other);
+        hexes
+    }
+
+    /// Get this Coordinate if it is within `radius` of `center`, or else the
+    /// hex `radius` steps along the `line_to` from `center` toward it.
+    ///
+    /// The result is always within `radius` of `center`, with a negative
+    /// `radius` treated as 0.
+    pub fn clamp_to_range(&self, center: Coordinate, radius: i64) -> Coordinate {
+        let steps = center.distance_to(*self);
+        let radius = radius.max(0);
+        if steps <= radius {
+            *self
+        } else {
+            center.lerp_hex(*self, radius as f64 / steps as f64)
+        }
+    }
+}
+
+/// Get the distance and angle of a hex's center from the origin's, as drawn
+/// in a pointy-top layout with hexes of size 1 and its y axis pointing down.
+fn polar(c: Coordinate) -> (f64, f64) {
+    let px = 3f64.sqrt() * (c.x() as f64 + c.y() as f64 / 2.0);
+    let py = 1.5 * c.y() as f64;
+    (px.hypot(py), py.atan2(px))
+}
+
+/// The hexes of a line, as returned by `Coordinate::line_iter`.
+struct LineIter {
+    from: Coordinate,
+    to: Coordinate,
+    steps: i64,
+    /// The next step to yield from the front.
+    front: i64,
+    /// One past the next step to yield from the back.
+    back: i64,
+}
+
+impl LineIter {
+    fn hex(&self, i: i64) -> Coordinate {
+        let t = if self.steps == 0 { 0.0 } else { i as f64 / self.steps as f64 };
+        lerp(self.from, self.to, t, 1.0).round()
+    }
+}
+
+impl Iterator for LineIter {
+    type Item = Coordinate;
+
+    fn next(&mut self) -> Option<Coordinate> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.hex(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for LineIter {}
+
+impl DoubleEndedIterator for LineIter {
+    fn next_back(&mut self) -> Option<Coordinate> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.hex(self.back))
+    }
+}
+
+impl From<Coordinate> for FractionalCoordinate {
+    fn from(c: Coordinate) -> Self {
+        FractionalCoordinate { x: c.x as f64, y: c.y as f64, z: c.z as f64 }
+    }
+}
+
+/// The x, y, and z components, in that order.
+#[cfg(feature = "glam")]
+impl From<FractionalCoordinate> for DVec3 {
+    fn from(c: FractionalCoordinate) -> Self {
+        DVec3::new(c.x, c.y, c.z)
+    }
+}
+
+/// The x, y, and z components, in that order, at `f32` precision.
+#[cfg(feature = "glam")]
+impl From<FractionalCoordinate> for Vec3 {
+    fn from(c: FractionalCoordinate) -> Self {
+        DVec3::from(c).as_vec3()
+    }
+}
+
+/// The point with the vector's x and y components. Its z component is
+/// recomputed from them, so the components sum to zero even if the
+/// vector's don't.
+#[cfg(feature = "glam")]
+impl From<DVec3> for FractionalCoordinate {
+    fn from(v: DVec3) -> Self {
+        FractionalCoordinate::from_axial(v.x, v.y)
+    }
+}
+
+/// The point with the vector's x and y components, as for `DVec3`.
+#[cfg(feature = "glam")]
+impl From<Vec3> for FractionalCoordinate {
+    fn from(v: Vec3) -> Self {
+        FractionalCoordinate::from(v.as_dvec3())
+    }
+}
+
+/// The x, y, and z components, in that order.
+#[cfg(feature = "mint")]
+impl From<FractionalCoordinate> for Vector3<f64> {
+    fn from(c: FractionalCoordinate) -> Self {
+        Vector3 { x: c.x, y: c.y, z: c.z }
+    }
+}
+
+/// The point with the vector's x and y components, recomputing z as for
+/// glam's `DVec3`.
+#[cfg(feature = "mint")]
+impl From<Vector3<f64>> for FractionalCoordinate {
+    fn from(v: Vector3<f64>) -> Self {
+        FractionalCoordinate::from_axial(v.x, v.y)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rounds_to_the_nearest_hex() {
+        let point = FractionalCoordinate::from_axial(-2.8, -1.1);
+        assert_eq!(point.round(), Coordinate::at(-3, -1, 4).unwrap());
+        let point = FractionalCoordinate::from_axial(0.3, 0.1);
+        assert_eq!(point.round(), Coordinate::new());
+    }
+
+    #[test]
+    fn it_round_trips_whole_coordinates() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(FractionalCoordinate::from(coord).round(), coord);
+    }
+
+    #[test]
+    fn it_draws_lines_one_step_at_a_time() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(2, 7, -9).unwrap();
+        let line = a.line_to(b);
+        assert_eq!(line.len() as i64, a.distance_to(b) + 1);
+        assert_eq!(line.first(), Some(&a));
+        assert_eq!(line.last(), Some(&b));
+        for pair in line.windows(2) {
+            assert_eq!(pair[0].distance_to(pair[1]), 1);
+        }
+        assert_eq!(a.line_to(a), vec![a]);
+    }
+
+    #[test]
+    fn it_iterates_lines_from_either_end() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(2, 1, -3).unwrap();
+        let mut line = a.line_iter(b);
+        assert_eq!(line.len(), 8);
+        assert_eq!(line.next(), Some(a));
+        assert_eq!(line.next_back(), Some(b));
+        assert_eq!(line.len(), 6);
+        let middle: Vec<_> = line.collect();
+        assert_eq!(middle, a.line_to(b)[1..7].to_vec());
+        let mut reversed: Vec<_> = a.line_iter(b).rev().collect();
+        reversed.reverse();
+        assert_eq!(reversed, a.line_to(b));
+        assert_eq!(a.line_iter(a).collect::<Vec<_>>(), vec![a]);
+    }
+
+    #[test]
+    fn it_scans_lines_only_as_far_as_needed() {
+        // Far too long to ever compute in full.
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = a + Coordinate::from_axial(1 << 50, -(1 << 49));
+        let mut line = a.line_iter(b);
+        assert_eq!(line.len(), (1usize << 50) + 1);
+        let open: Vec<_> = line.by_ref().take_while(|c| a.distance_to(*c) < 5).collect();
+        assert_eq!(open.len(), 5);
+        assert!(open.windows(2).all(|w| w[0].distance_to(w[1]) == 1));
+        assert_eq!(line.len(), (1usize << 50) - 5);
+        assert_eq!(line.next_back(), Some(b));
+    }
+
+    proptest! {
+        #[test]
+        fn it_iterates_the_same_line_as_line_to(
+            ax in -50i64..50, ay in -50i64..50, bx in -50i64..50, by in -50i64..50
+        ) {
+            let a = Coordinate::from_axial(ax, ay);
+            let b = Coordinate::from_axial(bx, by);
+            let eager: Vec<_> = line_points(a, b, 1.0).map(|p| p.round()).collect();
+            prop_assert_eq!(a.line_iter(b).len(), eager.len());
+            prop_assert_eq!(a.line_iter(b).collect::<Vec<_>>(), eager.clone());
+            let mut reversed: Vec<_> = a.line_iter(b).rev().collect();
+            reversed.reverse();
+            prop_assert_eq!(reversed, eager);
+        }
+    }
+
+    #[test]
+    fn it_covers_both_hexes_along_an_edge() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        // A diagonal runs exactly along the edge between two neighbors.
+        let b = a + Coordinate::at(4, -2, -2).unwrap();
+        let line = a.line_to(b);
+        let supercover = a.line_to_supercover(b);
+        assert_eq!(line.len(), 5);
+        assert_eq!(supercover.len(), 7);
+        assert!(line.iter().all(|hex| supercover.contains(hex)));
+        let distances: Vec<_> = supercover.iter().map(|hex| a.distance_to(*hex)).collect();
+        assert_eq!(distances, vec![0, 1, 1, 2, 3, 3, 4]);
+    }
+
+    #[test]
+    fn it_keeps_the_supercover_near_the_ideal_line() {
+        use {Layout, Orientation};
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (0.0, 0.0));
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        for b in a.spiral(6) {
+            let (ax, ay) = layout.hex_to_pixel(a);
+            let (bx, by) = layout.hex_to_pixel(b);
+            let length = ((bx - ax).powi(2) + (by - ay).powi(2)).sqrt().max(1e-9);
+            for hex in a.line_to_supercover(b) {
+                let (x, y) = layout.hex_to_pixel(hex);
+                // Distance from the hex center to the segment from a to b.
+                let t = (((x - ax) * (bx - ax) + (y - ay) * (by - ay)) / length.powi(2)).clamp(0.0, 1.0);
+                let (px, py) = (ax + t * (bx - ax), ay + t * (by - ay));
+                let distance = ((x - px).powi(2) + (y - py).powi(2)).sqrt();
+                assert!(distance <= 0.5 * 3f64.sqrt() * 10.0 + 1e-6, "{:?} to {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn it_interpolates_between_hexes() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(2, 7, -9).unwrap();
+        assert_eq!(a.lerp_hex(b, 0.0), a);
+        assert_eq!(a.lerp_hex(b, 1.0), b);
+        assert_eq!(a.lerp_hex(a, 0.5), a);
+        let line = a.line_to(b);
+        let steps = line.len() - 1;
+        for (i, hex) in line.iter().enumerate() {
+            assert_eq!(a.lerp_hex(b, i as f64 / steps as f64), *hex);
+        }
+        for i in 0..100 {
+            assert!(line.contains(&a.lerp_hex(b, i as f64 / 99.0)));
+        }
+    }
+
+    #[test]
+    fn it_interpolates_to_the_middle_of_odd_lines() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = a + Coordinate::at(4, -2, -2).unwrap();
+        let line = a.line_to(b);
+        assert_eq!(line.len() % 2, 1);
+        assert_eq!(a.lerp_hex(b, 0.5), line[line.len() / 2]);
+    }
+
+    #[test]
+    fn it_clamps_hexes_to_a_range() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for c in center.spiral(3) {
+            assert_eq!(c.clamp_to_range(center, 3), c);
+        }
+        for c in center.ring(7) {
+            let clamped = c.clamp_to_range(center, 3);
+            assert_eq!(center.distance_to(clamped), 3);
+            assert_eq!(clamped, center.line_to(c)[3]);
+        }
+        let far = Coordinate::at(2, 7, -9).unwrap();
+        assert_eq!(far.clamp_to_range(center, 0), center);
+        assert_eq!(far.clamp_to_range(center, -2), center);
+    }
+
+    #[test]
+    fn it_rounds_corners_to_their_vertex() {
+        let hex = Coordinate::at(-3, -1, 4).unwrap();
+        for i in 0..6 {
+            let vertex = Vertex::new(hex, i).unwrap();
+            let hexes = vertex.hexes();
+            let q = hexes.iter().map(|c| c.x() as f64).sum::<f64>() / 3.0;
+            let r = hexes.iter().map(|c| c.y() as f64).sum::<f64>() / 3.0;
+            assert_eq!(FractionalCoordinate::from_axial(q, r).round_to_vertex(), vertex);
+            for &(jq, jr) in [(0.05, 0.0), (-0.04, 0.03), (0.0, -0.06), (0.02, 0.02)].iter() {
+                let nearby = FractionalCoordinate::from_axial(q + jq, r + jr);
+                assert_eq!(nearby.round_to_vertex(), vertex);
+            }
+        }
+    }
+
+    #[test]
+    fn it_rounds_centers_and_edges_to_a_corner_of_their_hex() {
+        let hex = Coordinate::at(-3, -1, 4).unwrap();
+        let center = FractionalCoordinate::from(hex);
+        assert_eq!(center.round_to_vertex(), Vertex::new(hex, 0).unwrap());
+        // Just inside the hex, on either side of its eastern edge's midpoint.
+        let east = FractionalCoordinate::from_axial(hex.x() as f64 + 0.45, hex.y() as f64);
+        let above = FractionalCoordinate::from_axial(east.x(), east.y() - 0.01);
+        let below = FractionalCoordinate::from_axial(east.x() - 0.01, east.y() + 0.01);
+        assert_eq!(above.round_to_vertex(), Vertex::new(hex, 0).unwrap());
+        assert_eq!(below.round_to_vertex(), Vertex::new(hex, 5).unwrap());
+    }
+
+    #[test]
+    fn it_keeps_the_ends_of_arcs() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let a = center + Coordinate::at(4, -4, 0).unwrap();
+        let b = center + Coordinate::at(-1, 4, -3).unwrap();
+        for segments in 0..20 {
+            let arc = a.arc_to(b, center, segments);
+            assert_eq!(arc.len(), segments.max(1) + 1);
+            assert_eq!(arc[0], a);
+            assert_eq!(arc[arc.len() - 1], b);
+        }
+        assert_eq!(center.arc_to(center, center, 3), vec![center; 4]);
+    }
+
+    #[test]
+    fn it_follows_the_sampled_radius() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let a = center + Coordinate::at(6, -3, -3).unwrap();
+        let b = center + Coordinate::at(-2, -2, 4).unwrap();
+        let (a_radius, b_radius) = (polar(a - center).0, polar(b - center).0);
+        let arc = a.arc_to(b, center, 24);
+        for (i, hex) in arc.iter().enumerate() {
+            let t = i as f64 / 24.0;
+            let radius = a_radius + (b_radius - a_radius) * t;
+            assert!((polar(*hex - center).0 - radius).abs() <= 1.0);
+        }
+        // A straight line between opposite hexes passes through the center,
+        // but the arc keeps its distance.
+        let opposite = center + Coordinate::at(-6, 3, 3).unwrap();
+        let half_turn = a.arc_to(opposite, center, 12);
+        assert!(half_turn.iter().all(|hex| (polar(*hex - center).0 - a_radius).abs() <= 1.0));
+        assert!(half_turn[6].y() > center.y());
+    }
+
+    #[test]
+    fn it_measures_fractional_distances() {
+        let a = FractionalCoordinate::from(Coordinate::at(-3, -1, 4).unwrap());
+        let b = FractionalCoordinate::from(Coordinate::at(2, 7, -9).unwrap());
+        assert_eq!(a.distance_to(b), 13.0);
+        let halfway = FractionalCoordinate::from_axial(0.5, 0.0);
+        assert_eq!(halfway.distance_to(FractionalCoordinate::from_axial(0.0, 0.0)), 0.5);
+    }
+
+    #[test]
+    fn it_breaks_ties_deterministically() {
+        // Exactly halfway between the origin and its eastern neighbor.
+        let point = FractionalCoordinate::from_axial(0.5, 0.0);
+        assert_eq!(point.round(), Coordinate::at(1, 0, -1).unwrap());
+        let point = FractionalCoordinate::from_axial(-0.5, 0.0);
+        assert_eq!(point.round(), Coordinate::at(-1, 0, 1).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "glam")]
+    fn it_converts_to_and_from_glam_vectors() {
+        let point = FractionalCoordinate::from_axial(-2.75, -1.5);
+        let vector = DVec3::from(point);
+        assert_eq!(vector, DVec3::new(-2.75, -1.5, 4.25));
+        assert_eq!(FractionalCoordinate::from(vector), point);
+        assert_eq!(Vec3::from(point), Vec3::new(-2.75, -1.5, 4.25));
+        assert_eq!(FractionalCoordinate::from(Vec3::from(point)), point);
+        let skewed = FractionalCoordinate::from(DVec3::new(-2.75, -1.5, 0.0));
+        assert_eq!(skewed, point);
+    }
+
+    #[test]
+    #[cfg(feature = "mint")]
+    fn it_converts_to_and_from_mint_vectors() {
+        let point = FractionalCoordinate::from_axial(-2.75, -1.5);
+        let vector = Vector3::from(point);
+        assert_eq!(vector, Vector3 { x: -2.75, y: -1.5, z: 4.25 });
+        assert_eq!(FractionalCoordinate::from(vector), point);
+        let skewed = FractionalCoordinate::from(Vector3 { x: -2.75, y: -1.5, z: 0.0 });
+        assert_eq!(skewed, point);
+        let array: [f64; 3] = Vector3::from(point).into();
+        assert_eq!(array, [-2.75, -1.5, 4.25]);
+    }
+}
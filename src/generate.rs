@@ -0,0 +1,153 @@
+//! Random map generation.
+//!
+//! Every function here draws only from the `rng` it is given, in an order
+//! that doesn't depend on hashing, so a seeded generator such as
+//! `rand::rngs::Xoshiro256PlusPlus` always produces the same map.
+use rand::{Rng, RngExt};
+
+use algorithms::Bounds;
+use {Coordinate, HexSet};
+
+
+/// Take `steps` steps from `start`, each to a uniformly random neighbor,
+/// returning every hex visited, starting with `start`.
+///
+/// With `bounds`, a step that would leave them is rolled again, so the walk
+/// stays within them once inside. A walk with no neighbor to step to stays
+/// where it is.
+pub fn random_walk<R: Rng + ?Sized>(rng: &mut R, start: Coordinate, steps: usize, bounds: Option<&Bounds>) -> Vec<Coordinate> {
+    let mut visited = Vec::with_capacity(steps + 1);
+    visited.push(start);
+    let mut at = start;
+    for _ in 0..steps {
+        at = step(rng, at, |c| bounds.is_none_or(|b| b.contains(c))).unwrap_or(at);
+        visited.push(at);
+    }
+    visited
+}
+
+/// Carve caves out of `shape` with a drunkard's walk, returning the carved
+/// hexes once they make up `target_open_fraction` of the shape, rounded up.
+///
+/// The walk starts from a random hex of the shape and carves every hex it
+/// steps on, rolling again any step that would leave the shape. After as
+/// many steps as the shape has hexes it restarts from a random carved hex,
+/// up to `restarts` times, so fewer hexes may be carved if the restarts run
+/// out first. Each hex of `shape` counts once.
+pub fn drunkards_walk<R, I>(rng: &mut R, shape: I, target_open_fraction: f64, restarts: usize) -> HexSet
+    where R: Rng + ?Sized, I: IntoIterator<Item = Coordinate>
+{
+    let mut hexes: Vec<Coordinate> = shape.into_iter().collect();
+    hexes.sort();
+    hexes.dedup();
+    let fraction = target_open_fraction.clamp(0.0, 1.0);
+    let target = (fraction * hexes.len() as f64).ceil() as usize;
+    let mut carved = HexSet::new();
+    if target == 0 {
+        return carved;
+    }
+    // Carved hexes in the order they were carved, to restart from.
+    let mut open = Vec::with_capacity(target);
+    let mut at = hexes[rng.random_range(0..hexes.len())];
+    carved.insert(at);
+    open.push(at);
+    for walk in 0..restarts + 1 {
+        if walk > 0 {
+            at = open[rng.random_range(0..open.len())];
+        }
+        for _ in 0..hexes.len() {
+            if open.len() >= target {
+                return carved;
+            }
+            at = match step(rng, at, |c| hexes.binary_search(&c).is_ok()) {
+                Some(next) => next,
+                None => break,
+            };
+            if carved.insert(at) {
+                open.push(at);
+            }
+        }
+    }
+    carved
+}
+
+/// Pick a uniformly random neighbor of `at` that is `allowed`, or `None` if
+/// there are none.
+fn step<R, F>(rng: &mut R, at: Coordinate, allowed: F) -> Option<Coordinate>
+    where R: Rng + ?Sized, F: Fn(Coordinate) -> bool
+{
+    let neighbors = at.neighbors();
+    let mut choices = [at; 6];
+    let mut count = 0;
+    for n in neighbors.iter().filter(|n| allowed(**n)) {
+        choices[count] = *n;
+        count += 1;
+    }
+    if count == 0 {
+        None
+    } else {
+        Some(choices[rng.random_range(0..count)])
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algorithms::bounds;
+    use rand::rngs::Xoshiro256PlusPlus;
+    use rand::SeedableRng;
+
+    #[test]
+    fn it_walks_one_step_at_a_time() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(7);
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let walk = random_walk(&mut rng, start, 200, None);
+        assert_eq!(walk.len(), 201);
+        assert_eq!(walk[0], start);
+        assert!(walk.windows(2).all(|w| w[0].distance_to(w[1]) == 1));
+        assert_eq!(random_walk(&mut rng, start, 0, None), vec![start]);
+    }
+
+    #[test]
+    fn it_walks_within_bounds() {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(7);
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let bounded = bounds(start.spiral(2)).unwrap();
+        let walk = random_walk(&mut rng, start, 500, Some(&bounded));
+        assert_eq!(walk.len(), 501);
+        assert!(walk.iter().all(|c| start.distance_to(*c) <= 2));
+        assert!(walk.windows(2).all(|w| w[0].distance_to(w[1]) == 1));
+        let single = bounds(Some(start)).unwrap();
+        assert_eq!(random_walk(&mut rng, start, 3, Some(&single)), vec![start; 4]);
+    }
+
+    #[test]
+    fn it_carves_the_target_fraction() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let shape = center.spiral(10);
+        for fraction in [0.0, 0.1, 0.45, 0.8, 1.0].iter() {
+            let mut rng = Xoshiro256PlusPlus::seed_from_u64(11);
+            let carved = drunkards_walk(&mut rng, shape.iter().cloned(), *fraction, 100);
+            let open = carved.len() as f64 / shape.len() as f64;
+            assert!((open - fraction).abs() <= 1.0 / shape.len() as f64);
+            assert!(carved.iter().all(|c| center.distance_to(*c) <= 10));
+        }
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(11);
+        assert!(drunkards_walk(&mut rng, Vec::new(), 0.5, 10).is_empty());
+    }
+
+    #[test]
+    fn it_repeats_itself_under_a_seed() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let walk = |seed| random_walk(&mut Xoshiro256PlusPlus::seed_from_u64(seed), start, 100, None);
+        assert_eq!(walk(3), walk(3));
+        assert_ne!(walk(3), walk(4));
+        let carve = |seed| {
+            let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+            drunkards_walk(&mut rng, start.spiral(8), 0.4, 50)
+        };
+        assert_eq!(carve(3), carve(3));
+        assert_ne!(carve(3), carve(4));
+    }
+}
@@ -0,0 +1,148 @@
+//! GeoJSON export of hex cells, enabled by the `geojson` feature.
+use serde_json::{Map, Value};
+
+use {Coordinate, Layout};
+
+
+/// Render hex cells as a GeoJSON `FeatureCollection`.
+///
+/// Each cell becomes a `Polygon` feature built from the layout's hex corners,
+/// with its value as the feature's properties. Positions are in pixel space;
+/// see `to_geojson_projected` to map them to longitude and latitude.
+pub fn to_geojson<I>(layout: &Layout, cells: I) -> String
+    where I: IntoIterator<Item = (Coordinate, Value)>
+{
+    to_geojson_projected(layout, cells, |point| point)
+}
+
+/// Render hex cells as a GeoJSON `FeatureCollection`, passing every corner
+/// through `project` first.
+///
+/// Rings are closed by repeating their first position, and are wound
+/// counterclockwise after projection, as RFC 7946 requires of exterior rings.
+pub fn to_geojson_projected<I, F>(layout: &Layout, cells: I, project: F) -> String
+    where I: IntoIterator<Item = (Coordinate, Value)>,
+          F: Fn((f64, f64)) -> (f64, f64)
+{
+    let features: Vec<Value> = cells.into_iter()
+        .map(|(coord, properties)| feature(layout, coord, properties, &project))
+        .collect();
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    }).to_string()
+}
+
+fn feature<F>(layout: &Layout, coord: Coordinate, properties: Value, project: &F) -> Value
+    where F: Fn((f64, f64)) -> (f64, f64)
+{
+    let mut ring: Vec<(f64, f64)> = layout.hex_corners(coord)
+        .iter()
+        .map(|corner| project(*corner))
+        .collect();
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+    ring.push(ring[0]);
+    let ring: Vec<Value> = ring.iter().map(|&(x, y)| json!([x, y])).collect();
+    let properties = match properties {
+        Value::Null => Value::Object(Map::new()),
+        other => other,
+    };
+    json!({
+        "type": "Feature",
+        "geometry": {
+            "type": "Polygon",
+            "coordinates": [ring],
+        },
+        "properties": properties,
+    })
+}
+
+/// The shoelace area of an open ring, positive when counterclockwise in a
+/// y-up frame.
+fn signed_area(ring: &[(f64, f64)]) -> f64 {
+    let mut area = 0.0;
+    for (i, a) in ring.iter().enumerate() {
+        let b = ring[(i + 1) % ring.len()];
+        area += a.0 * b.1 - b.0 * a.1;
+    }
+    area / 2.0
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Orientation;
+
+    fn cells() -> Vec<(Coordinate, Value)> {
+        vec![
+            (Coordinate::new(), json!({"terrain": "grass"})),
+            (Coordinate::at(1, 0, -1).unwrap(), json!({"terrain": "water", "depth": 3})),
+        ]
+    }
+
+    fn rings(output: &str) -> Vec<Vec<(f64, f64)>> {
+        let parsed: Value = serde_json::from_str(output).unwrap();
+        parsed["features"].as_array().unwrap().iter().map(|feature| {
+            feature["geometry"]["coordinates"][0].as_array().unwrap().iter().map(|p| {
+                (p[0].as_f64().unwrap(), p[1].as_f64().unwrap())
+            }).collect()
+        }).collect()
+    }
+
+    #[test]
+    fn it_produces_a_polygon_per_cell() {
+        let layout = Layout::new(Orientation::Pointy, (1.0, 1.0), (0.0, 0.0));
+        let output = to_geojson(&layout, cells());
+        let parsed: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed["type"], "FeatureCollection");
+        let features = parsed["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        for feature in features {
+            assert_eq!(feature["geometry"]["type"], "Polygon");
+        }
+    }
+
+    #[test]
+    fn it_closes_rings() {
+        let layout = Layout::new(Orientation::Flat, (1.0, 1.0), (0.0, 0.0));
+        for ring in rings(&to_geojson(&layout, cells())) {
+            assert_eq!(ring.len(), 7);
+            assert_eq!(ring[0], ring[6]);
+        }
+    }
+
+    #[test]
+    fn it_winds_rings_counterclockwise_after_projection() {
+        let layout = Layout::new(Orientation::Pointy, (1.0, 1.0), (0.0, 0.0));
+        let flipped = to_geojson_projected(&layout, cells(), |(x, y)| (x, -y));
+        for output in [to_geojson(&layout, cells()), flipped].iter() {
+            for ring in rings(output) {
+                assert!(signed_area(&ring[..6]) > 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn it_round_trips_properties() {
+        let layout = Layout::new(Orientation::Pointy, (1.0, 1.0), (0.0, 0.0));
+        let parsed: Value = serde_json::from_str(&to_geojson(&layout, cells())).unwrap();
+        let features = parsed["features"].as_array().unwrap();
+        for (feature, (_, properties)) in features.iter().zip(cells()) {
+            assert_eq!(feature["properties"], properties);
+        }
+    }
+
+    #[test]
+    fn it_applies_the_projection() {
+        let layout = Layout::new(Orientation::Pointy, (1.0, 1.0), (0.0, 0.0));
+        let output = to_geojson_projected(&layout, cells(), |(x, y)| (x + 100.0, y + 50.0));
+        for ring in rings(&output) {
+            for &(x, y) in ring.iter() {
+                assert!(x > 90.0 && y > 40.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Exporting hexes and their adjacencies as graphs, e.g. to inspect the
+//! costs a pathfinding function sees.
+use std::fmt::Write;
+
+use {Coordinate, Layout};
+
+
+/// Write `hexes` as an undirected Graphviz DOT graph, with a node for each
+/// hex and an edge between each pair of neighbors that `edge_label` labels.
+///
+/// Nodes are named by their cube coordinates and labeled with `node_label`.
+/// Each is pinned with a `pos` attribute at its center as drawn by `layout`,
+/// with the pixel y axis flipped to point up as DOT expects, so `neato -n`
+/// draws the grid as laid out. `edge_label` is called once for each pair of
+/// neighbors, with the lesser hex first, and the pair is left unconnected
+/// where it returns `None`.
+///
+/// Nodes are written in canonical order and each node's edges after it, so
+/// the output doesn't depend on the order of `hexes`. Repeated hexes are
+/// written once.
+pub fn to_dot<I, E, N>(hexes: I, layout: &Layout, edge_label: E, node_label: N) -> String
+    where I: IntoIterator<Item = Coordinate>,
+          E: Fn(Coordinate, Coordinate) -> Option<String>,
+          N: Fn(Coordinate) -> String
+{
+    let mut hexes: Vec<Coordinate> = hexes.into_iter().collect();
+    hexes.sort();
+    hexes.dedup();
+    let mut dot = String::from("graph hexes {\n");
+    for &hex in hexes.iter() {
+        let (x, y) = layout.hex_to_pixel(hex);
+        let label = quote(&node_label(hex));
+        writeln!(dot, "    {} [label={}, pos=\"{:.2},{:.2}!\"];", name(hex), label, x, 0.0 - y)
+            .expect("writing to a String can't fail");
+    }
+    for &hex in hexes.iter() {
+        for &neighbor in hex.neighbors().iter() {
+            if neighbor <= hex || hexes.binary_search(&neighbor).is_err() {
+                continue;
+            }
+            if let Some(label) = edge_label(hex, neighbor) {
+                writeln!(dot, "    {} -- {} [label={}];", name(hex), name(neighbor), quote(&label))
+                    .expect("writing to a String can't fail");
+            }
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Get the DOT node name of a hex.
+fn name(c: Coordinate) -> String {
+    format!("\"{},{},{}\"", c.x(), c.y(), c.z())
+}
+
+/// Quote a string for DOT, escaping quotes and backslashes.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for ch in s.chars() {
+        if ch == '"' || ch == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    quoted.push('"');
+    quoted
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Orientation;
+
+    fn layout() -> Layout {
+        Layout::new(Orientation::Pointy, (10.0, 10.0), (0.0, 0.0))
+    }
+
+    fn count(dot: &str, pattern: &str) -> usize {
+        dot.lines().filter(|line| line.contains(pattern)).count()
+    }
+
+    #[test]
+    fn it_writes_a_node_per_hex_and_an_edge_per_pair() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let region = center.spiral(1);
+        let dot = to_dot(region.iter().cloned().chain(region.clone()), &layout(),
+                         |a, b| Some(format!("{}", a.distance_to(b))),
+                         |c| format!("{:?}", (c.x(), c.y())));
+        assert!(dot.starts_with("graph hexes {\n") && dot.ends_with("}\n"));
+        assert_eq!(count(&dot, "pos="), 7);
+        // Six spokes and six sides of the ring.
+        assert_eq!(count(&dot, " -- "), 12);
+        assert!(dot.contains("\"-3,-1,4\" [label=\"(-3, -1)\", pos=\"-60.62,15.00!\"];"));
+    }
+
+    #[test]
+    fn it_omits_unlabeled_edges() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let wall = center + Coordinate::at(1, 0, -1).unwrap();
+        let dot = to_dot(center.spiral(1), &layout(),
+                         |a, b| if a == wall || b == wall { None } else { Some("1".to_string()) },
+                         |_| String::new());
+        assert_eq!(count(&dot, "pos="), 7);
+        assert_eq!(count(&dot, " -- "), 9);
+        assert_eq!(count(&dot, "\"-2,-1,3\" --") + count(&dot, "-- \"-2,-1,3\""), 0);
+    }
+
+    #[test]
+    fn it_escapes_labels() {
+        let hex = Coordinate::new();
+        let dot = to_dot(vec![hex], &layout(), |_, _| None, |_| "say \"hi\" \\o/".to_string());
+        assert!(dot.contains("[label=\"say \\\"hi\\\" \\\\o/\", pos=\"0.00,0.00!\"]"));
+    }
+}
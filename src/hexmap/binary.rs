@@ -0,0 +1,382 @@
+//! A compact binary encoding for `HexMap`s.
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, Read, Write};
+
+use Coordinate;
+use super::{hexagon, Cells, Dense, HexMap, MapShape};
+
+
+const MAGIC: &[u8; 4] = b"HEXM";
+const VERSION: u8 = 1;
+const HEXAGON: u8 = 0;
+const SPARSE: u8 = 1;
+
+/// An error reading a binary `HexMap`.
+#[derive(Debug)]
+pub enum BinaryError {
+    /// The underlying reader failed, or the data ended early.
+    Io(io::Error),
+    /// The data doesn't start with the `HexMap` magic bytes.
+    BadMagic,
+    /// The data was written by an unsupported version of the format.
+    UnsupportedVersion(u8),
+    /// The data is malformed.
+    Corrupt(&'static str),
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BinaryError::Io(ref err) => write!(f, "I/O error: {}", err),
+            BinaryError::BadMagic => write!(f, "Not a binary HexMap"),
+            BinaryError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported binary HexMap version {} (expected {})", version, VERSION)
+            }
+            BinaryError::Corrupt(message) => write!(f, "Corrupt binary HexMap: {}", message),
+        }
+    }
+}
+
+impl Error for BinaryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            BinaryError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BinaryError {
+    fn from(err: io::Error) -> Self {
+        BinaryError::Io(err)
+    }
+}
+
+/// A value that can be stored in a binary `HexMap`.
+///
+/// Single-byte values are stored as-is; wider integers are stored as LEB128
+/// varints, zigzag-encoded if signed, so small values take little space.
+pub trait BinaryValue: Sized {
+    /// Write this value.
+    fn write_value<W: Write>(&self, w: &mut W) -> io::Result<()>;
+
+    /// Read a value written by `write_value`.
+    fn read_value<R: Read>(r: &mut R) -> Result<Self, BinaryError>;
+}
+
+impl BinaryValue for u8 {
+    fn write_value<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&[*self])
+    }
+
+    fn read_value<R: Read>(r: &mut R) -> Result<Self, BinaryError> {
+        read_byte(r)
+    }
+}
+
+impl BinaryValue for i8 {
+    fn write_value<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.to_le_bytes())
+    }
+
+    fn read_value<R: Read>(r: &mut R) -> Result<Self, BinaryError> {
+        Ok(i8::from_le_bytes([read_byte(r)?]))
+    }
+}
+
+impl BinaryValue for bool {
+    fn write_value<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&[u8::from(*self)])
+    }
+
+    fn read_value<R: Read>(r: &mut R) -> Result<Self, BinaryError> {
+        match read_byte(r)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(BinaryError::Corrupt("Invalid bool")),
+        }
+    }
+}
+
+macro_rules! impl_unsigned_value {
+    ($($t:ty),*) => {$(
+        impl BinaryValue for $t {
+            fn write_value<W: Write>(&self, w: &mut W) -> io::Result<()> {
+                write_varint(w, u64::from(*self))
+            }
+
+            fn read_value<R: Read>(r: &mut R) -> Result<Self, BinaryError> {
+                let value = read_varint(r)?;
+                <$t>::try_from(value).map_err(|_| BinaryError::Corrupt("Value out of range"))
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed_value {
+    ($($t:ty),*) => {$(
+        impl BinaryValue for $t {
+            fn write_value<W: Write>(&self, w: &mut W) -> io::Result<()> {
+                write_signed(w, i64::from(*self))
+            }
+
+            fn read_value<R: Read>(r: &mut R) -> Result<Self, BinaryError> {
+                let value = read_signed(r)?;
+                <$t>::try_from(value).map_err(|_| BinaryError::Corrupt("Value out of range"))
+            }
+        }
+    )*};
+}
+
+impl_unsigned_value!(u16, u32, u64);
+impl_signed_value!(i16, i32, i64);
+
+fn read_byte<R: Read>(r: &mut R) -> Result<u8, BinaryError> {
+    let mut buf = [0];
+    r.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn write_varint<W: Write>(w: &mut W, mut value: u64) -> io::Result<()> {
+    while value >= 0x80 {
+        w.write_all(&[(value as u8) | 0x80])?;
+        value >>= 7;
+    }
+    w.write_all(&[value as u8])
+}
+
+fn read_varint<R: Read>(r: &mut R) -> Result<u64, BinaryError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = read_byte(r)?;
+        let bits = u64::from(byte & 0x7f);
+        if shift == 63 && bits > 1 {
+            break;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(BinaryError::Corrupt("Varint out of range"))
+}
+
+fn write_signed<W: Write>(w: &mut W, value: i64) -> io::Result<()> {
+    write_varint(w, ((value << 1) ^ (value >> 63)) as u64)
+}
+
+fn read_signed<R: Read>(r: &mut R) -> Result<i64, BinaryError> {
+    let value = read_varint(r)?;
+    Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+}
+
+fn write_coordinate<W: Write>(w: &mut W, c: Coordinate) -> io::Result<()> {
+    write_signed(w, c.x())?;
+    write_signed(w, c.y())
+}
+
+fn read_coordinate<R: Read>(r: &mut R) -> Result<Coordinate, BinaryError> {
+    let x = read_signed(r)?;
+    let y = read_signed(r)?;
+    match x.checked_add(y).and_then(i64::checked_neg) {
+        Some(z) => Ok(Coordinate::at_unchecked(x, y, z)),
+        None => Err(BinaryError::Corrupt("Coordinate out of range")),
+    }
+}
+
+impl<T: BinaryValue> HexMap<T> {
+    /// Write this map in a compact binary encoding.
+    ///
+    /// The encoding starts with a header:
+    ///
+    /// * the magic bytes `HEXM`;
+    /// * a version byte, currently `1`;
+    /// * a shape byte: `0` for a hexagon, followed by its center's `x` and
+    ///   `y` components and its radius, or `1` for a sparse map, followed by
+    ///   its number of hexes.
+    ///
+    /// A hexagonal map's values follow, row by row, without coordinates. A
+    /// sparse map's hexes follow in the same order, each as its `x` and `y`
+    /// components and then its value. Header integers are LEB128 varints,
+    /// zigzag-encoded if signed; values are encoded by their `BinaryValue`
+    /// implementation.
+    pub fn write_binary<W: Write>(&self, mut w: W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        let coords = self.canonical_coordinates();
+        match self.shape {
+            MapShape::Hexagon { center, radius } => {
+                w.write_all(&[HEXAGON])?;
+                write_coordinate(&mut w, center)?;
+                write_signed(&mut w, radius)?;
+                for c in coords {
+                    self.cells[&c].write_value(&mut w)?;
+                }
+            }
+            MapShape::Sparse => {
+                w.write_all(&[SPARSE])?;
+                write_varint(&mut w, coords.len() as u64)?;
+                for c in coords {
+                    write_coordinate(&mut w, c)?;
+                    self.cells[&c].write_value(&mut w)?;
+                }
+            }
+        }
+        w.flush()
+    }
+
+    /// Read a map written by `write_binary`.
+    ///
+    /// Data written by another version of the format is rejected with
+    /// `BinaryError::UnsupportedVersion` rather than misread.
+    pub fn read_binary<R: Read>(mut r: R) -> Result<Self, BinaryError> {
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(BinaryError::BadMagic);
+        }
+        let version = read_byte(&mut r)?;
+        if version != VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+        match read_byte(&mut r)? {
+            HEXAGON => {
+                let center = read_coordinate(&mut r)?;
+                let radius = read_signed(&mut r)?;
+                if radius < 0 || radius > i64::from(u32::MAX) {
+                    return Err(BinaryError::Corrupt("Invalid radius"));
+                }
+                // Values are read before the hexagon is stored, so a corrupt
+                // radius runs out of data rather than allocating room for it.
+                let mut values = Vec::new();
+                for _ in hexagon(center, radius) {
+                    values.push(T::read_value(&mut r)?);
+                }
+                let mut values = values.into_iter();
+                let dense = Dense::from_fn(center, radius, |_| values.next().expect("a value per hex"));
+                Ok(HexMap { shape: MapShape::Hexagon { center, radius }, cells: Cells::Dense(dense) })
+            }
+            SPARSE => {
+                let len = read_varint(&mut r)?;
+                let mut cells = HashMap::new();
+                for _ in 0..len {
+                    let c = read_coordinate(&mut r)?;
+                    let value = T::read_value(&mut r)?;
+                    if cells.insert(c, value).is_some() {
+                        return Err(BinaryError::Corrupt("Duplicate coordinate"));
+                    }
+                }
+                Ok(HexMap { shape: MapShape::Sparse, cells: Cells::Sparse(cells) })
+            }
+            _ => Err(BinaryError::Corrupt("Unknown shape")),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hexmap::hexagon;
+
+    fn round_trip<T: BinaryValue>(map: &HexMap<T>) -> HexMap<T> {
+        let mut buf = Vec::new();
+        map.write_binary(&mut buf).unwrap();
+        HexMap::read_binary(&buf[..]).unwrap()
+    }
+
+    #[test]
+    fn it_round_trips_hexagons() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let mut map = HexMap::hexagon(center, 4, 0u8);
+        for (i, c) in hexagon(center, 4).enumerate() {
+            map.insert(c, i as u8).unwrap();
+        }
+        assert_eq!(round_trip(&map), map);
+
+        let mut map = HexMap::hexagon(Coordinate::new(), 3, false);
+        map.insert(Coordinate::at(1, 2, -3).unwrap(), true).unwrap();
+        assert_eq!(round_trip(&map), map);
+
+        let map = HexMap::hexagon(center, 0, -7i8);
+        assert_eq!(round_trip(&map), map);
+    }
+
+    #[test]
+    fn it_round_trips_sparse_maps() {
+        let map: HexMap<u32> = vec![
+            (Coordinate::at(200, -100, -100).unwrap(), u32::MAX),
+            (Coordinate::at(-3, -1, 4).unwrap(), 0),
+            (Coordinate::at(0, 0, 0).unwrap(), 300),
+        ].into_iter().collect();
+        assert_eq!(round_trip(&map), map);
+
+        let map: HexMap<i64> = vec![
+            (Coordinate::at(i64::MAX, -i64::MAX, 0).unwrap(), i64::MIN),
+            (Coordinate::at(1, -1, 0).unwrap(), -1),
+        ].into_iter().collect();
+        assert_eq!(round_trip(&map), map);
+
+        assert_eq!(round_trip(&HexMap::<u16>::new()), HexMap::new());
+    }
+
+    #[test]
+    fn it_rejects_bad_magic() {
+        let mut buf = Vec::new();
+        HexMap::hexagon(Coordinate::new(), 2, 1u8).write_binary(&mut buf).unwrap();
+        buf[0] = b'X';
+        match HexMap::<u8>::read_binary(&buf[..]) {
+            Err(BinaryError::BadMagic) => {}
+            other => panic!("expected BadMagic, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_rejects_other_versions() {
+        let mut buf = Vec::new();
+        HexMap::hexagon(Coordinate::new(), 2, 1u8).write_binary(&mut buf).unwrap();
+        buf[4] = VERSION + 1;
+        match HexMap::<u8>::read_binary(&buf[..]) {
+            Err(BinaryError::UnsupportedVersion(version)) => assert_eq!(version, VERSION + 1),
+            other => panic!("expected UnsupportedVersion, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_rejects_truncated_and_corrupt_data() {
+        let mut buf = Vec::new();
+        HexMap::hexagon(Coordinate::new(), 2, 1u8).write_binary(&mut buf).unwrap();
+        assert!(HexMap::<u8>::read_binary(&buf[..buf.len() - 1]).is_err());
+        buf[5] = 9;
+        match HexMap::<u8>::read_binary(&buf[..]) {
+            Err(BinaryError::Corrupt(_)) => {}
+            other => panic!("expected Corrupt, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_rejects_huge_radii_without_the_data_for_them() {
+        for radius in [2_000_000, i64::from(u32::MAX)].iter() {
+            let mut buf = Vec::new();
+            buf.extend_from_slice(MAGIC);
+            buf.extend_from_slice(&[VERSION, HEXAGON]);
+            write_coordinate(&mut buf, Coordinate::new()).unwrap();
+            write_signed(&mut buf, *radius).unwrap();
+            assert!(HexMap::<u8>::read_binary(&buf[..]).is_err());
+            buf.extend_from_slice(&[1; 100]);
+            assert!(HexMap::<u8>::read_binary(&buf[..]).is_err());
+        }
+    }
+
+    #[test]
+    fn it_stays_close_to_the_raw_payload() {
+        let map = HexMap::hexagon(Coordinate::at(-3, -1, 4).unwrap(), 20, 255u8);
+        let mut buf = Vec::new();
+        map.write_binary(&mut buf).unwrap();
+        assert_eq!(map.len(), 1261);
+        assert!(buf.len() <= map.len() + 16);
+    }
+}
@@ -0,0 +1,355 @@
+//! The storage behind a `HexMap`.
+use std::collections::hash_map;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::ops::Index;
+use std::slice;
+
+use hexmap::{hexagon, MapShape};
+use Coordinate;
+
+
+/// The values of a `HexMap`: packed row by row for a hexagon, or hashed by
+/// coordinate for a sparse map.
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) enum Cells<T> {
+    Dense(Dense<T>),
+    Sparse(HashMap<Coordinate, T>),
+}
+
+/// The values of a hexagonal map, stored in canonical order: by increasing
+/// `y` component and then increasing `x` component, with no padding.
+#[derive(Debug,PartialEq,Clone)]
+pub(crate) struct Dense<T> {
+    center: Coordinate,
+    radius: i64,
+    /// The `y` component of the first row.
+    y_min: i64,
+    rows: Vec<Row>,
+    values: Vec<T>,
+}
+
+/// Where one row of a `Dense` hexagon lies in its values.
+#[derive(Debug,PartialEq,Clone,Copy)]
+struct Row {
+    /// The index of the row's first value.
+    start: usize,
+    /// The `x` component of the row's first hex.
+    x_min: i64,
+    /// The number of hexes in the row.
+    len: usize,
+}
+
+impl<T> Dense<T> {
+    /// Create a hexagon with the value `f` gives for each hex, calling it in
+    /// canonical order.
+    pub(crate) fn from_fn<F: FnMut(Coordinate) -> T>(center: Coordinate, radius: i64, mut f: F) -> Self {
+        match Dense::try_from_fn(center, radius, |c| Ok::<T, Infallible>(f(c))) {
+            Ok(dense) => dense,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Create a hexagon with the value `f` gives for each hex, as for
+    /// `from_fn`, or the first error it returns.
+    pub(crate) fn try_from_fn<F, E>(center: Coordinate, radius: i64, f: F) -> Result<Self, E>
+        where F: FnMut(Coordinate) -> Result<T, E>
+    {
+        let radius = radius.max(-1);
+        let mut rows = Vec::with_capacity((2 * radius + 1).max(0) as usize);
+        let mut start = 0;
+        for dy in -radius..radius + 1 {
+            let dx_min = (-radius).max(-dy - radius);
+            let len = (radius.min(-dy + radius) - dx_min + 1) as usize;
+            rows.push(Row { start, x_min: center.x() + dx_min, len });
+            start += len;
+        }
+        let mut values = Vec::with_capacity(start);
+        for value in hexagon(center, radius).map(f) {
+            values.push(value?);
+        }
+        Ok(Dense { center, radius, y_min: center.y() - radius, rows, values })
+    }
+
+    /// Get a hexagon of the same shape with the value `f` gives for each
+    /// hex and its value.
+    pub(crate) fn map<U, F: FnMut(Coordinate, &T) -> U>(&self, mut f: F) -> Dense<U> {
+        let values = self.iter().map(|(c, v)| f(c, v)).collect();
+        Dense {
+            center: self.center,
+            radius: self.radius,
+            y_min: self.y_min,
+            rows: self.rows.clone(),
+            values,
+        }
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        Iter::Dense(Walk::new(&self.rows, self.y_min), self.values.iter())
+    }
+
+    /// Get the index of a hex's value, if it is in the hexagon.
+    #[inline]
+    fn index(&self, c: Coordinate) -> Option<usize> {
+        // Negative offsets wrap around to huge ones, so each bound is a
+        // single comparison.
+        let row = self.rows.get(c.y().wrapping_sub(self.y_min) as usize)?;
+        let along = c.x().wrapping_sub(row.x_min) as usize;
+        if along < row.len {
+            Some(row.start + along)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub(crate) fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+}
+
+impl<T> Cells<T> {
+    /// Store `cells` for a map of `shape`, which must have a value at every
+    /// hex of a hexagonal shape.
+    pub(crate) fn new(shape: MapShape, mut cells: HashMap<Coordinate, T>) -> Self {
+        match shape {
+            MapShape::Hexagon { center, radius } => Cells::Dense(Dense::from_fn(center, radius, |c| {
+                cells.remove(&c).expect("a hexagonal map has a value at every hex")
+            })),
+            MapShape::Sparse => Cells::Sparse(cells),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match *self {
+            Cells::Dense(ref dense) => dense.values.len(),
+            Cells::Sparse(ref cells) => cells.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn contains_key(&self, c: &Coordinate) -> bool {
+        self.get(c).is_some()
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, c: &Coordinate) -> Option<&T> {
+        match *self {
+            Cells::Dense(ref dense) => dense.index(*c).map(|i| &dense.values[i]),
+            Cells::Sparse(ref cells) => cells.get(c),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self, c: &Coordinate) -> Option<&mut T> {
+        match *self {
+            Cells::Dense(ref mut dense) => match dense.index(*c) {
+                Some(i) => Some(&mut dense.values[i]),
+                None => None,
+            },
+            Cells::Sparse(ref mut cells) => cells.get_mut(c),
+        }
+    }
+
+    /// Set the value at a hex, which must be in a hexagon's shape,
+    /// returning the previous value if there was one.
+    pub(crate) fn insert(&mut self, c: Coordinate, value: T) -> Option<T> {
+        match *self {
+            Cells::Dense(ref mut dense) => {
+                let i = dense.index(c).expect("hexes inserted into a hexagon are within it");
+                Some(::std::mem::replace(&mut dense.values[i], value))
+            }
+            Cells::Sparse(ref mut cells) => cells.insert(c, value),
+        }
+    }
+
+    /// Iterate over the hexes and their values, in canonical order for a
+    /// hexagon and arbitrary order otherwise.
+    pub(crate) fn iter(&self) -> Iter<'_, T> {
+        match *self {
+            Cells::Dense(ref dense) => dense.iter(),
+            Cells::Sparse(ref cells) => Iter::Sparse(cells.iter()),
+        }
+    }
+
+    /// Iterate over the hexes and mutable references to their values, in
+    /// the same order as `iter`.
+    pub(crate) fn iter_mut(&mut self) -> IterMut<'_, T> {
+        match *self {
+            Cells::Dense(ref mut dense) => {
+                IterMut::Dense(Walk::new(&dense.rows, dense.y_min), dense.values.iter_mut())
+            }
+            Cells::Sparse(ref mut cells) => IterMut::Sparse(cells.iter_mut()),
+        }
+    }
+
+    /// Get cells of the same shape with the value `f` gives for each hex
+    /// and its value.
+    pub(crate) fn map<U, F: FnMut(Coordinate, &T) -> U>(&self, mut f: F) -> Cells<U> {
+        match *self {
+            Cells::Dense(ref dense) => Cells::Dense(dense.map(f)),
+            Cells::Sparse(ref cells) => Cells::Sparse(cells.iter().map(|(c, v)| (*c, f(*c, v))).collect()),
+        }
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.iter().map(|(c, _)| c)
+    }
+
+    /// Move the values into a map keyed by hex.
+    pub(crate) fn into_map(self) -> HashMap<Coordinate, T> {
+        match self {
+            Cells::Dense(dense) => hexagon(dense.center, dense.radius).zip(dense.values).collect(),
+            Cells::Sparse(cells) => cells,
+        }
+    }
+}
+
+impl<T> Index<&Coordinate> for Cells<T> {
+    type Output = T;
+
+    fn index(&self, c: &Coordinate) -> &T {
+        self.get(c).expect("no value at hex")
+    }
+}
+
+/// An iterator over the hexes and values of `Cells`.
+pub(crate) enum Iter<'a, T> {
+    Dense(Walk<'a>, slice::Iter<'a, T>),
+    Sparse(hash_map::Iter<'a, Coordinate, T>),
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (Coordinate, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match *self {
+            Iter::Dense(ref mut walk, ref mut values) => values.next().map(|v| (walk.next_hex(), v)),
+            Iter::Sparse(ref mut iter) => iter.next().map(|(c, v)| (*c, v)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            Iter::Dense(_, ref values) => values.size_hint(),
+            Iter::Sparse(ref iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// An iterator over the hexes and mutable values of `Cells`.
+pub(crate) enum IterMut<'a, T> {
+    Dense(Walk<'a>, slice::IterMut<'a, T>),
+    Sparse(hash_map::IterMut<'a, Coordinate, T>),
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = (Coordinate, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match *self {
+            IterMut::Dense(ref mut walk, ref mut values) => {
+                values.next().map(|v| (walk.next_hex(), v))
+            }
+            IterMut::Sparse(ref mut iter) => iter.next().map(|(c, v)| (*c, v)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match *self {
+            IterMut::Dense(_, ref values) => values.size_hint(),
+            IterMut::Sparse(ref iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// The hexes of a `Dense` hexagon in canonical order, alongside its values.
+pub(crate) struct Walk<'a> {
+    rows: &'a [Row],
+    row: usize,
+    x: i64,
+    y: i64,
+}
+
+impl<'a> Walk<'a> {
+    fn new(rows: &'a [Row], y_min: i64) -> Self {
+        Walk { rows, row: 0, x: rows.first().map_or(0, |r| r.x_min), y: y_min }
+    }
+
+    /// Get the next hex, which must exist.
+    #[inline]
+    fn next_hex(&mut self) -> Coordinate {
+        let row = self.rows[self.row];
+        if self.x == row.x_min + row.len as i64 {
+            self.row += 1;
+            self.y += 1;
+            self.x = self.rows[self.row].x_min;
+        }
+        let c = Coordinate::from_axial(self.x, self.y);
+        self.x += 1;
+        c
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_indexes_every_hex_of_a_hexagon() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for radius in 0..6 {
+            let dense = Dense::from_fn(center, radius, |c| c);
+            let coords: Vec<Coordinate> = hexagon(center, radius).collect();
+            assert_eq!(dense.values(), &coords[..]);
+            for (i, c) in coords.iter().enumerate() {
+                assert_eq!(dense.index(*c), Some(i));
+            }
+        }
+    }
+
+    #[test]
+    fn it_misses_hexes_outside_the_hexagon() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let dense = Dense::from_fn(center, 3, |_| ());
+        for c in center.ring(4).into_iter().chain(center.ring(9)) {
+            assert_eq!(dense.index(c), None);
+        }
+        let far = Coordinate::from_axial(i64::MAX / 2, i64::MIN / 2);
+        assert_eq!(dense.index(far), None);
+        let extremes = [
+            Coordinate::at(0, i64::MAX, -i64::MAX).unwrap(),
+            Coordinate::at(0, -i64::MAX, i64::MAX).unwrap(),
+            Coordinate::at(i64::MAX, -i64::MAX, 0).unwrap(),
+            Coordinate::at(i64::MIN, i64::MAX, 1).unwrap(),
+            Coordinate::at(i64::MAX, i64::MIN, 1).unwrap(),
+        ];
+        for c in extremes.iter() {
+            assert_eq!(dense.index(*c), None);
+        }
+        let empty = Dense::from_fn(center, -2, |_| ());
+        assert!(empty.values().is_empty());
+        assert_eq!(empty.index(center), None);
+    }
+
+    #[test]
+    fn it_iterates_in_canonical_order() {
+        let center = Coordinate::at(2, 1, -3).unwrap();
+        let mut cells = Cells::Dense(Dense::from_fn(center, 4, |c| c));
+        let walked: Vec<_> = cells.iter().map(|(c, v)| (c, *v)).collect();
+        let expected: Vec<_> = hexagon(center, 4).map(|c| (c, c)).collect();
+        assert_eq!(walked, expected);
+        assert_eq!(cells.iter().size_hint(), (61, Some(61)));
+        let walked_mut: Vec<_> = cells.iter_mut().map(|(c, v)| (c, *v)).collect();
+        assert_eq!(walked_mut, expected);
+        let map: HashMap<_, _> = expected.into_iter().collect();
+        assert_eq!(Cells::new(MapShape::Hexagon { center, radius: 4 }, map), cells);
+    }
+}
@@ -0,0 +1,145 @@
+//! Transforming maps' values, and combining maps hex by hex.
+use std::collections::HashMap;
+
+use hexmap::{Cells, HexMap, MapShape};
+use {Coordinate, HexError};
+
+
+impl<T> HexMap<T> {
+    /// Get a map of the same shape with the value `f` gives for each hex and
+    /// its value.
+    pub fn map<U, F: Fn(&Coordinate, &T) -> U>(&self, f: F) -> HexMap<U> {
+        HexMap { shape: self.shape, cells: self.cells.map(|c, v| f(&c, v)) }
+    }
+
+    /// Combine this map with `other` hex by hex, keeping only the hexes
+    /// with values in both.
+    ///
+    /// Maps of the same shape keep it. Otherwise, as for `crop`, the result
+    /// is hexagonal if the hexes in both make up a whole hexagon, and sparse
+    /// if not. Use `zip_map_strict` to require the maps to match.
+    pub fn zip_map<U, V, F>(&self, other: &HexMap<U>, f: F) -> HexMap<V>
+        where F: Fn(&Coordinate, &T, &U) -> V
+    {
+        if self.shape == other.shape {
+            if let MapShape::Hexagon { .. } = self.shape {
+                let cells = self.cells.map(|c, t| f(&c, t, &other.cells[&c]));
+                return HexMap { shape: self.shape, cells };
+            }
+        }
+        let cells: HashMap<Coordinate, V> = self.cells.iter()
+            .filter_map(|(c, t)| other.cells.get(&c).map(|u| (c, f(&c, t, u))))
+            .collect();
+        let map = HexMap { shape: MapShape::Sparse, cells: Cells::Sparse(cells) };
+        if self.shape == other.shape { map } else { map.fitted() }
+    }
+
+    /// Combine this map with `other` hex by hex, as for `zip_map`, if they
+    /// have values at exactly the same hexes.
+    ///
+    /// This fails with `HexError::ShapeMismatch` naming the least hex, in
+    /// `Ord` order, with a value in only one of the maps. A sparse map with
+    /// a value at every hex of a hexagonal one matches it, and the result
+    /// has this map's shape.
+    pub fn zip_map_strict<U, V, F>(&self, other: &HexMap<U>, f: F) -> Result<HexMap<V>, HexError>
+        where F: Fn(&Coordinate, &T, &U) -> V
+    {
+        let only_self = self.cells.keys().filter(|c| !other.cells.contains_key(c));
+        let only_other = other.cells.keys().filter(|c| !self.cells.contains_key(c));
+        if let Some(c) = only_self.chain(only_other).min() {
+            return Err(HexError::ShapeMismatch(c));
+        }
+        Ok(HexMap { shape: self.shape, cells: self.cells.map(|c, t| f(&c, t, &other.cells[&c])) })
+    }
+
+    /// Call `f` with each hex and a mutable reference to its value, in
+    /// arbitrary order.
+    pub fn update_each<F: FnMut(&Coordinate, &mut T)>(&mut self, mut f: F) {
+        for (c, v) in self.cells.iter_mut() {
+            f(&c, v);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elevation(center: Coordinate, radius: i64) -> HexMap<i64> {
+        HexMap::from_fn(center.spiral(radius), |c| c.x() * 10 + c.y())
+    }
+
+    #[test]
+    fn it_maps_values_keeping_the_shape() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let map = elevation(center, 2);
+        let doubled = map.map(|c, v| (*c, v * 2));
+        assert_eq!(doubled.shape(), map.shape());
+        assert_eq!(doubled.len(), map.len());
+        for (c, v) in map.iter() {
+            assert_eq!(doubled.get(c), Some(&(c, v * 2)));
+        }
+        let sparse: HexMap<_> = vec![(center, 'a')].into_iter().collect();
+        assert_eq!(sparse.map(|_, v| v.to_ascii_uppercase()).shape(), MapShape::Sparse);
+    }
+
+    #[test]
+    fn it_zips_matching_maps_elementwise() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let height = elevation(center, 2);
+        let moisture = HexMap::from_fn(center.spiral(2), |c| center.distance_to(*c));
+        let biome = height.zip_map(&moisture, |_, h, m| h - m);
+        let strict = height.zip_map_strict(&moisture, |_, h, m| h - m);
+        assert_eq!(biome.shape(), height.shape());
+        assert_eq!(strict, Ok(biome.clone()));
+        for (c, v) in biome.iter() {
+            assert_eq!(*v, height.get(c).unwrap() - moisture.get(c).unwrap());
+        }
+    }
+
+    #[test]
+    fn it_zips_the_intersection_of_shapes() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let height = elevation(center, 2);
+        let moved = center + Coordinate::at(2, 0, -2).unwrap();
+        let other = elevation(moved, 2);
+        let both = height.zip_map(&other, |_, a, b| a + b);
+        assert_eq!(both.shape(), MapShape::Sparse);
+        assert!(both.len() < height.len());
+        for c in center.spiral(5) {
+            let overlap = center.distance_to(c) <= 2 && moved.distance_to(c) <= 2;
+            assert_eq!(both.contains(c), overlap);
+        }
+        let inner = elevation(center, 1);
+        let cropped = height.zip_map(&inner, |_, a, _| *a);
+        assert_eq!(cropped, height.crop(center.spiral(1)));
+    }
+
+    #[test]
+    fn it_names_the_mismatch_when_strict() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let height = elevation(center, 2);
+        let inner = elevation(center, 1);
+        let least = height.crop(center.ring(2)).iter().map(|(c, _)| c).min().unwrap();
+        let result = height.zip_map_strict(&inner, |_, a, b| a + b);
+        assert_eq!(result, Err(HexError::ShapeMismatch(least)));
+        assert_eq!(inner.zip_map_strict(&height, |_, a, b| a + b), result);
+        let sparse: HexMap<_> = center.spiral(2).into_iter().map(|c| (c, ())).collect();
+        assert_eq!(height.zip_map_strict(&sparse, |_, a, _| *a), Ok(height.clone()));
+    }
+
+    #[test]
+    fn it_updates_each_value_in_place() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let mut map = elevation(center, 2);
+        let original = map.clone();
+        let mut calls = 0;
+        map.update_each(|c, v| {
+            calls += 1;
+            *v += center.distance_to(*c);
+        });
+        assert_eq!(calls, map.len());
+        assert_eq!(map, original.map(|c, v| v + center.distance_to(*c)));
+    }
+}
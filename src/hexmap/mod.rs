@@ -0,0 +1,551 @@
+//! Maps from hexes to values.
+use std::collections::hash_map;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::iter::FromIterator;
+
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use algorithms::bounds;
+use shapes::Shape;
+use {Coordinate, HexError, HexRegion, Offset};
+
+mod binary;
+mod cells;
+mod combine;
+mod reshape;
+#[cfg(feature = "serde")]
+mod save;
+mod symmetry;
+
+pub use self::binary::{BinaryError, BinaryValue};
+#[cfg(feature = "serde")]
+pub use self::save::MapDocument;
+pub use self::symmetry::{Symmetry, SymmetryCenter, SymmetryGroup};
+pub(crate) use self::symmetry::transform;
+use self::cells::{Cells, Dense};
+
+
+/// The set of hexes a `HexMap` covers.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MapShape {
+    /// Every hex within `radius` of `center`, each of which always has a
+    /// value.
+    Hexagon { center: Coordinate, radius: i64 },
+    /// Whichever hexes have had values inserted.
+    Sparse,
+}
+
+impl MapShape {
+    /// Whether a hex is part of this shape. Every hex is part of a sparse
+    /// shape.
+    pub fn contains(&self, c: Coordinate) -> bool {
+        match *self {
+            MapShape::Hexagon { center, radius } => center.distance_to(c) <= radius,
+            MapShape::Sparse => true,
+        }
+    }
+}
+
+/// Iterate over the hexes within `radius` of `center`, by increasing `y`
+/// component and then increasing `x` component.
+pub(crate) fn hexagon(center: Coordinate, radius: i64) -> impl Iterator<Item = Coordinate> {
+    (-radius..radius + 1).flat_map(move |dy| {
+        let dxs = (-radius).max(-dy - radius)..radius.min(-dy + radius) + 1;
+        dxs.map(move |dx| center + Coordinate::from_axial(dx, dy))
+    })
+}
+
+/// A map from hexes to values.
+///
+/// A map either has a fixed hexagonal shape, with a value at every hex, or
+/// is sparse and holds values only where they've been inserted.
+///
+/// A hexagonal map stores its values in one contiguous block in canonical
+/// order, row by row with no padding, so looking a hex up is a few
+/// additions and iterating over the map walks the block; see `as_slice`. A
+/// sparse map hashes its hexes.
+#[derive(Debug,PartialEq,Clone)]
+pub struct HexMap<T> {
+    shape: MapShape,
+    cells: Cells<T>,
+}
+
+impl<T> HexMap<T> {
+    /// Create a new, empty, sparse HexMap.
+    pub fn new() -> Self {
+        HexMap { shape: MapShape::Sparse, cells: Cells::Sparse(HashMap::new()) }
+    }
+
+    /// Create a new HexMap covering every hex within `radius` of `center`,
+    /// with `fill` at each.
+    pub fn hexagon(center: Coordinate, radius: i64, fill: T) -> Self
+        where T: Clone
+    {
+        let cells = Cells::Dense(Dense::from_fn(center, radius, |_| fill.clone()));
+        HexMap { shape: MapShape::Hexagon { center, radius }, cells }
+    }
+
+    /// Create a HexMap with the value `f` gives for each hex of `shape`.
+    ///
+    /// `f` is called exactly once per hex, in the order `shape` gives them,
+    /// skipping any repeats. As for `crop`, the map is hexagonal if its hexes
+    /// make up a whole hexagon, and sparse otherwise.
+    pub fn from_fn<I, F>(shape: I, mut f: F) -> Self
+        where I: IntoIterator<Item = Coordinate>, F: FnMut(&Coordinate) -> T
+    {
+        match HexMap::try_from_fn(shape, |c| Ok::<T, Infallible>(f(c))) {
+            Ok(map) => map,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Create a HexMap with the value `f` gives for each hex of `shape`, as
+    /// for `from_fn`, or the first error it returns.
+    ///
+    /// `f` isn't called again after an error.
+    pub fn try_from_fn<I, F, E>(shape: I, mut f: F) -> Result<Self, E>
+        where I: IntoIterator<Item = Coordinate>, F: FnMut(&Coordinate) -> Result<T, E>
+    {
+        let mut cells = HashMap::new();
+        for c in shape {
+            if let hash_map::Entry::Vacant(entry) = cells.entry(c) {
+                entry.insert(f(&c)?);
+            }
+        }
+        Ok(HexMap { shape: MapShape::Sparse, cells: Cells::Sparse(cells) }.fitted())
+    }
+
+    /// Create a HexMap with the value `f` gives for each hex of `shape`, in
+    /// the order it iterates over them, as for `from_fn`.
+    pub fn from_shape<S, F>(shape: &S, f: F) -> Self
+        where S: Shape + ?Sized, F: FnMut(&Coordinate) -> T
+    {
+        HexMap::from_fn(shape.iter(), f)
+    }
+
+    /// Replace the value at every hex with a value `f` gives, in place.
+    ///
+    /// `f` is called exactly once per hex with a value, in the map's
+    /// canonical order: by increasing `y` component and then increasing `x`
+    /// component. The map's storage is reused, not reallocated.
+    pub fn fill<F: FnMut(&Coordinate) -> T>(&mut self, mut f: F) {
+        if let Cells::Dense(_) = self.cells {
+            for (c, value) in self.cells.iter_mut() {
+                *value = f(&c);
+            }
+            return;
+        }
+        for c in self.canonical_coordinates() {
+            if let Some(value) = self.cells.get_mut(&c) {
+                *value = f(&c);
+            }
+        }
+    }
+
+    /// Get the shape of this HexMap.
+    pub fn shape(&self) -> MapShape {
+        self.shape
+    }
+
+    /// Get the number of hexes with values.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether no hexes have values.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whether a hex has a value.
+    pub fn contains(&self, c: Coordinate) -> bool {
+        self.cells.contains_key(&c)
+    }
+
+    /// Whether every hex with a value lies in `shape`.
+    pub fn is_within<S: Shape + ?Sized>(&self, shape: &S) -> bool {
+        self.cells.keys().all(|c| shape.contains(c))
+    }
+
+    /// Get the value at a hex.
+    pub fn get(&self, c: Coordinate) -> Option<&T> {
+        self.cells.get(&c)
+    }
+
+    /// Get a mutable reference to the value at a hex.
+    pub fn get_mut(&mut self, c: Coordinate) -> Option<&mut T> {
+        self.cells.get_mut(&c)
+    }
+
+    /// Set the value at a hex, returning the previous value if there was one.
+    ///
+    /// This fails if the hex is outside the map's shape.
+    pub fn insert(&mut self, c: Coordinate, value: T) -> Result<Option<T>, HexError> {
+        if self.shape.contains(c) {
+            Ok(self.cells.insert(c, value))
+        } else {
+            Err(HexError::OutsideShape)
+        }
+    }
+
+    /// Iterate over the hexes and their values: in canonical order for a
+    /// hexagonal map, and in arbitrary order for a sparse one.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, &T)> + '_ {
+        self.cells.iter()
+    }
+
+    /// Iterate over the hexes and their values in `Coordinate`'s `Ord`
+    /// order, whatever the map's shape, e.g. to save maps reproducibly.
+    ///
+    /// This is the same as `iter` for a hexagonal map. A sparse map's hexes
+    /// are sorted first.
+    pub fn iter_sorted(&self) -> impl Iterator<Item = (Coordinate, &T)> + '_ {
+        self.canonical_coordinates().into_iter().map(move |c| (c, &self.cells[&c]))
+    }
+
+    /// Get a hexagonal map's values in canonical order, by increasing `y`
+    /// component and then increasing `x` component, or `None` for a sparse
+    /// map.
+    ///
+    /// This is the map's own storage, for passes over every value at once.
+    pub fn as_slice(&self) -> Option<&[T]> {
+        match self.cells {
+            Cells::Dense(ref dense) => Some(dense.values()),
+            Cells::Sparse(_) => None,
+        }
+    }
+
+    /// Get a hexagonal map's values in canonical order for changing in
+    /// place, as for `as_slice`.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [T]> {
+        match self.cells {
+            Cells::Dense(ref mut dense) => Some(dense.values_mut()),
+            Cells::Sparse(_) => None,
+        }
+    }
+
+    /// Iterate over the map's rows of hexes with values in `offset`
+    /// coordinates, as for `HexRegion::rows`.
+    ///
+    /// This walks the region the map's hexes span, so it suits hexagonal and
+    /// compact sparse maps; scattered sparse maps are better sorted.
+    pub fn rows(&self, offset: Offset)
+        -> impl Iterator<Item = impl Iterator<Item = Coordinate> + Clone + '_> + '_
+    {
+        self.span().rows(offset)
+            .map(move |row| row.filter(move |c| self.cells.contains_key(c)))
+            .filter(|row| row.clone().next().is_some())
+    }
+
+    /// Iterate over the map's columns of hexes with values in `offset`
+    /// coordinates, as for `rows`.
+    pub fn columns(&self, offset: Offset)
+        -> impl Iterator<Item = impl Iterator<Item = Coordinate> + Clone + '_> + '_
+    {
+        self.span().columns(offset)
+            .map(move |column| column.filter(move |c| self.cells.contains_key(c)))
+            .filter(|column| column.clone().next().is_some())
+    }
+
+    /// Get a region holding every hex with a value.
+    fn span(&self) -> HexRegion {
+        match self.shape {
+            MapShape::Hexagon { center, radius } => HexRegion::hexagon(center, radius),
+            MapShape::Sparse => match bounds(self.cells.keys()) {
+                Some(b) => b.region(),
+                None => HexRegion::hexagon(Coordinate::new(), -1),
+            },
+        }
+    }
+
+    /// Iterate over the hexes with values in the map's canonical order.
+    ///
+    /// For a hexagonal map this is by increasing `y` component and then
+    /// increasing `x` component, i.e. row by row in a pointy-top layout; a
+    /// sparse map's hexes are sorted into the same order.
+    pub(crate) fn canonical_coordinates(&self) -> Vec<Coordinate> {
+        match self.shape {
+            MapShape::Hexagon { center, radius } => hexagon(center, radius).collect(),
+            MapShape::Sparse => {
+                let mut coords: Vec<Coordinate> = self.cells.keys().collect();
+                coords.sort();
+                coords
+            }
+        }
+    }
+}
+
+impl<T> Default for HexMap<T> {
+    fn default() -> Self {
+        HexMap::new()
+    }
+}
+
+impl<T> FromIterator<(Coordinate, T)> for HexMap<T> {
+    /// Collect hexes and values into a sparse HexMap.
+    fn from_iter<I: IntoIterator<Item = (Coordinate, T)>>(iter: I) -> Self {
+        HexMap { shape: MapShape::Sparse, cells: Cells::Sparse(iter.into_iter().collect()) }
+    }
+}
+
+/// The form a `HexMap` takes under serde: its shape and its hexes with their
+/// values, in canonical order.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct SerdeHexMap<C> {
+    shape: MapShape,
+    cells: Vec<C>,
+}
+
+/// HexMaps serialize as a struct with a `shape` field and a `cells` field
+/// holding `(coordinate, value)` pairs. This isn't compact, so prefer
+/// `write_binary` for large maps.
+#[cfg(feature = "serde")]
+impl<T: Serialize> Serialize for HexMap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let cells = self.canonical_coordinates().into_iter().map(|c| (c, &self.cells[&c]));
+        SerdeHexMap { shape: self.shape, cells: cells.collect() }.serialize(serializer)
+    }
+}
+
+/// HexMaps deserialize from the form they serialize to. A hexagonal map must
+/// have exactly one value for every hex in its shape.
+#[cfg(feature = "serde")]
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for HexMap<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = SerdeHexMap::<(Coordinate, T)>::deserialize(deserializer)?;
+        let shape = raw.shape;
+        let len = raw.cells.len();
+        let cells: HashMap<Coordinate, T> = raw.cells.into_iter().collect();
+        if cells.len() != len {
+            return Err(de::Error::custom("Duplicate coordinate"));
+        }
+        if !cells.keys().all(|c| shape.contains(*c)) {
+            return Err(de::Error::custom("Coordinate outside map shape"));
+        }
+        if let MapShape::Hexagon { center, radius } = shape {
+            if hexagon(center, radius).count() != len {
+                return Err(de::Error::custom("Missing coordinate"));
+            }
+        }
+        Ok(HexMap { shape, cells: Cells::new(shape, cells) })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_fills_a_hexagon() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let map = HexMap::hexagon(center, 2, 'a');
+        assert_eq!(map.len(), 19);
+        for (coord, value) in map.iter() {
+            assert!(center.distance_to(coord) <= 2);
+            assert_eq!(*value, 'a');
+        }
+    }
+
+    #[test]
+    fn it_only_inserts_within_its_shape() {
+        let mut map = HexMap::hexagon(Coordinate::new(), 1, 0);
+        let inside = Coordinate::at(1, 0, -1).unwrap();
+        let outside = Coordinate::at(2, 0, -2).unwrap();
+        assert_eq!(map.insert(inside, 5), Ok(Some(0)));
+        assert_eq!(map.get(inside), Some(&5));
+        assert!(map.insert(outside, 5).is_err());
+        assert_eq!(map.get(outside), None);
+        let far = Coordinate::from_axial(0, i64::MAX);
+        assert_eq!(map.get(far), None);
+    }
+
+    #[test]
+    fn it_inserts_anywhere_when_sparse() {
+        let mut map = HexMap::new();
+        let far = Coordinate::at(200, -100, -100).unwrap();
+        assert_eq!(map.insert(far, "x"), Ok(None));
+        *map.get_mut(far).unwrap() = "y";
+        assert_eq!(map.get(far), Some(&"y"));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn it_builds_maps_from_functions_of_position() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let shape = center.spiral(2);
+        let mut calls = Vec::new();
+        let map = HexMap::from_fn(shape.iter().cloned().chain(Some(center)), |c| {
+            calls.push(*c);
+            center.distance_to(*c)
+        });
+        assert_eq!(calls, shape);
+        assert_eq!(map.shape(), MapShape::Hexagon { center, radius: 2 });
+        assert!(map.iter().all(|(c, d)| center.distance_to(c) == *d));
+        let ring = HexMap::from_fn(center.ring(2), |_| ());
+        assert_eq!(ring.shape(), MapShape::Sparse);
+        assert_eq!(ring.len(), 12);
+    }
+
+    #[test]
+    fn it_builds_and_checks_maps_against_shapes() {
+        use shapes::{CompositeShape, HexagonShape, TriangleShape};
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let hexagon = HexagonShape::new(center, 2);
+        let map = HexMap::from_shape(&hexagon, |c| center.distance_to(*c));
+        assert_eq!(map.shape(), MapShape::Hexagon { center, radius: 2 });
+        assert!(map.is_within(&hexagon));
+        let hole = HexagonShape::new(center, 0);
+        let annulus: &dyn Shape = &CompositeShape::Difference(hexagon, hole);
+        let ring = HexMap::from_shape(annulus, |_| ());
+        assert_eq!(ring.len(), 18);
+        assert!(ring.is_within(annulus));
+        assert!(!map.is_within(annulus));
+        assert!(!map.is_within(&TriangleShape::new(center, 3)));
+    }
+
+    #[test]
+    fn it_stops_building_at_the_first_error() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let mut calls = 0;
+        let result = HexMap::try_from_fn(center.spiral(3), |c| {
+            calls += 1;
+            if center.distance_to(*c) < 2 { Ok(calls) } else { Err(*c) }
+        });
+        assert_eq!(result, Err(center.ring(2)[0]));
+        assert_eq!(calls, 8);
+        let built: Result<HexMap<_>, ()> = HexMap::try_from_fn(center.spiral(1), |_| Ok(1));
+        assert_eq!(built.unwrap().len(), 7);
+    }
+
+    #[test]
+    fn it_fills_in_place_in_canonical_order() {
+        let mut map = HexMap::hexagon(Coordinate::at(-3, -1, 4).unwrap(), 3, 0);
+        let first = map.canonical_coordinates()[0];
+        let storage = map.as_slice().map(|values| values.as_ptr());
+        let mut calls = Vec::new();
+        let mut count = 0;
+        map.fill(|c| {
+            calls.push(*c);
+            count += 1;
+            count
+        });
+        assert_eq!(calls, map.canonical_coordinates());
+        assert_eq!(map.get(first), Some(&1));
+        assert_eq!(map.as_slice().map(|values| values.as_ptr()), storage);
+        let far = Coordinate::at(5, 0, -5).unwrap();
+        let mut sparse: HexMap<_> = vec![(far, 'a'), (Coordinate::new(), 'b')]
+            .into_iter()
+            .collect();
+        let mut order = Vec::new();
+        sparse.fill(|c| {
+            order.push(*c);
+            'c'
+        });
+        assert_eq!(order, sparse.canonical_coordinates());
+        assert!(sparse.iter().all(|(_, v)| *v == 'c'));
+    }
+
+    #[test]
+    fn it_exposes_hexagon_storage_as_a_slice() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let mut map = HexMap::from_fn(center.spiral(2), |c| center.distance_to(*c));
+        let expected: Vec<_> = map.canonical_coordinates().into_iter()
+            .map(|c| center.distance_to(c))
+            .collect();
+        assert_eq!(map.as_slice(), Some(&expected[..]));
+        for value in map.as_mut_slice().unwrap() {
+            *value *= 10;
+        }
+        assert_eq!(map.get(center + Coordinate::at(2, 0, -2).unwrap()), Some(&20));
+        let mut sparse: HexMap<_> = vec![(center, 1)].into_iter().collect();
+        assert_eq!(sparse.as_slice(), None);
+        assert_eq!(sparse.as_mut_slice(), None);
+    }
+
+    #[test]
+    fn it_iterates_sorted_whatever_the_insertion_order() {
+        let pairs: Vec<_> = Coordinate::at(-3, -1, 4).unwrap().spiral(3).into_iter()
+            .enumerate()
+            .map(|(i, c)| (c, i))
+            .collect();
+        let forward: HexMap<_> = pairs.iter().cloned().collect();
+        let backward: HexMap<_> = pairs.iter().rev().cloned().collect();
+        let sorted: Vec<_> = forward.iter_sorted().collect();
+        assert_eq!(sorted, backward.iter_sorted().collect::<Vec<_>>());
+        assert!(sorted.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(sorted.len(), pairs.len());
+        let hexagon = HexMap::hexagon(Coordinate::new(), 2, 'a');
+        assert!(hexagon.iter_sorted().eq(hexagon.iter()));
+    }
+
+    #[test]
+    fn it_orders_hexagon_coordinates_by_row() {
+        let map = HexMap::hexagon(Coordinate::new(), 1, ());
+        let expected = vec![
+            Coordinate::at(0, -1, 1).unwrap(),
+            Coordinate::at(1, -1, 0).unwrap(),
+            Coordinate::at(-1, 0, 1).unwrap(),
+            Coordinate::at(0, 0, 0).unwrap(),
+            Coordinate::at(1, 0, -1).unwrap(),
+            Coordinate::at(-1, 1, 0).unwrap(),
+            Coordinate::at(0, 1, -1).unwrap(),
+        ];
+        assert_eq!(map.canonical_coordinates(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn it_round_trips_through_serde() {
+        let mut map = HexMap::hexagon(Coordinate::new(), 1, 0u8);
+        map.insert(Coordinate::at(1, 0, -1).unwrap(), 9).unwrap();
+        let json = ::serde_json::to_string(&map).unwrap();
+        assert_eq!(::serde_json::from_str::<HexMap<u8>>(&json).unwrap(), map);
+
+        let sparse: HexMap<u8> = vec![(Coordinate::at(5, 0, -5).unwrap(), 1)].into_iter().collect();
+        let json = ::serde_json::to_string(&sparse).unwrap();
+        assert_eq!(json, r#"{"shape":"Sparse","cells":[[[5,0,-5],1]]}"#);
+        assert_eq!(::serde_json::from_str::<HexMap<u8>>(&json).unwrap(), sparse);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn it_rejects_incomplete_hexagons_from_serde() {
+        let json = r#"{"shape":{"Hexagon":{"center":[0,0,0],"radius":1}},"cells":[[[0,0,0],1]]}"#;
+        assert!(::serde_json::from_str::<HexMap<u8>>(json).is_err());
+    }
+
+    #[test]
+    fn it_iterates_in_offset_rows_and_columns() {
+        for offset in [Offset::OddR, Offset::EvenR, Offset::OddQ, Offset::EvenQ].iter() {
+            let mut rectangle = HexMap::new();
+            for row in -3..4 {
+                for column in -2..5 {
+                    rectangle.insert(Coordinate::from_offset(column, row, *offset), ()).unwrap();
+                }
+            }
+            let rows: Vec<Vec<(i64, i64)>> = rectangle.rows(*offset)
+                .map(|row| row.map(|c| c.to_offset(*offset)).collect())
+                .collect();
+            let expected: Vec<Vec<(i64, i64)>> = (-3..4)
+                .map(|row| (-2..5).map(|column| (column, row)).collect())
+                .collect();
+            assert_eq!(rows, expected);
+            let columns: Vec<Vec<(i64, i64)>> = rectangle.columns(*offset)
+                .map(|column| column.map(|c| c.to_offset(*offset)).collect())
+                .collect();
+            let expected: Vec<Vec<(i64, i64)>> = (-2..5)
+                .map(|column| (-3..4).map(|row| (column, row)).collect())
+                .collect();
+            assert_eq!(columns, expected);
+        }
+        let map = HexMap::hexagon(Coordinate::new(), 2, ());
+        assert_eq!(map.rows(Offset::OddR).count(), 5);
+        assert_eq!(map.rows(Offset::OddR).map(|row| row.count()).sum::<usize>(), 19);
+        assert_eq!(HexMap::<()>::new().columns(Offset::EvenQ).count(), 0);
+    }
+}
@@ -0,0 +1,141 @@
+//! Cropping, translating, and reshaping maps.
+use algorithms::bounding_hexagon;
+use hexmap::{Cells, Dense, HexMap, MapShape};
+use Coordinate;
+
+
+impl<T> HexMap<T> {
+    /// Make this sparse map hexagonal if its hexes make up a whole hexagon.
+    pub(crate) fn fitted(self) -> HexMap<T> {
+        match bounding_hexagon(self.cells.keys()) {
+            Some((center, radius)) if Coordinate::spiral_size(radius.into()) == self.len() => {
+                let shape = MapShape::Hexagon { center, radius: radius.into() };
+                HexMap { shape, cells: Cells::new(shape, self.cells.into_map()) }
+            }
+            _ => self,
+        }
+    }
+}
+
+impl<T: Clone> HexMap<T> {
+    /// Get a copy of this map holding only the values at hexes in `shape`.
+    ///
+    /// The copy is hexagonal if the hexes it keeps make up a whole hexagon,
+    /// and sparse otherwise, so cropping a map to a shape it doesn't reach
+    /// gives an empty sparse map.
+    pub fn crop<I: IntoIterator<Item = Coordinate>>(&self, shape: I) -> HexMap<T> {
+        let cells: HexMap<T> = shape.into_iter()
+            .filter_map(|c| self.cells.get(&c).map(|v| (c, v.clone())))
+            .collect();
+        cells.fitted()
+    }
+
+    /// Get a copy of this map with every value, and a hexagonal map's
+    /// shape, moved by `offset`.
+    pub fn translated(&self, offset: Coordinate) -> HexMap<T> {
+        let shape = match self.shape {
+            MapShape::Hexagon { center, radius } => {
+                MapShape::Hexagon { center: center + offset, radius }
+            }
+            MapShape::Sparse => MapShape::Sparse,
+        };
+        let cells = self.cells.iter().map(|(c, v)| (c + offset, v.clone())).collect();
+        HexMap { shape, cells: Cells::new(shape, cells) }
+    }
+
+    /// Get a copy of this map with a new shape, keeping the values at hexes
+    /// in both shapes.
+    ///
+    /// A hexagonal shape's hexes without values are given `fill`. Reshaping
+    /// to `MapShape::Sparse` keeps every value.
+    pub fn reshaped(&self, new_shape: MapShape, fill: T) -> HexMap<T> {
+        let cells = match new_shape {
+            MapShape::Hexagon { center, radius } => Cells::Dense(Dense::from_fn(center, radius, |c| {
+                self.cells.get(&c).unwrap_or(&fill).clone()
+            })),
+            MapShape::Sparse => Cells::Sparse(self.cells.iter().map(|(c, v)| (c, v.clone())).collect()),
+        };
+        HexMap { shape: new_shape, cells }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(center: Coordinate, radius: i64) -> HexMap<usize> {
+        let mut map = HexMap::hexagon(center, radius, 0);
+        for (i, c) in center.spiral(radius).into_iter().enumerate() {
+            map.insert(c, i + 1).unwrap();
+        }
+        map
+    }
+
+    #[test]
+    fn it_crops_to_the_intersection() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let map = numbered(center, 2);
+        let ring = center.ring(2);
+        let cropped = map.crop(ring.iter().cloned().chain(center.ring(3)));
+        assert_eq!(cropped.shape(), MapShape::Sparse);
+        assert_eq!(cropped.len(), ring.len());
+        for c in ring {
+            assert_eq!(cropped.get(c), map.get(c));
+        }
+        let far = Coordinate::at(30, -10, -20).unwrap();
+        assert!(map.crop(far.spiral(3)).is_empty());
+        assert_eq!(map.crop(center.spiral(5)), map);
+    }
+
+    #[test]
+    fn it_translates_by_zero_as_identity() {
+        let map = numbered(Coordinate::at(-3, -1, 4).unwrap(), 2);
+        assert_eq!(map.translated(Coordinate::new()), map);
+        let sparse: HexMap<_> = vec![(Coordinate::new(), 'a')].into_iter().collect();
+        assert_eq!(sparse.translated(Coordinate::new()), sparse);
+    }
+
+    #[test]
+    fn it_translates_values_and_shapes() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let offset = Coordinate::at(5, -7, 2).unwrap();
+        let map = numbered(center, 2);
+        let moved = map.translated(offset);
+        assert_eq!(moved.shape(), MapShape::Hexagon { center: center + offset, radius: 2 });
+        for (c, v) in map.iter() {
+            assert_eq!(moved.get(c + offset), Some(v));
+        }
+        assert_eq!(moved.translated(Coordinate::new() - offset), map);
+    }
+
+    #[test]
+    fn it_reshapes_and_crops_back() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let map = numbered(center, 2);
+        let grown = map.reshaped(MapShape::Hexagon { center, radius: 4 }, 0);
+        assert_eq!(grown.len(), Coordinate::spiral_size(4));
+        assert_eq!(grown.crop(center.spiral(2)), map);
+        let sparse: HexMap<_> = center.ring(2).into_iter().map(|c| (c, 'a')).collect();
+        let filled = sparse.reshaped(MapShape::Hexagon { center, radius: 2 }, 'b');
+        assert_eq!(filled.crop(center.ring(2)), sparse);
+        assert_eq!(filled.reshaped(MapShape::Sparse, 'c').len(), filled.len());
+    }
+
+    #[test]
+    fn it_reshapes_asymmetric_shapes() {
+        // A map along the y axis, reshaped to a hexagon overlapping one end.
+        let map: HexMap<_> = (0..5)
+            .map(|i| (Coordinate::from_axial(0, i), i))
+            .collect();
+        let center = Coordinate::from_axial(1, 4);
+        let reshaped = map.reshaped(MapShape::Hexagon { center, radius: 1 }, -1);
+        assert_eq!(reshaped.len(), 7);
+        assert_eq!(reshaped.get(Coordinate::from_axial(0, 4)), Some(&4));
+        assert_eq!(reshaped.get(Coordinate::from_axial(1, 3)), Some(&-1));
+        assert_eq!(reshaped.get(Coordinate::from_axial(0, 3)), None);
+        let overlap: Vec<_> = reshaped.iter().filter(|&(_, v)| *v >= 0).collect();
+        assert_eq!(overlap, vec![(Coordinate::from_axial(0, 4), &4)]);
+        assert_eq!(reshaped.get(Coordinate::from_axial(0, 5)), Some(&-1));
+    }
+}
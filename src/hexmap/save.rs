@@ -0,0 +1,195 @@
+//! A self-describing save format for maps.
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use hexmap::{hexagon, Cells, HexMap, MapShape};
+use {Coordinate, Layout};
+
+
+/// The version of the `MapDocument` format written by this crate.
+const FORMAT_VERSION: u32 = 1;
+
+/// A map saved together with everything needed to rebuild and draw it.
+///
+/// A MapDocument serializes as a struct with a `version` field, the map's
+/// `shape`, its `layout` or null, and its `values` in canonical order: by
+/// increasing `y` component and then increasing `x` component. A hexagonal
+/// map's shape gives its hexes, so only a sparse map also has a `hexes`
+/// field, listing its hexes in the same order as its values.
+///
+/// Deserializing fails with a descriptive message if the version is newer
+/// than this crate understands, or the values don't fit the shape. Unknown
+/// fields are ignored, so later versions can add fields for older readers
+/// to skip.
+#[derive(Debug,PartialEq,Clone)]
+pub struct MapDocument<T> {
+    /// The map itself.
+    pub map: HexMap<T>,
+    /// How the map is meant to be drawn, if that is saved with it.
+    pub layout: Option<Layout>,
+}
+
+/// The form a `MapDocument` takes under serde.
+#[derive(Serialize, Deserialize)]
+struct RawDocument<V> {
+    version: u32,
+    shape: MapShape,
+    #[serde(default)]
+    layout: Option<Layout>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hexes: Option<Vec<Coordinate>>,
+    values: Vec<V>,
+}
+
+impl<T: Serialize> Serialize for MapDocument<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let coords = self.map.canonical_coordinates();
+        let values = coords.iter().map(|c| &self.map.cells[c]).collect();
+        let hexes = match self.map.shape {
+            MapShape::Hexagon { .. } => None,
+            MapShape::Sparse => Some(coords),
+        };
+        let raw = RawDocument {
+            version: FORMAT_VERSION,
+            shape: self.map.shape,
+            layout: self.layout,
+            hexes,
+            values,
+        };
+        raw.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MapDocument<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawDocument::<T>::deserialize(deserializer)?;
+        if raw.version == 0 || raw.version > FORMAT_VERSION {
+            return Err(de::Error::custom(format!(
+                "Unsupported map format version {}, expected at most {}",
+                raw.version, FORMAT_VERSION,
+            )));
+        }
+        let coords: Vec<Coordinate> = match (raw.shape, raw.hexes) {
+            (MapShape::Hexagon { center, radius }, _) => {
+                // Count the hexagon's hexes before listing them, so a small
+                // document can't claim a huge one. Counting in `u128` can't
+                // overflow for any radius.
+                let expected = match u128::try_from(radius) {
+                    Ok(r) => 1 + 3 * r * (r + 1),
+                    Err(_) => 0,
+                };
+                if expected != raw.values.len() as u128 {
+                    return Err(de::Error::custom(format!(
+                        "Expected {} values for the map's hexes, found {}",
+                        expected, raw.values.len(),
+                    )));
+                }
+                hexagon(center, radius).collect()
+            }
+            (MapShape::Sparse, Some(hexes)) => hexes,
+            (MapShape::Sparse, None) => {
+                return Err(de::Error::custom("Sparse map without a list of hexes"));
+            }
+        };
+        let len = coords.len();
+        if len != raw.values.len() {
+            return Err(de::Error::custom(format!(
+                "Expected {} values for the map's hexes, found {}",
+                len, raw.values.len(),
+            )));
+        }
+        let cells: HashMap<Coordinate, T> = coords.into_iter().zip(raw.values).collect();
+        if cells.len() != len {
+            return Err(de::Error::custom("Duplicate coordinate"));
+        }
+        let map = HexMap { shape: raw.shape, cells: Cells::new(raw.shape, cells) };
+        Ok(MapDocument { map, layout: raw.layout })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{self, json};
+    use Orientation;
+
+    fn layout() -> Layout {
+        Layout::new(Orientation::Flat, (10.0, 12.0), (100.0, 50.0))
+    }
+
+    #[test]
+    fn it_round_trips_hexagonal_maps() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let map = HexMap::from_fn(center.spiral(2), |c| center.distance_to(*c) as u8);
+        let document = MapDocument { map, layout: Some(layout()) };
+        let value = serde_json::to_value(&document).unwrap();
+        assert_eq!(value["version"], json!(1));
+        assert!(value.get("hexes").is_none());
+        let values = value["values"].as_array().unwrap();
+        assert_eq!(values.len(), 19);
+        let expected: Vec<_> = document.map.canonical_coordinates().into_iter()
+            .map(|c| json!(center.distance_to(c)))
+            .collect();
+        assert_eq!(*values, expected);
+        let json = serde_json::to_string(&document).unwrap();
+        assert_eq!(serde_json::from_str::<MapDocument<u8>>(&json).unwrap(), document);
+    }
+
+    #[test]
+    fn it_round_trips_sparse_maps() {
+        let far = Coordinate::at(5, 0, -5).unwrap();
+        let map: HexMap<_> = vec![(far, "b".to_string()), (Coordinate::new(), "a".to_string())]
+            .into_iter()
+            .collect();
+        let document = MapDocument { map, layout: None };
+        let json = serde_json::to_string(&document).unwrap();
+        let expected = r#"{"version":1,"shape":"Sparse","layout":null,"hexes":[[0,0,0],[5,0,-5]],"values":["a","b"]}"#;
+        assert_eq!(json, expected);
+        assert_eq!(serde_json::from_str::<MapDocument<String>>(&json).unwrap(), document);
+    }
+
+    #[test]
+    fn it_rejects_truncated_values() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let document = MapDocument { map: HexMap::hexagon(center, 1, 0u8), layout: None };
+        let mut value = serde_json::to_value(&document).unwrap();
+        value["values"].as_array_mut().unwrap().pop();
+        let error = serde_json::from_value::<MapDocument<u8>>(value).unwrap_err();
+        assert_eq!(error.to_string(), "Expected 7 values for the map's hexes, found 6");
+        let huge = json!({
+            "version": 1,
+            "shape": {"Hexagon": {"center": [0, 0, 0], "radius": i64::MAX}},
+            "values": [1],
+        });
+        let error = serde_json::from_value::<MapDocument<u8>>(huge).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected 255211775190703847569860839463261831169 values for the map's hexes, found 1",
+        );
+        let sparse = json!({"version": 1, "shape": "Sparse", "values": [1]});
+        assert!(serde_json::from_value::<MapDocument<u8>>(sparse).is_err());
+        let twice = json!({
+            "version": 1,
+            "shape": "Sparse",
+            "hexes": [[0, 0, 0], [0, 0, 0]],
+            "values": [1, 2],
+        });
+        assert!(serde_json::from_value::<MapDocument<u8>>(twice).is_err());
+    }
+
+    #[test]
+    fn it_skips_unknown_fields_but_not_unknown_versions() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let document = MapDocument { map: HexMap::hexagon(center, 1, 3u8), layout: Some(layout()) };
+        let mut value = serde_json::to_value(&document).unwrap();
+        value["author"] = json!("someone");
+        value["palette"] = json!({"3": "#00ff00"});
+        assert_eq!(serde_json::from_value::<MapDocument<u8>>(value.clone()).unwrap(), document);
+        value["version"] = json!(2);
+        let error = serde_json::from_value::<MapDocument<u8>>(value).unwrap_err();
+        assert_eq!(error.to_string(), "Unsupported map format version 2, expected at most 1");
+    }
+}
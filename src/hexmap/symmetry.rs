@@ -0,0 +1,337 @@
+//! Rotating, mirroring, and symmetrizing maps, and describing the
+//! symmetries of sets of hexes.
+use std::collections::HashSet;
+
+use hexmap::{Cells, HexMap, MapShape};
+use {Axis, Coordinate, Edge, Vertex};
+
+
+/// A group of rotations and reflections about the origin, under which a map
+/// can be made symmetric with `HexMap::symmetrize`.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub enum Symmetry {
+    /// Rotation by 180 degrees.
+    Rotate180,
+    /// Rotation by multiples of 120 degrees.
+    Rotate120,
+    /// Rotation by multiples of 60 degrees.
+    Rotate60,
+    /// Reflection across an axis.
+    Mirror(Axis),
+    /// Every rotation by multiples of 60 degrees, and every reflection.
+    Full,
+}
+
+impl Symmetry {
+    /// Get every hex that `c` is carried to by this symmetry, starting with
+    /// `c` itself and without repeats.
+    pub fn orbit(self, c: Coordinate) -> Vec<Coordinate> {
+        let rotations = |c: Coordinate, step: usize| {
+            let mut images = vec![c];
+            for _ in (step..6).step_by(step) {
+                let last = images[images.len() - 1];
+                images.push((0..step).fold(last, |c, _| c.rotate_right()));
+            }
+            images
+        };
+        let mut images = match self {
+            Symmetry::Rotate180 => rotations(c, 3),
+            Symmetry::Rotate120 => rotations(c, 2),
+            Symmetry::Rotate60 => rotations(c, 1),
+            Symmetry::Mirror(axis) => vec![c, c.reflect(axis)],
+            Symmetry::Full => {
+                let mut images = rotations(c, 1);
+                images.extend(rotations(c.reflect_x(), 1));
+                images
+            }
+        };
+        let mut seen = HashSet::new();
+        images.retain(|c| seen.insert(*c));
+        images
+    }
+}
+
+impl Coordinate {
+    /// Get every hex this Coordinate is carried to by the rotations and
+    /// reflections about the origin, starting with itself and without
+    /// repeats, as for `Symmetry::Full`.
+    ///
+    /// A hex off every axis of symmetry has 12 images, a hex on one has 6,
+    /// and the origin only itself.
+    pub fn symmetry_orbit(&self) -> Vec<Coordinate> {
+        Symmetry::Full.orbit(*self)
+    }
+}
+
+/// A point that a set of hexes can be symmetric about.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub enum SymmetryCenter {
+    /// The center of a hex.
+    Hex(Coordinate),
+    /// The midpoint of an edge.
+    Edge(Edge),
+    /// A corner where three hexes meet.
+    Vertex(Vertex),
+}
+
+impl SymmetryCenter {
+    /// Get this point in cube coordinates scaled up by 6, in which hexes,
+    /// edges, and corners all have whole components.
+    pub(crate) fn scaled(self) -> Coordinate {
+        let sum = |hexes: &[Coordinate], scale: i64| {
+            let total = hexes.iter().fold(Coordinate::new(), |sum, c| sum + *c);
+            Coordinate::from_axial(total.x() * scale, total.y() * scale)
+        };
+        match self {
+            SymmetryCenter::Hex(c) => sum(&[c], 6),
+            SymmetryCenter::Edge(edge) => {
+                let (a, b) = edge.hexes();
+                sum(&[a, b], 3)
+            }
+            SymmetryCenter::Vertex(vertex) => sum(&vertex.hexes(), 2),
+        }
+    }
+}
+
+/// A set of the twelve symmetries of the grid about a point, as found by
+/// `algorithms::symmetries`.
+///
+/// These are the rotations by each multiple of 60 degrees, including the
+/// identity, and the reflections across six lines through the point: the
+/// three of `Coordinate::reflect`, which each keep one component, and the
+/// three perpendicular to them, which each negate one component.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct SymmetryGroup {
+    /// One bit for each symmetry, numbered as for `transform`.
+    elements: u16,
+}
+
+impl SymmetryGroup {
+    /// Make the set of the symmetries, numbered as for `transform`, that
+    /// `has` is true for.
+    pub(crate) fn from_elements<F: Fn(usize) -> bool>(has: F) -> Self {
+        let elements = (0..12).filter(|&e| has(e)).fold(0, |bits, e| bits | 1 << e);
+        SymmetryGroup { elements }
+    }
+
+    /// Get the number of symmetries in the set. The identity is always a
+    /// symmetry of a set of hexes, so this is at least 1 for those.
+    pub fn order(&self) -> usize {
+        self.elements.count_ones() as usize
+    }
+
+    /// Whether rotating by `steps` sixths of a turn, as for
+    /// `Coordinate::rotate`, is in the set.
+    pub fn has_rotation(&self, steps: i64) -> bool {
+        self.has(steps.rem_euclid(6) as usize)
+    }
+
+    /// Whether reflecting across `axis`, as for `Coordinate::reflect`, is in
+    /// the set.
+    pub fn has_reflection(&self, axis: Axis) -> bool {
+        self.has(match axis {
+            Axis::X => 6,
+            Axis::Y => 10,
+            Axis::Z => 8,
+        })
+    }
+
+    /// Whether reflecting across the line perpendicular to `axis`, which
+    /// negates the `axis` component and swaps and negates the other two, is
+    /// in the set.
+    pub fn has_perpendicular_reflection(&self, axis: Axis) -> bool {
+        self.has(match axis {
+            Axis::X => 9,
+            Axis::Y => 7,
+            Axis::Z => 11,
+        })
+    }
+
+    /// Whether every rotation or reflection of `symmetry` is in the set.
+    pub fn contains(&self, symmetry: Symmetry) -> bool {
+        match symmetry {
+            Symmetry::Rotate180 => self.has_rotation(3),
+            Symmetry::Rotate120 => self.has_rotation(2),
+            Symmetry::Rotate60 => self.has_rotation(1),
+            Symmetry::Mirror(axis) => self.has_reflection(axis),
+            Symmetry::Full => self.order() == 12,
+        }
+    }
+
+    fn has(&self, element: usize) -> bool {
+        self.elements & 1 << element != 0
+    }
+}
+
+/// Apply symmetry number `element` of a `SymmetryGroup` about the origin:
+/// rotation by `element` steps for 0 to 5, or `reflect_x` then rotation by
+/// `element - 6` steps for 6 to 11.
+pub(crate) fn transform(element: usize, c: Coordinate) -> Coordinate {
+    if element < 6 {
+        c.rotate(element as i64)
+    } else {
+        c.reflect_x().rotate(element as i64 - 6)
+    }
+}
+
+impl<T: Clone> HexMap<T> {
+    /// Get a copy of this map rotated about the origin by `steps` sixths of
+    /// a turn, clockwise as viewed in a pointy-top layout. Negative `steps`
+    /// rotate counterclockwise.
+    ///
+    /// A hexagonal map's shape rotates with it, so a map centered on the
+    /// origin keeps the same shape.
+    pub fn rotated(&self, steps: i32) -> HexMap<T> {
+        self.transformed(|c| c.rotate(i64::from(steps)))
+    }
+
+    /// Get a copy of this map reflected across `axis`, with
+    /// `Coordinate::reflect`.
+    ///
+    /// A hexagonal map's shape is reflected with it, as for `rotated`.
+    pub fn mirrored(&self, axis: Axis) -> HexMap<T> {
+        self.transformed(|c| c.reflect(axis))
+    }
+
+    /// Make this map unchanged by `symmetry`, by giving every hex in each
+    /// orbit the value `resolve` makes from their current values.
+    ///
+    /// `resolve` is passed the values in the order of `Symmetry::orbit`,
+    /// starting from the orbit's least hex by `Ord`. Hexes in an orbit
+    /// without values are skipped, and stay without values.
+    pub fn symmetrize<F>(&mut self, symmetry: Symmetry, resolve: F)
+        where F: Fn(&[&T]) -> T
+    {
+        let mut done = HashSet::new();
+        for c in self.canonical_coordinates() {
+            if done.contains(&c) {
+                continue;
+            }
+            let orbit: Vec<Coordinate> = symmetry.orbit(c)
+                .into_iter()
+                .filter(|c| self.cells.contains_key(c))
+                .collect();
+            let value = {
+                let values: Vec<&T> = orbit.iter().map(|c| &self.cells[c]).collect();
+                resolve(&values)
+            };
+            for c in orbit {
+                self.cells.insert(c, value.clone());
+                done.insert(c);
+            }
+        }
+    }
+
+    /// Get a copy of this map with the value at each hex moved to
+    /// `transform` of that hex, where `transform` preserves distances.
+    fn transformed<F: Fn(Coordinate) -> Coordinate>(&self, transform: F) -> HexMap<T> {
+        let shape = match self.shape {
+            MapShape::Hexagon { center, radius } => {
+                MapShape::Hexagon { center: transform(center), radius }
+            }
+            MapShape::Sparse => MapShape::Sparse,
+        };
+        let cells = self.cells.iter().map(|(c, v)| (transform(c), v.clone())).collect();
+        HexMap { shape, cells: Cells::new(shape, cells) }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(center: Coordinate, radius: i64) -> HexMap<usize> {
+        let mut map = HexMap::hexagon(center, radius, 0);
+        for (i, c) in center.spiral(radius).into_iter().enumerate() {
+            map.insert(c, i).unwrap();
+        }
+        map
+    }
+
+    #[test]
+    fn it_round_trips_six_rotations() {
+        let map = numbered(Coordinate::new(), 3);
+        let mut rotated = map.clone();
+        for _ in 0..6 {
+            rotated = rotated.rotated(1);
+            assert_eq!(rotated.shape(), map.shape());
+        }
+        assert_eq!(rotated, map);
+        assert_eq!(map.rotated(-1), map.rotated(5));
+        assert_eq!(map.rotated(2).rotated(-2), map);
+    }
+
+    #[test]
+    fn it_moves_values_with_rotations() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let map = numbered(center, 2);
+        let rotated = map.rotated(1);
+        let shape = MapShape::Hexagon { center: center.rotate_right(), radius: 2 };
+        assert_eq!(rotated.shape(), shape);
+        for (c, v) in map.iter() {
+            assert_eq!(rotated.get(c.rotate_right()), Some(v));
+        }
+    }
+
+    #[test]
+    fn it_round_trips_two_mirrorings() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let map = numbered(center, 2);
+        for axis in [Axis::X, Axis::Y, Axis::Z].iter() {
+            let mirrored = map.mirrored(*axis);
+            assert_ne!(mirrored, map);
+            assert_eq!(mirrored.get(center.reflect(*axis)), map.get(center));
+            assert_eq!(mirrored.mirrored(*axis), map);
+        }
+        let sparse: HexMap<_> = vec![(center, 'a')].into_iter().collect();
+        assert_eq!(sparse.mirrored(Axis::X).get(center.reflect_x()), Some(&'a'));
+    }
+
+    #[test]
+    fn it_makes_orbits_uniform() {
+        let symmetries = [
+            Symmetry::Rotate180,
+            Symmetry::Rotate120,
+            Symmetry::Rotate60,
+            Symmetry::Mirror(Axis::Y),
+            Symmetry::Full,
+        ];
+        for symmetry in symmetries.iter() {
+            let mut map = numbered(Coordinate::new(), 3);
+            map.symmetrize(*symmetry, |values| values.iter().map(|v| **v).max().unwrap());
+            for (c, v) in map.iter() {
+                for image in symmetry.orbit(c) {
+                    assert_eq!(map.get(image), Some(v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_finds_orbits() {
+        let c = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(Symmetry::Rotate180.orbit(c), vec![c, c.reflect_across(Coordinate::new())]);
+        assert_eq!(Symmetry::Rotate120.orbit(c).len(), 3);
+        assert_eq!(Symmetry::Rotate60.orbit(c).len(), 6);
+        assert_eq!(Symmetry::Full.orbit(c).len(), 12);
+        assert_eq!(Symmetry::Full.orbit(Coordinate::new()), vec![Coordinate::new()]);
+        let on_axis = Coordinate::at(2, -1, -1).unwrap();
+        assert_eq!(Symmetry::Mirror(Axis::X).orbit(on_axis), vec![on_axis]);
+        assert_eq!(Symmetry::Full.orbit(on_axis).len(), 6);
+    }
+
+    #[test]
+    fn it_finds_the_full_orbit_of_a_hex() {
+        let origin = Coordinate::new();
+        assert_eq!(origin.symmetry_orbit(), vec![origin]);
+        let c = Coordinate::at(-3, -1, 4).unwrap();
+        let orbit = c.symmetry_orbit();
+        assert_eq!(orbit[0], c);
+        assert_eq!(orbit.iter().collect::<HashSet<_>>().len(), 12);
+        assert!(orbit.iter().all(|image| image.distance_to(origin) == c.distance_to(origin)));
+        assert!(orbit.contains(&c.rotate_left()));
+        assert!(orbit.contains(&c.reflect(Axis::Z)));
+        assert_eq!(Coordinate::at(2, -1, -1).unwrap().symmetry_orbit().len(), 6);
+    }
+}
@@ -0,0 +1,300 @@
+//! Sets of hexes within a fixed hexagon, stored as bits.
+use algorithms::bounding_hexagon;
+use hexset::HexSet;
+use {Coordinate, HexError};
+
+
+/// What a `BoundedHexSet` does with hexes outside its bounds.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub enum OutOfBounds {
+    /// Fail with `HexError::OutsideShape`.
+    Reject,
+    /// Do nothing, as if the hex were already absent.
+    Ignore,
+}
+
+/// A set of hexes within `radius` of a fixed `center`, stored as one bit per
+/// hex in the hexagon.
+///
+/// This takes far less memory than a `HexSet` when most of the hexagon is
+/// in the set, and `union_with` and `intersect_with` work on 64 hexes at a
+/// time. Hexes are numbered in rows, by increasing `y` and then increasing
+/// `x` component, which is also the order they are iterated in.
+#[derive(Debug,PartialEq,Eq,Clone)]
+pub struct BoundedHexSet {
+    center: Coordinate,
+    radius: i64,
+    outside: OutOfBounds,
+    /// The index of the first hex in each row, plus the total.
+    rows: Vec<usize>,
+    words: Vec<u64>,
+}
+
+impl BoundedHexSet {
+    /// Create an empty set bounded by the hexagon within `radius` of
+    /// `center`, which rejects hexes outside it.
+    pub fn new(center: Coordinate, radius: i64) -> Self {
+        BoundedHexSet::with_policy(center, radius, OutOfBounds::Reject)
+    }
+
+    /// Create an empty set bounded by the hexagon within `radius` of
+    /// `center`, which handles hexes outside it according to `outside`.
+    pub fn with_policy(center: Coordinate, radius: i64, outside: OutOfBounds) -> Self {
+        let mut rows = vec![0];
+        for dy in -radius..radius + 1 {
+            let len = (2 * radius + 1 - dy.abs()) as usize;
+            let start = rows[rows.len() - 1];
+            rows.push(start + len);
+        }
+        let words = vec![0; rows[rows.len() - 1].div_ceil(64)];
+        BoundedHexSet { center, radius, outside, rows, words }
+    }
+
+    /// Get the center of the bounds.
+    pub fn center(&self) -> Coordinate {
+        self.center
+    }
+
+    /// Get the radius of the bounds.
+    pub fn radius(&self) -> i64 {
+        self.radius
+    }
+
+    /// Whether a hex is within the bounds, whether or not it is in the set.
+    pub fn in_bounds(&self, c: Coordinate) -> bool {
+        self.center.distance_to(c) <= self.radius
+    }
+
+    /// Get the number of hexes in the set.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Whether the set has no hexes.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Whether a hex is in the set. Hexes outside the bounds never are.
+    pub fn contains(&self, c: Coordinate) -> bool {
+        self.index(c).is_some_and(|i| self.words[i / 64] & (1 << (i % 64)) != 0)
+    }
+
+    /// Add a hex to the set, returning whether it was not already there.
+    ///
+    /// A hex outside the bounds gives `HexError::OutsideShape`, or is not
+    /// added and gives `false`, depending on the set's `OutOfBounds` policy.
+    pub fn insert(&mut self, c: Coordinate) -> Result<bool, HexError> {
+        let i = match self.index(c) {
+            Some(i) => i,
+            None => return self.outside(),
+        };
+        let bit = 1 << (i % 64);
+        let added = self.words[i / 64] & bit == 0;
+        self.words[i / 64] |= bit;
+        Ok(added)
+    }
+
+    /// Remove a hex from the set, returning whether it was there.
+    ///
+    /// A hex outside the bounds is handled as for `insert`.
+    pub fn remove(&mut self, c: Coordinate) -> Result<bool, HexError> {
+        let i = match self.index(c) {
+            Some(i) => i,
+            None => return self.outside(),
+        };
+        let bit = 1 << (i % 64);
+        let removed = self.words[i / 64] & bit != 0;
+        self.words[i / 64] &= !bit;
+        Ok(removed)
+    }
+
+    /// Add every hex in `other` to this set.
+    ///
+    /// Both sets must have the same bounds, or this fails with
+    /// `HexError::InvalidDimensions` and leaves this set unchanged.
+    pub fn union_with(&mut self, other: &BoundedHexSet) -> Result<(), HexError> {
+        self.check_bounds(other)?;
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= *other;
+        }
+        Ok(())
+    }
+
+    /// Remove every hex not in `other` from this set.
+    ///
+    /// Both sets must have the same bounds, as for `union_with`.
+    pub fn intersect_with(&mut self, other: &BoundedHexSet) -> Result<(), HexError> {
+        self.check_bounds(other)?;
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word &= *other;
+        }
+        Ok(())
+    }
+
+    /// Iterate over the hexes in the set, by increasing `y` and then
+    /// increasing `x` component.
+    pub fn iter(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.words.iter().enumerate().flat_map(move |(w, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| self.coordinate(w * 64 + bit))
+        })
+    }
+
+    /// Get the bit index of a hex, if it is within the bounds.
+    fn index(&self, c: Coordinate) -> Option<usize> {
+        if !self.in_bounds(c) {
+            return None;
+        }
+        let d = c - self.center;
+        let first = (-self.radius).max(-self.radius - d.y());
+        Some(self.rows[(d.y() + self.radius) as usize] + (d.x() - first) as usize)
+    }
+
+    /// Get the hex with a given bit index.
+    fn coordinate(&self, i: usize) -> Coordinate {
+        let row = self.rows.partition_point(|&start| start <= i) - 1;
+        let dy = row as i64 - self.radius;
+        let dx = (-self.radius).max(-self.radius - dy) + (i - self.rows[row]) as i64;
+        self.center + Coordinate::from_axial(dx, dy)
+    }
+
+    fn outside(&self) -> Result<bool, HexError> {
+        match self.outside {
+            OutOfBounds::Reject => Err(HexError::OutsideShape),
+            OutOfBounds::Ignore => Ok(false),
+        }
+    }
+
+    fn check_bounds(&self, other: &BoundedHexSet) -> Result<(), HexError> {
+        if (self.center, self.radius) == (other.center, other.radius) {
+            Ok(())
+        } else {
+            Err(HexError::InvalidDimensions("Sets have different bounds"))
+        }
+    }
+}
+
+impl<'a> From<&'a BoundedHexSet> for HexSet {
+    fn from(set: &'a BoundedHexSet) -> Self {
+        set.iter().collect()
+    }
+}
+
+impl From<BoundedHexSet> for HexSet {
+    fn from(set: BoundedHexSet) -> Self {
+        HexSet::from(&set)
+    }
+}
+
+impl<'a> From<&'a HexSet> for BoundedHexSet {
+    /// Store a HexSet in the smallest bounds that hold it, as found by
+    /// `algorithms::bounding_hexagon`. An empty set gets bounds of radius 0
+    /// around the origin.
+    fn from(set: &'a HexSet) -> Self {
+        let (center, radius) = bounding_hexagon(set.iter().cloned())
+            .unwrap_or((Coordinate::new(), 0));
+        let mut bounded = BoundedHexSet::new(center, i64::from(radius));
+        for c in set {
+            bounded.insert(*c).expect("the bounding hexagon holds every hex");
+        }
+        bounded
+    }
+}
+
+impl From<HexSet> for BoundedHexSet {
+    fn from(set: HexSet) -> Self {
+        BoundedHexSet::from(&set)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_holds_hexes_up_to_its_boundary() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let mut set = BoundedHexSet::new(center, 3);
+        for c in center.spiral(3) {
+            assert_eq!(set.insert(c), Ok(true));
+            assert_eq!(set.insert(c), Ok(false));
+        }
+        assert_eq!(set.count_ones(), Coordinate::spiral_size(3));
+        assert!(center.ring(3).into_iter().all(|c| set.contains(c)));
+        for c in center.ring(3) {
+            assert_eq!(set.remove(c), Ok(true));
+            assert_eq!(set.remove(c), Ok(false));
+        }
+        assert_eq!(set.count_ones(), Coordinate::spiral_size(2));
+    }
+
+    #[test]
+    fn it_handles_hexes_out_of_bounds() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let outside = center + Coordinate::at(4, -4, 0).unwrap();
+        let mut rejecting = BoundedHexSet::new(center, 3);
+        assert_eq!(rejecting.insert(outside), Err(HexError::OutsideShape));
+        assert_eq!(rejecting.remove(outside), Err(HexError::OutsideShape));
+        let mut ignoring = BoundedHexSet::with_policy(center, 3, OutOfBounds::Ignore);
+        assert_eq!(ignoring.insert(outside), Ok(false));
+        assert_eq!(ignoring.remove(outside), Ok(false));
+        assert!(!ignoring.contains(outside));
+        assert!(ignoring.is_empty() && rejecting.is_empty());
+        let mut empty = BoundedHexSet::new(center, -1);
+        assert_eq!(empty.insert(center), Err(HexError::OutsideShape));
+        assert_eq!(empty.iter().count(), 0);
+    }
+
+    #[test]
+    fn it_iterates_in_row_order() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let mut set = BoundedHexSet::new(center, 4);
+        let mut expected = center.ring(4);
+        expected.extend(center.ring(1));
+        for c in expected.iter() {
+            set.insert(*c).unwrap();
+        }
+        expected.sort();
+        assert_eq!(set.iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn it_combines_sets_across_word_boundaries() {
+        // A radius of 5 has 91 hexes, so the second word is partly used.
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let mut a = BoundedHexSet::new(center, 5);
+        let mut b = BoundedHexSet::new(center, 5);
+        let in_a: HexSet = center.spiral(5).into_iter().filter(|c| c.x() % 2 == 0).collect();
+        let in_b: HexSet = center.spiral(5).into_iter().filter(|c| c.y() % 3 != 0).collect();
+        for c in in_a.iter() {
+            a.insert(*c).unwrap();
+        }
+        for c in in_b.iter() {
+            b.insert(*c).unwrap();
+        }
+        let mut union = a.clone();
+        union.union_with(&b).unwrap();
+        assert_eq!(HexSet::from(&union), &in_a | &in_b);
+        let mut intersection = a.clone();
+        intersection.intersect_with(&b).unwrap();
+        assert_eq!(HexSet::from(&intersection), &in_a & &in_b);
+        assert_eq!(intersection.count_ones(), (&in_a & &in_b).len());
+        let other = BoundedHexSet::new(center, 4);
+        assert!(a.union_with(&other).is_err());
+        assert!(a.intersect_with(&other).is_err());
+    }
+
+    #[test]
+    fn it_converts_to_and_from_hash_sets() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let ring: HexSet = center.ring(3).into_iter().collect();
+        let bounded = BoundedHexSet::from(&ring);
+        assert_eq!((bounded.center(), bounded.radius()), (center, 3));
+        assert_eq!(HexSet::from(bounded), ring);
+        let empty = BoundedHexSet::from(HexSet::new());
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+//! Sets of hexes, with set algebra and morphology.
+use std::collections::hash_set;
+use std::collections::HashSet;
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, Sub};
+
+use Coordinate;
+
+mod bounded;
+
+pub use self::bounded::{BoundedHexSet, OutOfBounds};
+
+
+/// A set of hexes.
+///
+/// Besides the usual set operations, written with the `|`, `&`, and `-`
+/// operators on references as for `HashSet`, a HexSet can be grown with
+/// `expand` and worn away with `shrink`.
+#[derive(Debug,PartialEq,Eq,Clone,Default)]
+pub struct HexSet {
+    hexes: HashSet<Coordinate>,
+}
+
+impl HexSet {
+    /// Create an empty HexSet.
+    pub fn new() -> Self {
+        HexSet { hexes: HashSet::new() }
+    }
+
+    /// Get the number of hexes in the set.
+    pub fn len(&self) -> usize {
+        self.hexes.len()
+    }
+
+    /// Whether the set has no hexes.
+    pub fn is_empty(&self) -> bool {
+        self.hexes.is_empty()
+    }
+
+    /// Whether a hex is in the set.
+    pub fn contains(&self, c: Coordinate) -> bool {
+        self.hexes.contains(&c)
+    }
+
+    /// Add a hex to the set, returning whether it was not already there.
+    pub fn insert(&mut self, c: Coordinate) -> bool {
+        self.hexes.insert(c)
+    }
+
+    /// Remove a hex from the set, returning whether it was there.
+    pub fn remove(&mut self, c: Coordinate) -> bool {
+        self.hexes.remove(&c)
+    }
+
+    /// Iterate over the hexes in the set, in no particular order.
+    pub fn iter(&self) -> hash_set::Iter<'_, Coordinate> {
+        self.hexes.iter()
+    }
+
+    /// Get the hexes outside the set that are adjacent to a hex in it.
+    pub fn neighbors(&self) -> HexSet {
+        outline(self.hexes.iter(), |c| self.hexes.contains(&c))
+    }
+
+    /// Get every hex within `n` of a hex in the set, i.e. the set grown by
+    /// `n` rings.
+    ///
+    /// `expand(1)` is the set together with its `neighbors`. Each ring is
+    /// found from the one before, so this takes time in proportion to the
+    /// size of the result.
+    pub fn expand(&self, n: u32) -> HexSet {
+        let mut grown = self.clone();
+        let mut frontier = self.clone();
+        for _ in 0..n {
+            frontier = outline(frontier.iter(), |c| grown.hexes.contains(&c));
+            grown.hexes.extend(frontier.iter().cloned());
+        }
+        grown
+    }
+
+    /// Get the hexes in the set whose every hex within `n` is also in the
+    /// set, i.e. the set worn away by `n` rings.
+    ///
+    /// `shrink(n)` followed by `expand(n)` never gives a hex that wasn't in
+    /// the original set.
+    pub fn shrink(&self, n: u32) -> HexSet {
+        let mut worn = self.clone();
+        for _ in 0..n {
+            let edge: Vec<Coordinate> = worn.iter()
+                .filter(|c| c.neighbors().iter().any(|n| !worn.hexes.contains(n)))
+                .cloned()
+                .collect();
+            if edge.is_empty() {
+                break;
+            }
+            for c in edge {
+                worn.hexes.remove(&c);
+            }
+        }
+        worn
+    }
+}
+
+/// Get the hexes adjacent to `hexes` for which `inside` is false.
+fn outline<'a, I, F>(hexes: I, inside: F) -> HexSet
+    where I: Iterator<Item = &'a Coordinate>,
+          F: Fn(Coordinate) -> bool
+{
+    hexes
+        .flat_map(|c| c.neighbors().to_vec())
+        .filter(|&c| !inside(c))
+        .collect()
+}
+
+impl FromIterator<Coordinate> for HexSet {
+    fn from_iter<I: IntoIterator<Item = Coordinate>>(iter: I) -> Self {
+        HexSet { hexes: iter.into_iter().collect() }
+    }
+}
+
+impl Extend<Coordinate> for HexSet {
+    fn extend<I: IntoIterator<Item = Coordinate>>(&mut self, iter: I) {
+        self.hexes.extend(iter)
+    }
+}
+
+impl IntoIterator for HexSet {
+    type Item = Coordinate;
+    type IntoIter = hash_set::IntoIter<Coordinate>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hexes.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a HexSet {
+    type Item = &'a Coordinate;
+    type IntoIter = hash_set::Iter<'a, Coordinate>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.hexes.iter()
+    }
+}
+
+impl BitOr<&HexSet> for &HexSet {
+    type Output = HexSet;
+
+    /// Get the hexes in either set.
+    fn bitor(self, other: &HexSet) -> HexSet {
+        HexSet { hexes: &self.hexes | &other.hexes }
+    }
+}
+
+impl BitAnd<&HexSet> for &HexSet {
+    type Output = HexSet;
+
+    /// Get the hexes in both sets.
+    fn bitand(self, other: &HexSet) -> HexSet {
+        HexSet { hexes: &self.hexes & &other.hexes }
+    }
+}
+
+impl Sub<&HexSet> for &HexSet {
+    type Output = HexSet;
+
+    /// Get the hexes in this set but not `other`.
+    fn sub(self, other: &HexSet) -> HexSet {
+        HexSet { hexes: &self.hexes - &other.hexes }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shapes() -> Vec<HexSet> {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let line = center.line_to(Coordinate::at(2, 7, -9).unwrap());
+        let donut = center.spiral(4).into_iter().filter(|c| center.distance_to(*c) != 2);
+        vec![
+            HexSet::new(),
+            vec![center].into_iter().collect(),
+            center.spiral(3).into_iter().collect(),
+            line.into_iter().collect(),
+            donut.collect(),
+        ]
+    }
+
+    #[test]
+    fn it_inserts_and_removes_hexes() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let mut set = HexSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(a));
+        assert!(!set.insert(a));
+        assert!(set.contains(a));
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![&a]);
+        assert!(set.remove(a));
+        assert!(!set.remove(a));
+        set.extend(a.ring(1));
+        assert_eq!(set.len(), 6);
+    }
+
+    #[test]
+    fn it_combines_sets_with_operators() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let a: HexSet = center.spiral(2).into_iter().collect();
+        let b: HexSet = center.ring(2).into_iter().chain(center.ring(3)).collect();
+        assert_eq!(&a | &b, center.spiral(3).into_iter().collect());
+        assert_eq!(&a & &b, center.ring(2).into_iter().collect());
+        assert_eq!(&a - &b, center.spiral(1).into_iter().collect());
+        assert_eq!(&b - &a, center.ring(3).into_iter().collect());
+    }
+
+    #[test]
+    fn it_outlines_a_set() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let hexagon: HexSet = center.spiral(2).into_iter().collect();
+        assert_eq!(hexagon.neighbors(), center.ring(3).into_iter().collect());
+        assert_eq!(HexSet::new().neighbors(), HexSet::new());
+    }
+
+    #[test]
+    fn it_expands_by_rings() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let single: HexSet = vec![center].into_iter().collect();
+        assert_eq!(single.expand(3), center.spiral(3).into_iter().collect());
+        assert_eq!(single.expand(0), single);
+        for shape in shapes() {
+            assert_eq!(shape.expand(1), &shape | &shape.neighbors());
+            assert_eq!(shape.expand(2), shape.expand(1).expand(1));
+        }
+    }
+
+    #[test]
+    fn it_shrinks_by_rings() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let hexagon: HexSet = center.spiral(3).into_iter().collect();
+        assert_eq!(hexagon.shrink(2), center.spiral(1).into_iter().collect());
+        assert_eq!(hexagon.shrink(4), HexSet::new());
+        for shape in shapes() {
+            for n in 0..3 {
+                let opened = shape.shrink(n).expand(n);
+                assert_eq!(&opened - &shape, HexSet::new());
+                let closed = shape.expand(n).shrink(n);
+                assert_eq!(&shape - &closed, HexSet::new());
+            }
+        }
+    }
+}
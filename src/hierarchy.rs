@@ -0,0 +1,110 @@
+//! Grouping hexes into super-hexes of seven, level by level.
+use {Coordinate, DIRECTIONS};
+
+
+impl Coordinate {
+    /// Get the super-hex containing this hex `level` levels up.
+    ///
+    /// Each super-hex at level 1 is a hex and its six neighbors, and these
+    /// groups tile the grid. Their centers form a grid of their own, rotated
+    /// and `sqrt(7)` times larger, and a super-hex's coordinate is its place
+    /// in that grid, so grouping the super-hexes in turn gives level 2, and
+    /// so on. `parent(2)` is therefore `parent(1).parent(1)`, and level 0 is
+    /// the hex itself.
+    ///
+    /// The super-hex at the origin is centered on the origin, and the one at
+    /// `(1, 0, -1)` on `(2, 1, -3)`. Neighboring hexes always have parents
+    /// that are the same or neighbors, at every level.
+    pub fn parent(&self, level: u32) -> Coordinate {
+        (0..level).fold(*self, |c, _| {
+            let center = c.iter_neighbors()
+                .chain(Some(c))
+                .find(|n| (3 * n.x + n.y).rem_euclid(7) == 0)
+                .expect("every hex is within one step of a super-hex center");
+            Coordinate::from_axial((3 * center.x + center.y) / 7, (2 * center.y - center.x) / 7)
+        })
+    }
+
+    /// Iterate over the `7^level` hexes in this super-hex at `level`, as for
+    /// `parent`.
+    ///
+    /// Each run of seven consecutive hexes makes up one super-hex a level
+    /// down: its center, then the center's `neighbors` in order.
+    pub fn children(&self, level: u32) -> impl Iterator<Item = Coordinate> {
+        let center = (0..level).fold(*self, |c, _| c.child_center());
+        let count = 7u64.pow(level);
+        (0..count).map(move |index| {
+            let mut place = count;
+            let mut offset = Coordinate::new();
+            for _ in 0..level {
+                place /= 7;
+                let step = match (index / place) % 7 {
+                    0 => Coordinate::new(),
+                    digit => DIRECTIONS[digit as usize - 1],
+                };
+                offset = offset.child_center() + step;
+            }
+            center + offset
+        })
+    }
+
+    /// Get the center, a level down, of the super-hex with this coordinate.
+    fn child_center(self) -> Coordinate {
+        Coordinate::from_axial(2 * self.x - self.y, self.x + 3 * self.y)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use std::collections::HashMap;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_partitions_hexes_into_super_hexes() {
+        let mut claimed: HashMap<Coordinate, usize> = HashMap::new();
+        for parent in Coordinate::new().spiral(12) {
+            for child in parent.children(1) {
+                *claimed.entry(child).or_default() += 1;
+            }
+        }
+        assert!(claimed.values().all(|&n| n == 1));
+        for c in Coordinate::new().spiral(20) {
+            assert_eq!(claimed.get(&c), Some(&1));
+        }
+        for c in Coordinate::new().spiral(12) {
+            assert_eq!(c.parent(1).children(1).filter(|child| *child == c).count(), 1);
+        }
+    }
+
+    #[test]
+    fn it_round_trips_children_and_parents() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        for level in 0..4 {
+            let children: Vec<_> = coord.children(level).collect();
+            assert_eq!(children.len(), 7usize.pow(level));
+            assert!(children.iter().all(|child| child.parent(level) == coord));
+        }
+        assert_eq!(coord.children(1).count(), 7);
+        assert_eq!(coord.children(2).count(), 49);
+        assert_eq!(coord.children(0).collect::<Vec<_>>(), vec![coord]);
+        let center = Coordinate::at(1, 0, -1).unwrap().children(1).next();
+        assert_eq!(center, Some(Coordinate::at(2, 1, -3).unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_composes_parents_across_levels() {
+        for c in Coordinate::at(-3, -1, 4).unwrap().spiral(15) {
+            assert_eq!(c.parent(2), c.parent(1).parent(1));
+            assert_eq!(c.parent(3), c.parent(2).parent(1));
+            assert_eq!(c.parent(0), c);
+            for level in 1..3 {
+                let parent = c.parent(level);
+                assert!(c.iter_neighbors().all(|n| n.parent(level).distance_to(parent) <= 1));
+            }
+        }
+    }
+}
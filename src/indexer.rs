@@ -0,0 +1,131 @@
+//! Packing the hexes of a hexagon into a flat array.
+use Coordinate;
+
+
+/// A numbering of the hexes within `radius` of the origin as `0..len()`,
+/// for storing data about them in a flat `Vec` or array.
+///
+/// Hexes are numbered in canonical order, by increasing `y` component and
+/// then increasing `x` component, which is the order of `Coordinate`'s
+/// `Ord` and of a hexagonal `HexMap`'s `as_slice`. Each row of the hexagon
+/// is therefore contiguous. To number a hexagon around another center,
+/// subtract the center first. A negative radius numbers no hexes.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct HexagonIndexer {
+    radius: i64,
+}
+
+impl HexagonIndexer {
+    /// Create a HexagonIndexer for the hexes within `radius` of the origin.
+    pub fn new(radius: i64) -> HexagonIndexer {
+        HexagonIndexer { radius: radius.max(-1) }
+    }
+
+    /// Get the radius of the hexagon.
+    pub fn radius(&self) -> i64 {
+        self.radius
+    }
+
+    /// Get the number of hexes in the hexagon.
+    pub fn len(&self) -> usize {
+        if self.is_empty() {
+            0
+        } else {
+            self.row_start(self.radius + 1) as usize
+        }
+    }
+
+    /// Whether the hexagon has no hexes, i.e. its radius is negative.
+    pub fn is_empty(&self) -> bool {
+        self.radius < 0
+    }
+
+    /// Get the index of a hex, or `None` if it is outside the hexagon.
+    pub fn index(&self, c: Coordinate) -> Option<usize> {
+        if c.distance_to(Coordinate::new()) > self.radius {
+            return None;
+        }
+        let x_min = (-self.radius).max(-c.y() - self.radius);
+        Some((self.row_start(c.y()) + c.x() - x_min) as usize)
+    }
+
+    /// Get the hex with an index, or `None` if the index is `len()` or more.
+    pub fn coordinate(&self, index: usize) -> Option<Coordinate> {
+        if index >= self.len() {
+            return None;
+        }
+        let index = index as i64;
+        // Find the last row starting at or before the index.
+        let (mut low, mut high) = (-self.radius, self.radius);
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            if self.row_start(mid) <= index {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        let x_min = (-self.radius).max(-low - self.radius);
+        Some(Coordinate::from_axial(x_min + index - self.row_start(low), low))
+    }
+
+    /// Get the index of the first hex in the row with `y` component `y`,
+    /// for `y` from `-radius` to `radius + 1`.
+    fn row_start(&self, y: i64) -> i64 {
+        let r = self.radius;
+        // The number of hexes in the first n rows, which grow by one each.
+        let top = |n: i64| n * (r + 1) + n * (n - 1) / 2;
+        if y <= 0 {
+            top(y + r)
+        } else {
+            // The rows from y on mirror the first r - y + 1 rows.
+            3 * r * (r + 1) + 1 - top(r - y + 1)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_numbers_every_hex_once() {
+        for radius in 0..8 {
+            let indexer = HexagonIndexer::new(radius);
+            assert_eq!(indexer.len(), Coordinate::spiral_size(radius));
+            for index in 0..indexer.len() {
+                let c = indexer.coordinate(index).unwrap();
+                assert!(c.distance_to(Coordinate::new()) <= radius);
+                assert_eq!(indexer.index(c), Some(index));
+            }
+            assert_eq!(indexer.coordinate(indexer.len()), None);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_numbers_hexes_in_canonical_order() {
+        let indexer = HexagonIndexer::new(5);
+        let mut hexes = Coordinate::new().spiral(5);
+        hexes.sort();
+        for (i, c) in hexes.into_iter().enumerate() {
+            assert_eq!(indexer.index(c), Some(i));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_leaves_hexes_outside_unnumbered() {
+        let indexer = HexagonIndexer::new(3);
+        for c in Coordinate::new().ring(4).into_iter().chain(Coordinate::new().ring(7)) {
+            assert_eq!(indexer.index(c), None);
+        }
+        let empty = HexagonIndexer::new(-3);
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.index(Coordinate::new()), None);
+        assert_eq!(empty.coordinate(0), None);
+        assert_eq!(HexagonIndexer::new(0).index(Coordinate::new()), Some(0));
+    }
+}
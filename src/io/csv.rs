@@ -0,0 +1,268 @@
+//! Coordinate-keyed data as CSV, e.g. for editing in a spreadsheet.
+//!
+//! Rows hold either axial `q,r,value` or cube `x,y,z,value` columns. Values
+//! may be quoted, in which case they can contain commas, doubled quotes, and
+//! line breaks.
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+
+use Coordinate;
+
+
+/// An error reading CSV data.
+#[derive(Debug)]
+pub enum CsvError {
+    /// The underlying reader failed.
+    Io(io::Error),
+    /// A row couldn't be parsed. Lines are numbered from 1, and a row whose
+    /// quoted value spans several lines is reported at its first.
+    Parse { line: usize, message: &'static str },
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CsvError::Io(ref err) => write!(f, "I/O error: {}", err),
+            CsvError::Parse { line, message } => write!(f, "Line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for CsvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            CsvError::Io(ref err) => Some(err),
+            CsvError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(err: io::Error) -> Self {
+        CsvError::Io(err)
+    }
+}
+
+/// Write `data` as `q,r,value` rows after a header row.
+///
+/// Rows are sorted by `r` and then `q`, so the output doesn't depend on the
+/// order of `data`. Values are quoted when they contain commas, quotes, or
+/// line breaks.
+pub fn write_csv<W, T, I>(mut w: W, data: I) -> io::Result<()>
+    where W: Write,
+          T: Display,
+          I: IntoIterator<Item = (Coordinate, T)>
+{
+    let mut rows: Vec<_> = data.into_iter().collect();
+    rows.sort_by_key(|&(coord, _)| coord);
+    writeln!(w, "q,r,value")?;
+    for (coord, value) in rows {
+        let (q, r) = coord.to_axial();
+        let value = value.to_string();
+        if value.contains([',', '"', '\n', '\r']) {
+            writeln!(w, "{},{},\"{}\"", q, r, value.replace('"', "\"\""))?;
+        } else {
+            writeln!(w, "{},{},{}", q, r, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read `q,r,value` or `x,y,z,value` rows.
+///
+/// A header row is optional; if present, it decides between axial and cube
+/// columns, and otherwise the number of columns does. Blank lines are
+/// skipped, and both LF and CRLF line endings are accepted. Line breaks
+/// within quoted values are kept as they are.
+pub fn read_csv<R, T>(r: R) -> Result<Vec<(Coordinate, T)>, CsvError>
+    where R: Read,
+          T: FromStr
+{
+    let mut results = vec![];
+    let mut cube = None;
+    let mut reader = BufReader::new(r);
+    let mut lines_read = 0;
+    loop {
+        let mut row = String::new();
+        if reader.read_line(&mut row)? == 0 {
+            break;
+        }
+        lines_read += 1;
+        let number = lines_read;
+        let error = |message| CsvError::Parse { line: number, message };
+        if row.trim().is_empty() {
+            continue;
+        }
+        // A quoted value can span lines, so read on until every quote closes.
+        let fields = loop {
+            if let Some(fields) = split_row(strip_line_ending(&row)) {
+                break fields;
+            }
+            if reader.read_line(&mut row)? == 0 {
+                return Err(error("Unterminated quoted value"));
+            }
+            lines_read += 1;
+        };
+        if results.is_empty() && cube.is_none() && fields[0].trim().parse::<i64>().is_err() {
+            let names: Vec<String> = fields.iter().map(|f| f.trim().to_lowercase()).collect();
+            let names: Vec<&str> = names.iter().map(String::as_str).collect();
+            cube = match names[..] {
+                ["q", "r", _] => Some(false),
+                ["x", "y", "z", _] => Some(true),
+                _ => return Err(error("Unrecognized header")),
+            };
+            continue;
+        }
+        let is_cube = *cube.get_or_insert(fields.len() == 4);
+        let expected = if is_cube { 4 } else { 3 };
+        if fields.len() != expected {
+            return Err(error("Wrong number of columns"));
+        }
+        let mut components = vec![];
+        for field in &fields[..expected - 1] {
+            let component = field.trim().parse::<i64>()
+                .map_err(|_| error("Invalid coordinate component"))?;
+            components.push(component);
+        }
+        let coord = if is_cube {
+            Coordinate::at(components[0], components[1], components[2])
+                .map_err(|_| error("Invalid cubic coordinates"))?
+        } else {
+            Coordinate::from_axial(components[0], components[1])
+        };
+        let value = fields[expected - 1].parse::<T>().map_err(|_| error("Invalid value"))?;
+        results.push((coord, value));
+    }
+    Ok(results)
+}
+
+/// Remove the LF or CRLF ending a line, if it has one.
+fn strip_line_ending(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// Split a row into fields, unquoting quoted ones. Returns `None` if a quote
+/// is left open.
+fn split_row(line: &str) -> Option<Vec<String>> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            (c, _) => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data() -> Vec<(Coordinate, String)> {
+        vec![
+            (Coordinate::at(2, 7, -9).unwrap(), "forest".to_string()),
+            (Coordinate::at(-3, -1, 4).unwrap(), "hills, rocky".to_string()),
+            (Coordinate::new(), "a \"big\" lake".to_string()),
+        ]
+    }
+
+    #[test]
+    fn it_writes_rows_in_a_deterministic_order() {
+        let mut output = vec![];
+        write_csv(&mut output, data()).unwrap();
+        let expected = "q,r,value\n-3,-1,\"hills, rocky\"\n0,0,\"a \"\"big\"\" lake\"\n2,7,forest\n";
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn it_round_trips_data() {
+        let mut output = vec![];
+        write_csv(&mut output, data()).unwrap();
+        let mut read: Vec<(Coordinate, String)> = read_csv(&output[..]).unwrap();
+        let mut expected = data();
+        read.sort_by_key(|&(c, _)| c.to_axial());
+        expected.sort_by_key(|&(c, _)| c.to_axial());
+        assert_eq!(read, expected);
+    }
+
+    #[test]
+    fn it_round_trips_values_with_line_breaks() {
+        let data = vec![
+            (Coordinate::at(-3, -1, 4).unwrap(), "first line\nsecond, \"quoted\"".to_string()),
+            (Coordinate::new(), "windows\r\nline\n\nbreaks\n".to_string()),
+            (Coordinate::at(2, 7, -9).unwrap(), "forest".to_string()),
+        ];
+        let mut output = vec![];
+        write_csv(&mut output, data.clone()).unwrap();
+        let read: Vec<(Coordinate, String)> = read_csv(&output[..]).unwrap();
+        assert_eq!(read, data);
+        let input = "q,r,value\n0,0,\"two\nlines\"\n0,1,eleven\n";
+        match read_csv::<_, u32>(input.as_bytes()) {
+            Err(CsvError::Parse { line, .. }) => assert_eq!(line, 2),
+            other => panic!("Expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_reads_cube_columns() {
+        let input = "x,y,z,value\n-3,-1,4,7\n2,7,-9,12\n";
+        let read: Vec<(Coordinate, u32)> = read_csv(input.as_bytes()).unwrap();
+        assert_eq!(read, vec![
+            (Coordinate::at(-3, -1, 4).unwrap(), 7),
+            (Coordinate::at(2, 7, -9).unwrap(), 12),
+        ]);
+    }
+
+    #[test]
+    fn it_reads_rows_without_a_header() {
+        let read: Vec<(Coordinate, u32)> = read_csv("-3,-1,7\n".as_bytes()).unwrap();
+        assert_eq!(read, vec![(Coordinate::at(-3, -1, 4).unwrap(), 7)]);
+    }
+
+    #[test]
+    fn it_reads_spreadsheet_exports_with_crlf_line_endings() {
+        let input = "q,r,value\r\n-3,-1,\"hills, rocky\"\r\n2,7,forest\r\n\r\n";
+        let read: Vec<(Coordinate, String)> = read_csv(input.as_bytes()).unwrap();
+        assert_eq!(read, vec![
+            (Coordinate::at(-3, -1, 4).unwrap(), "hills, rocky".to_string()),
+            (Coordinate::at(2, 7, -9).unwrap(), "forest".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn it_reports_the_line_of_malformed_rows() {
+        let cases = [
+            ("q,r,value\n0,0,1\n0,zero,1\n", 3),
+            ("q,r,value\n0,0,1\n\n0,0\n", 4),
+            ("x,y,z,value\n3,1,4,1\n", 2),
+            ("q,r,value\n0,0,\"open\n", 2),
+            ("0,0,eleven\n", 1),
+        ];
+        for &(input, line) in cases.iter() {
+            match read_csv::<_, u32>(input.as_bytes()) {
+                Err(CsvError::Parse { line: actual, .. }) => assert_eq!(actual, line, "{:?}", input),
+                other => panic!("Expected a parse error for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+}
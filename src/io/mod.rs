@@ -0,0 +1,2 @@
+//! Reading and writing coordinate-keyed data.
+pub mod csv;
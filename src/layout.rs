@@ -0,0 +1,317 @@
+//! Conversions between hex coordinates and pixel space.
+use std::f64::consts::PI;
+
+#[cfg(feature = "glam")]
+use glam::DVec2;
+#[cfg(feature = "mint")]
+use mint::Point2;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use {Coordinate, FractionalCoordinate};
+
+
+/// Which way hexes point in a `Layout`.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Orientation {
+    /// Hexes have a corner at the top, and rows run east-west.
+    Pointy,
+    /// Hexes have an edge at the top, and columns run north-south.
+    Flat,
+}
+
+impl Orientation {
+    /// The forward matrix from axial `(q, r)` to pixel space, before scaling
+    /// by the hex size.
+    fn forward(self) -> [f64; 4] {
+        let root3 = 3f64.sqrt();
+        match self {
+            Orientation::Pointy => [root3, root3 / 2.0, 0.0, 1.5],
+            Orientation::Flat => [1.5, 0.0, root3 / 2.0, root3],
+        }
+    }
+
+    /// The inverse of `forward`, from pixel space to axial `(q, r)`.
+    fn backward(self) -> [f64; 4] {
+        let m = self.forward();
+        let det = m[0] * m[3] - m[1] * m[2];
+        [m[3] / det, -m[1] / det, -m[2] / det, m[0] / det]
+    }
+
+    /// The angle of the first corner, in sixths of a turn.
+    fn start_angle(self) -> f64 {
+        match self {
+            Orientation::Pointy => 0.5,
+            Orientation::Flat => 0.0,
+        }
+    }
+}
+
+/// A mapping between hex coordinates and pixel space.
+///
+/// See http://www.redblobgames.com/grids/hexagons/implementation.html#layout
+/// for more detail. Pixel space has y increasing downward, as on screen.
+#[derive(Debug,PartialEq,Clone,Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Layout {
+    orientation: Orientation,
+    size: (f64, f64),
+    origin: (f64, f64),
+}
+
+impl Layout {
+    /// Create a new Layout, where `size` is the distance from a hex's center
+    /// to its corners along each pixel axis, and `origin` is the pixel center
+    /// of the hex at 0, 0, 0.
+    pub fn new(orientation: Orientation, size: (f64, f64), origin: (f64, f64)) -> Self {
+        Layout { orientation, size, origin }
+    }
+
+    /// Get the orientation of this Layout.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Get the pixel center of a hex.
+    pub fn hex_to_pixel(&self, c: Coordinate) -> (f64, f64) {
+        let m = self.orientation.forward();
+        let (q, r) = (c.x as f64, c.y as f64);
+        let x = (m[0] * q + m[1] * r) * self.size.0;
+        let y = (m[2] * q + m[3] * r) * self.size.1;
+        (x + self.origin.0, y + self.origin.1)
+    }
+
+    /// Get the point in hex space at a pixel.
+    pub fn pixel_to_hex(&self, pixel: (f64, f64)) -> FractionalCoordinate {
+        let m = self.orientation.backward();
+        let x = (pixel.0 - self.origin.0) / self.size.0;
+        let y = (pixel.1 - self.origin.1) / self.size.1;
+        FractionalCoordinate::from_axial(m[0] * x + m[1] * y, m[2] * x + m[3] * y)
+    }
+
+    /// Get the hex containing a pixel, e.g. to find the hex under the mouse.
+    ///
+    /// This is `pixel_to_hex` followed by `FractionalCoordinate::round`, so
+    /// pixels on the border between hexes resolve as documented there.
+    pub fn nearest_hex(&self, pixel: (f64, f64)) -> Coordinate {
+        self.pixel_to_hex(pixel).round()
+    }
+
+    /// Get the pixel positions of the six corners of a hex.
+    ///
+    /// Corners are in order of increasing angle, which runs clockwise on
+    /// screen since pixel y increases downward.
+    pub fn hex_corners(&self, c: Coordinate) -> [(f64, f64); 6] {
+        let (cx, cy) = self.hex_to_pixel(c);
+        let mut corners = [(0.0, 0.0); 6];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            let angle = 2.0 * PI * (self.orientation.start_angle() + i as f64) / 6.0;
+            *corner = (cx + self.size.0 * angle.cos(), cy + self.size.1 * angle.sin());
+        }
+        corners
+    }
+}
+
+/// The pixel conversions above, with points as `glam` vectors.
+#[cfg(feature = "glam")]
+impl Layout {
+    /// Get the pixel center of a hex, as for `hex_to_pixel`.
+    pub fn hex_to_pixel_vec2(&self, c: Coordinate) -> DVec2 {
+        self.hex_to_pixel(c).into()
+    }
+
+    /// Get the point in hex space at a pixel, as for `pixel_to_hex`.
+    pub fn pixel_to_hex_vec2(&self, pixel: DVec2) -> FractionalCoordinate {
+        self.pixel_to_hex(pixel.into())
+    }
+
+    /// Get the hex containing a pixel, as for `nearest_hex`.
+    pub fn nearest_hex_vec2(&self, pixel: DVec2) -> Coordinate {
+        self.nearest_hex(pixel.into())
+    }
+
+    /// Get the pixel positions of the six corners of a hex, as for
+    /// `hex_corners`.
+    pub fn hex_corners_vec2(&self, c: Coordinate) -> [DVec2; 6] {
+        self.hex_corners(c).map(DVec2::from)
+    }
+}
+
+/// The pixel conversions above, with points as `mint` points, which most
+/// math libraries convert to and from their own types.
+#[cfg(feature = "mint")]
+impl Layout {
+    /// Get the pixel center of a hex, as for `hex_to_pixel`.
+    pub fn hex_to_pixel_mint(&self, c: Coordinate) -> Point2<f64> {
+        let (x, y) = self.hex_to_pixel(c);
+        Point2 { x, y }
+    }
+
+    /// Get the point in hex space at a pixel, as for `pixel_to_hex`.
+    pub fn pixel_to_hex_mint<P: Into<Point2<f64>>>(&self, pixel: P) -> FractionalCoordinate {
+        let pixel = pixel.into();
+        self.pixel_to_hex((pixel.x, pixel.y))
+    }
+
+    /// Get the hex containing a pixel, as for `nearest_hex`.
+    pub fn nearest_hex_mint<P: Into<Point2<f64>>>(&self, pixel: P) -> Coordinate {
+        self.pixel_to_hex_mint(pixel).round()
+    }
+
+    /// Get the pixel positions of the six corners of a hex, as for
+    /// `hex_corners`.
+    pub fn hex_corners_mint(&self, c: Coordinate) -> [Point2<f64>; 6] {
+        self.hex_corners(c).map(|(x, y)| Point2 { x, y })
+    }
+}
+
+impl Coordinate {
+    /// Get the angle from this hex to `other` as drawn by `layout`, in
+    /// radians.
+    ///
+    /// The angle is measured counterclockwise on screen from the positive x
+    /// axis, so a hex drawn directly to the right is at 0 and one directly
+    /// above is at π/2. It lies in (-π, π], and is `None` when `other` is
+    /// this hex.
+    pub fn bearing_to(&self, other: Coordinate, layout: &Layout) -> Option<f64> {
+        if *self == other {
+            return None;
+        }
+        let (x1, y1) = layout.hex_to_pixel(*self);
+        let (x2, y2) = layout.hex_to_pixel(other);
+        // Pixel y increases downward, so flip it to measure counterclockwise.
+        Some((y1 - y2).atan2(x2 - x1))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn it_places_the_origin_hex_at_the_layout_origin() {
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (3.0, 4.0));
+        assert_close(layout.hex_to_pixel(Coordinate::new()), (3.0, 4.0));
+    }
+
+    #[test]
+    fn it_places_east_neighbors_to_the_right_in_pointy_layouts() {
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (0.0, 0.0));
+        let east = Coordinate::at(1, 0, -1).unwrap();
+        assert_close(layout.hex_to_pixel(east), (10.0 * 3f64.sqrt(), 0.0));
+    }
+
+    #[test]
+    fn it_places_neighbors_below_in_flat_layouts() {
+        let layout = Layout::new(Orientation::Flat, (10.0, 10.0), (0.0, 0.0));
+        let south = Coordinate::at(0, 1, -1).unwrap();
+        assert_close(layout.hex_to_pixel(south), (0.0, 10.0 * 3f64.sqrt()));
+    }
+
+    #[test]
+    fn it_places_corners_at_the_hex_size() {
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (0.0, 0.0));
+        let corners = layout.hex_corners(Coordinate::new());
+        assert_close(corners[4], (0.0, -10.0));
+        for corner in corners.iter() {
+            let distance = (corner.0 * corner.0 + corner.1 * corner.1).sqrt();
+            assert!((distance - 10.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn it_inverts_hex_to_pixel() {
+        for orientation in [Orientation::Pointy, Orientation::Flat].iter() {
+            let layout = Layout::new(*orientation, (10.0, 7.0), (3.0, 4.0));
+            let coord = Coordinate::at(-3, -1, 4).unwrap();
+            let point = layout.pixel_to_hex(layout.hex_to_pixel(coord));
+            assert!((point.x() + 3.0).abs() < 1e-9 && (point.y() + 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn it_picks_the_hex_under_a_pixel() {
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (3.0, 4.0));
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let (x, y) = layout.hex_to_pixel(coord);
+        assert_eq!(layout.nearest_hex((x + 4.0, y - 3.0)), coord);
+        assert_eq!(layout.nearest_hex((x - 6.0, y + 5.0)), coord);
+    }
+
+    #[test]
+    fn it_picks_one_hex_on_a_shared_edge() {
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (3.0, 4.0));
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        let east = coord + Coordinate::at(1, 0, -1).unwrap();
+        let (x1, y1) = layout.hex_to_pixel(coord);
+        let (x2, y2) = layout.hex_to_pixel(east);
+        let midpoint = ((x1 + x2) / 2.0, (y1 + y2) / 2.0);
+        let picked = layout.nearest_hex(midpoint);
+        assert!(picked == coord || picked == east);
+        for _ in 0..3 {
+            assert_eq!(layout.nearest_hex(midpoint), picked);
+        }
+    }
+
+    #[test]
+    fn it_measures_bearings_counterclockwise_from_the_right() {
+        let layout = Layout::new(Orientation::Flat, (10.0, 10.0), (0.0, 0.0));
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let east = a + Coordinate::at(2, -1, -1).unwrap();
+        assert!(a.bearing_to(east, &layout).unwrap().abs() < 1e-9);
+        let north = a + Coordinate::at(0, -1, 1).unwrap();
+        assert!((a.bearing_to(north, &layout).unwrap() - PI / 2.0).abs() < 1e-9);
+        assert!((east.bearing_to(a, &layout).unwrap() - PI).abs() < 1e-9);
+        assert_eq!(a.bearing_to(a, &layout), None);
+    }
+
+    #[test]
+    #[cfg(feature = "glam")]
+    fn it_agrees_with_glam_vectors() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for orientation in [Orientation::Pointy, Orientation::Flat].iter() {
+            let layout = Layout::new(*orientation, (10.0, 12.0), (100.0, 50.0));
+            for c in center.spiral(3) {
+                let pixel = layout.hex_to_pixel_vec2(c);
+                assert_eq!(pixel.to_array(), <[f64; 2]>::from(layout.hex_to_pixel(c)));
+                let corners = layout.hex_corners(c);
+                for (vector, corner) in layout.hex_corners_vec2(c).iter().zip(corners.iter()) {
+                    assert_eq!((vector.x, vector.y), *corner);
+                }
+                let nudged = pixel + DVec2::new(2.5, -1.5);
+                let point = layout.pixel_to_hex_vec2(nudged);
+                assert_eq!(point, layout.pixel_to_hex((nudged.x, nudged.y)));
+                assert_eq!(layout.nearest_hex_vec2(nudged), c);
+                let back = layout.hex_to_pixel_vec2(point.round());
+                assert!(back.distance(pixel) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "mint")]
+    fn it_agrees_with_mint_points() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for orientation in [Orientation::Pointy, Orientation::Flat].iter() {
+            let layout = Layout::new(*orientation, (10.0, 12.0), (100.0, 50.0));
+            for c in center.spiral(3) {
+                let pixel = layout.hex_to_pixel_mint(c);
+                assert_eq!((pixel.x, pixel.y), layout.hex_to_pixel(c));
+                let corners = layout.hex_corners(c);
+                for (point, corner) in layout.hex_corners_mint(c).iter().zip(corners.iter()) {
+                    assert_eq!((point.x, point.y), *corner);
+                }
+                let nudged = [pixel.x + 2.5, pixel.y - 1.5];
+                let point = layout.pixel_to_hex_mint(nudged);
+                assert_eq!(point, layout.pixel_to_hex((nudged[0], nudged[1])));
+                assert_eq!(layout.nearest_hex_mint(Point2::from(nudged)), c);
+            }
+        }
+    }
+}
@@ -1,167 +1,115 @@
 //! A library for hexmap operations.
-use std::ops::{Add, Sub};
-
-
-/// A hex coordinate using a cubic coordinate scheme.
-///
-/// See http://www.redblobgames.com/grids/hexagons/#coordinates for more detail.
-///
-/// All three coordinates must sum to zero.
-#[derive(Debug,PartialEq,PartialOrd,Clone,Copy)]
-pub struct Coordinate {
-    x: i64,
-    y: i64,
-    z: i64,
-}
-
-impl Coordinate {
-    /// Create a new Coordinate at 0, 0, 0.
-    ///
-    /// This returns a `Result` to match `at`, but always succeeds.
-    pub fn new() -> Result<Self, &'static str> {
-        Ok(Coordinate { x: 0, y: 0, z: 0 })
-    }
-
-    /// Create a new Coordinate at the specified location, if that location is
-    /// valid.
-    pub fn at(x: i64, y: i64, z: i64) -> Result<Self, &'static str> {
-        if x + y + z == 0 {
-            Ok(Coordinate { x: x, y: y, z: z })
-        } else {
-            Err("Invalid cubic coordinates")
-        }
-    }
-
-    /// Get the six neighbors of a given Coordinate.
-    pub fn neighbors(&self) -> Vec<Coordinate> {
-        vec![
-            Coordinate::at(self.x + 1, self.y, self.z - 1).unwrap(),
-            Coordinate::at(self.x + 1, self.y - 1, self.z).unwrap(),
-            Coordinate::at(self.x, self.y - 1, self.z + 1).unwrap(),
-            Coordinate::at(self.x, self.y + 1, self.z - 1).unwrap(),
-            Coordinate::at(self.x - 1, self.y, self.z + 1).unwrap(),
-            Coordinate::at(self.x - 1, self.y + 1, self.z).unwrap(),
-        ]
-    }
-
-    /// Get the neighbor in `direction`. Valid directions are in [0, 5].
-    pub fn neighbor(&self, direction: usize) -> Result<Coordinate, &'static str> {
-        let neighbors = self.neighbors();
-        match neighbors.get(direction) {
-            Some(val) => Ok(val.to_owned()),
-            None => Err("Invalid direction"),
-        }
-    }
-
-    /// Get the distance between two coordinates, in grid tiles.
-    pub fn distance_to(&self, other: Coordinate) -> i64 {
-        (
-            (self.x - other.x).abs()
-            + (self.y - other.y).abs()
-            + (self.z - other.z).abs()
-        ) / 2
-    }
-}
-
-impl Add for Coordinate {
-    type Output = Coordinate;
-
-    fn add(self, other: Coordinate) -> Coordinate {
-        Coordinate {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            z: self.z + other.z,
-        }
-    }
-}
-
-impl Sub for Coordinate {
-    type Output = Coordinate;
-
-    fn sub(self, other: Coordinate) -> Coordinate {
-        Coordinate {
-            x: self.x - other.x,
-            y: self.y - other.y,
-            z: self.z - other.z,
-        }
-    }
-}
-
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_makes_a_new_one() {
-        let coord = Coordinate::new().unwrap();
-        assert_eq!(coord.x, 0);
-        assert_eq!(coord.y, 0);
-        assert_eq!(coord.z, 0);
-    }
-
-    #[test]
-    fn it_accepts_args_in_constructor() {
-        let coord = Coordinate::at(-3, -1, 4).unwrap();
-        assert_eq!(coord.x, -3);
-        assert_eq!(coord.y, -1);
-        assert_eq!(coord.z, 4);
-    }
-
-    #[test]
-    fn it_rejects_invalid_cube_coordinates() {
-        let coord = Coordinate::at(3, 1, 4);
-        assert!(coord.is_err());
-    }
-
-    #[test]
-    fn it_generates_a_list_of_neighbors() {
-        let coord = Coordinate::new().unwrap();
-        let expected = vec![
-            Coordinate::at(1, 0, -1).unwrap(),
-            Coordinate::at(1, -1, 0).unwrap(),
-            Coordinate::at(0, -1, 1).unwrap(),
-            Coordinate::at(0, 1, -1).unwrap(),
-            Coordinate::at(-1, 0, 1).unwrap(),
-            Coordinate::at(-1, 1, 0).unwrap(),
-        ];
-        assert_eq!(coord.neighbors(), expected);
-    }
-
-    #[test]
-    fn it_calcuates_distances() {
-        let coord_a = Coordinate::at(-3, -1, 4).unwrap();
-        let coord_b = Coordinate::at(2, 7, -9).unwrap();
-        assert_eq!(coord_a.distance_to(coord_b), 13);
-    }
-
-    #[test]
-    fn it_supports_addition() {
-        let coord_a = Coordinate::at(-3, -1, 4).unwrap();
-        let coord_b = Coordinate::at(2, 7, -9).unwrap();
-        let expected = Coordinate::at(-1, 6, -5).unwrap();
-        assert_eq!(coord_a + coord_b, expected);
-    }
-
-    #[test]
-    fn it_supports_subtraction() {
-        let coord_a = Coordinate::at(-3, -1, 4).unwrap();
-        let coord_b = Coordinate::at(2, 7, -9).unwrap();
-        let expected = Coordinate::at(-5, -8, 13).unwrap();
-        assert_eq!(coord_a - coord_b, expected);
-    }
-
-    #[test]
-    fn it_produces_neighbors_given_a_direction() {
-        let coord = Coordinate::at(-3, -1, 4).unwrap();
-        let expected = Ok(Coordinate::at(-2, -1, 3).unwrap());
-        assert_eq!(coord.neighbor(0), expected);
-    }
-
-    #[test]
-    fn it_produces_no_neighbors_given_a_bad_direction() {
-        let coord = Coordinate::at(-3, -1, 4).unwrap();
-        let expected = Err("Invalid direction");
-        assert_eq!(coord.neighbor(6), expected);
-    }
-}
+//!
+//! The crate is `no_std` compatible. The `std` feature (on by default) enables
+//! everything; without it, the `alloc` feature enables the APIs that return
+//! collections, and the core coordinate math needs neither. The `proptest`
+//! feature implements `proptest::arbitrary::Arbitrary` for the crate's types,
+//! the `geojson` feature enables GeoJSON export, the `tiled` feature enables
+//! importing maps from the Tiled editor, the `serde` feature implements
+//! `Serialize` and `Deserialize` for coordinates and maps, the `rand`
+//! feature enables random map generation, the `image` feature enables
+//! rendering maps to images, and the `glam` and `mint` features let `Layout`
+//! and `FractionalCoordinate` work with `glam` vectors and `mint` points,
+//! which other math libraries convert to and from.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(any(feature = "std", test))]
+extern crate core;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "proptest", test))]
+#[cfg_attr(test, macro_use)]
+extern crate proptest;
+#[cfg(feature = "glam")]
+extern crate glam;
+#[cfg(feature = "image")]
+extern crate image;
+#[cfg(feature = "mint")]
+extern crate mint;
+#[cfg(feature = "rand")]
+extern crate rand;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(any(feature = "geojson", feature = "tiled", all(feature = "serde", test)))]
+#[cfg_attr(feature = "geojson", macro_use)]
+extern crate serde_json;
+
+#[cfg(feature = "std")]
+pub mod algorithms;
+#[cfg(any(feature = "proptest", test))]
+mod arbitrary;
+mod compact;
+mod coordinate;
+mod direction;
+mod edge;
+mod error;
+#[cfg(feature = "std")]
+mod fractional;
+#[cfg(feature = "rand")]
+pub mod generate;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+#[cfg(feature = "std")]
+pub mod graph;
+#[cfg(feature = "std")]
+mod hexmap;
+#[cfg(feature = "std")]
+mod hexset;
+mod hierarchy;
+mod indexer;
+#[cfg(feature = "std")]
+mod layout;
+#[cfg(feature = "std")]
+mod nearest;
+#[cfg(not(feature = "std"))]
+mod no_std_check;
+mod offset;
+#[cfg(feature = "std")]
+mod path;
+#[cfg(feature = "std")]
+pub mod pathfinding;
+mod region;
+#[cfg(feature = "std")]
+pub mod render;
+pub mod shapes;
+#[cfg(feature = "tiled")]
+pub mod tiled;
+#[cfg(feature = "std")]
+pub mod traverse;
+mod vertex;
+#[cfg(feature = "std")]
+pub mod visibility;
+mod wedge;
+#[cfg(feature = "alloc")]
+mod wrapping;
+
+pub use compact::CompactCoordinate;
+pub use coordinate::{Axis, Coordinate, CoordinateI64, Integer, DIAGONALS, DIRECTIONS, ORIGIN};
+pub use direction::Direction;
+pub use edge::Edge;
+pub use error::HexError;
+#[cfg(feature = "std")]
+pub use fractional::FractionalCoordinate;
+#[cfg(all(feature = "std", feature = "serde"))]
+pub use hexmap::MapDocument;
+#[cfg(feature = "std")]
+pub use hexmap::{
+    BinaryError, BinaryValue, HexMap, MapShape, Symmetry, SymmetryCenter, SymmetryGroup,
+};
+#[cfg(feature = "std")]
+pub use hexset::{BoundedHexSet, HexSet, OutOfBounds};
+pub use indexer::HexagonIndexer;
+#[cfg(feature = "std")]
+pub use layout::{Layout, Orientation};
+#[cfg(feature = "std")]
+pub use nearest::NearestIndex;
+pub use offset::Offset;
+#[cfg(feature = "std")]
+pub use path::{Path, PathError};
+pub use region::HexRegion;
+pub use vertex::Vertex;
+pub use wedge::WedgeWidth;
+#[cfg(feature = "alloc")]
+pub use wrapping::{Wrap, WrappingMap};
@@ -0,0 +1,299 @@
+//! An index for finding the members of a set nearest a hex.
+use std::collections::{HashMap, HashSet};
+
+use {Coordinate, FractionalCoordinate};
+
+
+/// The default `cell_size` of a `NearestIndex`.
+const DEFAULT_CELL_SIZE: i64 = 8;
+
+/// A set of hexes indexed for finding the members nearest a hex.
+///
+/// Members are bucketed into coarse cells, each covering the hexes that
+/// round to one hex of a grid scaled up by `cell_size`. A query searches
+/// outward from its own cell one ring of cells at a time, and stops as soon
+/// as no further ring can hold anything closer, so it only looks at members
+/// near the answer.
+#[derive(Debug,Clone)]
+pub struct NearestIndex {
+    cell_size: i64,
+    cells: HashMap<Coordinate, Vec<Coordinate>>,
+    len: usize,
+}
+
+impl NearestIndex {
+    /// Create an empty index with the default cell size.
+    pub fn new() -> Self {
+        NearestIndex::with_cell_size(DEFAULT_CELL_SIZE)
+    }
+
+    /// Create an empty index whose cells are `cell_size` hexes across.
+    ///
+    /// Larger cells suit sparser sets. A `cell_size` below 1 is treated as 1.
+    pub fn with_cell_size(cell_size: i64) -> Self {
+        NearestIndex { cell_size: cell_size.max(1), cells: HashMap::new(), len: 0 }
+    }
+
+    /// Get the number of members.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no members.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether a hex is a member.
+    pub fn contains(&self, c: Coordinate) -> bool {
+        self.cells.get(&self.cell(c)).is_some_and(|members| members.contains(&c))
+    }
+
+    /// Add a member, returning whether it was not already there.
+    pub fn insert(&mut self, c: Coordinate) -> bool {
+        let members = self.cells.entry(self.cell(c)).or_default();
+        if members.contains(&c) {
+            return false;
+        }
+        members.push(c);
+        self.len += 1;
+        true
+    }
+
+    /// Remove a member, returning whether it was there.
+    pub fn remove(&mut self, c: Coordinate) -> bool {
+        let cell = self.cell(c);
+        let members = match self.cells.get_mut(&cell) {
+            Some(members) => members,
+            None => return false,
+        };
+        let i = match members.iter().position(|&m| m == c) {
+            Some(i) => i,
+            None => return false,
+        };
+        members.swap_remove(i);
+        if members.is_empty() {
+            self.cells.remove(&cell);
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Get a member nearest `from` and its `distance_to` `from`, or `None` if
+    /// there are no members. Of several equally near members, any may be
+    /// given.
+    pub fn nearest(&self, from: Coordinate) -> Option<(Coordinate, i64)> {
+        let (home, offset) = self.locate(from);
+        let mut best: Option<(Coordinate, i64)> = None;
+        let mut cells_left = self.cells.len();
+        let mut ring = 0;
+        while cells_left > 0 && best.is_none_or(|(_, d)| self.closest_possible(ring, offset) < d) {
+            for cell in home.iter_ring(ring) {
+                let members = match self.cells.get(&cell) {
+                    Some(members) => members,
+                    None => continue,
+                };
+                cells_left -= 1;
+                for &member in members {
+                    let d = from.distance_to(member);
+                    if best.is_none_or(|(_, b)| d < b) {
+                        best = Some((member, d));
+                    }
+                }
+            }
+            ring += 1;
+        }
+        best
+    }
+
+    /// Iterate over the members within `radius` of `from`, in no particular
+    /// order.
+    pub fn within(&self, from: Coordinate, radius: i64) -> impl Iterator<Item = Coordinate> + '_ {
+        let (home, offset) = self.locate(from);
+        let mut rings = 0;
+        while self.closest_possible(rings + 1, offset) <= radius {
+            rings += 1;
+        }
+        let rings = if radius < 0 { -1 } else { rings };
+        home.iter_spiral(rings)
+            .filter_map(move |cell| self.cells.get(&cell))
+            .flat_map(|members| members.iter().cloned())
+            .filter(move |member| from.distance_to(*member) <= radius)
+    }
+
+    /// Get the cell a hex belongs to.
+    fn cell(&self, c: Coordinate) -> Coordinate {
+        let size = self.cell_size as f64;
+        FractionalCoordinate::from_axial(c.x() as f64 / size, c.y() as f64 / size).round()
+    }
+
+    /// Get the cell a hex belongs to, and its distance from that cell's
+    /// center.
+    fn locate(&self, c: Coordinate) -> (Coordinate, i64) {
+        let cell = self.cell(c);
+        let center = Coordinate::from_axial(cell.x() * self.cell_size, cell.y() * self.cell_size);
+        (cell, c.distance_to(center))
+    }
+
+    /// Get a lower bound on the distance from a hex `offset` from the
+    /// center of its cell to any member of a cell `ring` cells away.
+    ///
+    /// Every hex is within `cell_size` of the center of its cell, and the
+    /// centers of the two cells are `ring * cell_size` apart.
+    fn closest_possible(&self, ring: i64, offset: i64) -> i64 {
+        ring * self.cell_size - offset - self.cell_size
+    }
+}
+
+impl Default for NearestIndex {
+    fn default() -> Self {
+        NearestIndex::new()
+    }
+}
+
+impl ::std::iter::FromIterator<Coordinate> for NearestIndex {
+    /// Index hexes with the default cell size.
+    fn from_iter<I: IntoIterator<Item = Coordinate>>(iter: I) -> Self {
+        let mut index = NearestIndex::new();
+        index.extend(iter);
+        index
+    }
+}
+
+impl Extend<Coordinate> for NearestIndex {
+    fn extend<I: IntoIterator<Item = Coordinate>>(&mut self, iter: I) {
+        for c in iter {
+            self.insert(c);
+        }
+    }
+}
+
+impl Coordinate {
+    /// Get the `k` hexes nearest this Coordinate that aren't `excluded`,
+    /// nearest first.
+    ///
+    /// Hexes are taken ring by ring outward, so this Coordinate comes first
+    /// unless excluded, and ties are in the order given by `ring`. Since
+    /// `excluded` is finite, there are always `k` to be found.
+    pub fn k_nearest(&self, k: usize, excluded: &HashSet<Coordinate>) -> Vec<Coordinate> {
+        (0..)
+            .flat_map(|radius| self.iter_ring(radius))
+            .filter(|c| !excluded.contains(c))
+            .take(k)
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+
+    fn brute_force_distance(members: &[Coordinate], from: Coordinate) -> Option<i64> {
+        members.iter().map(|m| from.distance_to(*m)).min()
+    }
+
+    #[test]
+    fn it_finds_nothing_in_an_empty_index() {
+        let index = NearestIndex::new();
+        assert!(index.is_empty());
+        assert_eq!(index.nearest(Coordinate::new()), None);
+        assert_eq!(index.within(Coordinate::new(), 10).count(), 0);
+    }
+
+    #[test]
+    fn it_finds_the_nearest_member() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(20, 7, -27).unwrap();
+        let index: NearestIndex = vec![a, b].into_iter().collect();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.nearest(a), Some((a, 0)));
+        assert_eq!(index.nearest(b + Coordinate::at(1, 0, -1).unwrap()), Some((b, 1)));
+        let far = Coordinate::at(-300, 100, 200).unwrap();
+        assert_eq!(index.nearest(far), Some((a, far.distance_to(a))));
+    }
+
+    #[test]
+    fn it_inserts_and_removes_members() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(20, 7, -27).unwrap();
+        let mut index = NearestIndex::with_cell_size(3);
+        assert!(index.insert(a));
+        assert!(!index.insert(a));
+        assert!(index.insert(b));
+        assert!(index.contains(a));
+        assert!(index.remove(a));
+        assert!(!index.remove(a));
+        assert!(!index.contains(a));
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.nearest(a), Some((b, a.distance_to(b))));
+        assert!(index.remove(b));
+        assert_eq!(index.nearest(a), None);
+    }
+
+    #[test]
+    fn it_finds_members_within_a_radius() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let index: NearestIndex = center.spiral(12).into_iter().collect();
+        let found: HashSet<_> = index.within(center, 5).collect();
+        assert_eq!(found, center.spiral(5).into_iter().collect());
+        assert_eq!(index.within(center, -1).count(), 0);
+        assert_eq!(index.within(center, 0).collect::<Vec<_>>(), vec![center]);
+    }
+
+    #[test]
+    fn it_takes_the_nearest_hexes_first() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let nearest = center.k_nearest(20, &HashSet::new());
+        assert_eq!(nearest.len(), 20);
+        assert_eq!(nearest[0], center);
+        assert_eq!(nearest[..7], center.spiral(1)[..]);
+        assert!(nearest.windows(2).all(|w| center.distance_to(w[0]) <= center.distance_to(w[1])));
+        assert!(center.k_nearest(0, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn it_skips_excluded_hexes() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let excluded: HashSet<_> = center.spiral(1).into_iter()
+            .skip(2)
+            .chain(Some(center))
+            .collect();
+        let nearest = center.k_nearest(10, &excluded);
+        assert_eq!(nearest.len(), 10);
+        assert!(nearest.iter().all(|c| !excluded.contains(c)));
+        assert_eq!(nearest[0], center.spiral(1)[1]);
+        assert_eq!(nearest[1..].iter().filter(|c| center.distance_to(**c) == 2).count(), 9);
+    }
+
+    proptest! {
+        #[test]
+        fn it_matches_brute_force_queries(
+            members in vec((-60i64..60, -60i64..60), 0..40),
+            queries in vec((-100i64..100, -100i64..100), 1..10),
+            cell_size in 1i64..12,
+            radius in 0i64..30,
+        ) {
+            let members: Vec<_> = members.into_iter()
+                .map(|(q, r)| Coordinate::from_axial(q, r))
+                .collect();
+            let mut index = NearestIndex::with_cell_size(cell_size);
+            index.extend(members.iter().cloned());
+            for (q, r) in queries {
+                let from = Coordinate::from_axial(q, r);
+                let found = index.nearest(from);
+                prop_assert_eq!(found.map(|(_, d)| d), brute_force_distance(&members, from));
+                if let Some((member, d)) = found {
+                    prop_assert!(members.contains(&member));
+                    prop_assert_eq!(from.distance_to(member), d);
+                }
+                let within: HashSet<_> = index.within(from, radius).collect();
+                let expected: HashSet<_> = members.iter().cloned()
+                    .filter(|m| from.distance_to(*m) <= radius)
+                    .collect();
+                prop_assert_eq!(within, expected);
+            }
+        }
+    }
+}
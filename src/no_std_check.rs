@@ -0,0 +1,32 @@
+//! Uses of the core API, compiled only without `std` so that
+//! `cargo build --no-default-features` checks it needs no allocator, and
+//! `cargo build --no-default-features --features alloc` checks the APIs that
+//! return collections.
+#![allow(dead_code)]
+
+use core::convert::TryFrom;
+
+use {CompactCoordinate, Coordinate, Direction, HexError, HexagonIndexer, Vertex};
+
+
+fn core_api() -> Result<i64, HexError> {
+    let a = Coordinate::at(-3, -1, 4)?;
+    let b = Coordinate::from_axial(2, 7);
+    let neighbors: [Coordinate; 6] = a.neighbors();
+    let east = a.neighbor(Direction::E as usize)?;
+    let walked = a.walk(&[Direction::NE, Direction::SW]);
+    let ring = a.iter_ring(2).chain(a.iter_spiral(1)).count() as i64;
+    let grouped = a.parent(2).children(1).count() as i64;
+    let compact = CompactCoordinate::try_from(a + b - east)?;
+    let vertex = Vertex::new(walked, 3)?;
+    let indexer = HexagonIndexer::new(4);
+    let packed = indexer.coordinate(17).and_then(|c| indexer.index(c)).unwrap_or(0) as i64;
+    let components = neighbors[0].x() + Coordinate::from(compact).y() + vertex.hexes()[0].z();
+    Ok(a.distance_to(b) + ring + grouped + components + packed)
+}
+
+#[cfg(feature = "alloc")]
+fn alloc_api() -> usize {
+    let a = Coordinate::new();
+    a.ring(2).len() + a.spiral(2).len() + a.cone(Direction::W, 2).len()
+}
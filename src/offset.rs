@@ -0,0 +1,232 @@
+//! Conversions between cube and offset coordinates.
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use {Coordinate, HexError};
+
+
+/// An offset coordinate convention, i.e. a way of laying hexes out in a
+/// rectangular grid of columns and rows.
+///
+/// See http://www.redblobgames.com/grids/hexagons/#coordinates-offset for more
+/// detail. The `R` conventions are for pointy-top layouts and shift every
+/// other row; the `Q` conventions are for flat-top layouts and shift every
+/// other column. `Odd` and `Even` say which rows or columns are shifted.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum Offset {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+impl Offset {
+    /// Whether this convention shifts rows (as opposed to columns).
+    pub fn shifts_rows(self) -> bool {
+        match self {
+            Offset::OddR | Offset::EvenR => true,
+            Offset::OddQ | Offset::EvenQ => false,
+        }
+    }
+}
+
+impl Coordinate {
+    /// Convert to a `(column, row)` pair in the given offset convention.
+    pub fn to_offset(&self, offset: Offset) -> (i64, i64) {
+        let (q, r) = (self.x, self.y);
+        match offset {
+            Offset::OddR => (q + (r - (r & 1)) / 2, r),
+            Offset::EvenR => (q + (r + (r & 1)) / 2, r),
+            Offset::OddQ => (q, r + (q - (q & 1)) / 2),
+            Offset::EvenQ => (q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    /// Create a Coordinate from a `(column, row)` pair in the given offset
+    /// convention.
+    ///
+    /// Every pair is valid, so this always succeeds.
+    pub fn from_offset(column: i64, row: i64, offset: Offset) -> Coordinate {
+        let (q, r) = match offset {
+            Offset::OddR => (column - (row - (row & 1)) / 2, row),
+            Offset::EvenR => (column - (row + (row & 1)) / 2, row),
+            Offset::OddQ => (column, row - (column - (column & 1)) / 2),
+            Offset::EvenQ => (column, row - (column + (column & 1)) / 2),
+        };
+        Coordinate::at_unchecked(q, r, -q - r)
+    }
+
+    /// Get the number of columns between this Coordinate and `other` in the
+    /// given offset convention, which can be less than their `distance_to`.
+    pub fn column_distance(&self, other: Coordinate, offset: Offset) -> i64 {
+        (self.to_offset(offset).0 - other.to_offset(offset).0).abs()
+    }
+
+    /// Get the number of rows between this Coordinate and `other` in the
+    /// given offset convention, as for `column_distance`.
+    pub fn row_distance(&self, other: Coordinate, offset: Offset) -> i64 {
+        (self.to_offset(offset).1 - other.to_offset(offset).1).abs()
+    }
+
+    /// Get a board game style label, like `B3`, for this Coordinate's
+    /// position in the given offset convention.
+    ///
+    /// The column is written as letters, with column 0 as `A`, column 25 as
+    /// `Z`, and column 26 as `AA`. The row is written as a number, with row 0
+    /// as `1`. Hexes with a negative column or row have no label, and give
+    /// `HexError::OutOfRange`.
+    #[cfg(feature = "alloc")]
+    pub fn to_label(&self, offset: Offset) -> Result<String, HexError> {
+        let (column, row) = self.to_offset(offset);
+        if column < 0 || row < 0 {
+            return Err(HexError::OutOfRange);
+        }
+        let mut letters = Vec::new();
+        let mut rest = column + 1;
+        while rest > 0 {
+            rest -= 1;
+            letters.push(b'A' + (rest % 26) as u8);
+            rest /= 26;
+        }
+        letters.reverse();
+        let mut label: String = letters.into_iter().map(char::from).collect();
+        label.push_str(&(row + 1).to_string());
+        Ok(label)
+    }
+
+    /// Create a Coordinate from a label given by `to_label`.
+    ///
+    /// Letters may be in either case. This fails with
+    /// `HexError::InvalidLabel` unless the label is one or more letters
+    /// followed by a number from 1 without leading zeros, and with
+    /// `HexError::OutOfRange` if the column or row is too large.
+    pub fn from_label(label: &str, offset: Offset) -> Result<Coordinate, HexError> {
+        let split = label.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(label.len());
+        let (letters, digits) = label.split_at(split);
+        let valid_digits = digits.bytes().all(|b| b.is_ascii_digit()) && !digits.starts_with('0');
+        if letters.is_empty() || digits.is_empty() || !valid_digits {
+            return Err(HexError::InvalidLabel);
+        }
+        let column = letters.bytes().try_fold(0i64, |column, b| {
+            let letter = i64::from(b.to_ascii_uppercase() - b'A') + 1;
+            column.checked_mul(26).and_then(|c| c.checked_add(letter))
+        });
+        let row = digits.bytes().try_fold(0i64, |row, b| {
+            row.checked_mul(10).and_then(|r| r.checked_add(i64::from(b - b'0')))
+        });
+        match (column, row) {
+            (Some(column), Some(row)) => Ok(Coordinate::from_offset(column - 1, row - 1, offset)),
+            _ => Err(HexError::OutOfRange),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Offset; 4] = [Offset::OddR, Offset::EvenR, Offset::OddQ, Offset::EvenQ];
+
+    #[test]
+    fn it_converts_to_offset_coordinates() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(coord.to_offset(Offset::OddR), (-4, -1));
+        assert_eq!(coord.to_offset(Offset::EvenR), (-3, -1));
+        assert_eq!(coord.to_offset(Offset::OddQ), (-3, -3));
+        assert_eq!(coord.to_offset(Offset::EvenQ), (-3, -2));
+    }
+
+    #[test]
+    fn it_round_trips_offset_coordinates() {
+        for offset in ALL.iter() {
+            for column in -4..5 {
+                for row in -4..5 {
+                    let coord = Coordinate::from_offset(column, row, *offset);
+                    assert_eq!(coord.to_offset(*offset), (column, row));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_counts_columns_and_rows_apart() {
+        // In odd-r, (-1, 2, -1) sits at column 0, row 2, straight below the
+        // origin two hexes away; (2, -2, 0) at column 1, row -2; and
+        // (1, 1, -2) at column 1, row 1.
+        let origin = Coordinate::new();
+        let below = Coordinate::at(-1, 2, -1).unwrap();
+        let above = Coordinate::at(2, -2, 0).unwrap();
+        let odd = Coordinate::at(1, 1, -2).unwrap();
+        assert_eq!(origin.column_distance(below, Offset::OddR), 0);
+        assert_eq!(origin.row_distance(below, Offset::OddR), 2);
+        assert_eq!(origin.column_distance(above, Offset::OddR), 1);
+        assert_eq!(above.row_distance(origin, Offset::OddR), 2);
+        assert_eq!(odd.column_distance(origin, Offset::OddR), 1);
+        assert_eq!(below.column_distance(above, Offset::OddR), 1);
+        assert_eq!(below.row_distance(above, Offset::OddR), 4);
+        assert_eq!(below.distance_to(above), 4);
+        assert_eq!(origin.distance_to(odd), 2);
+        for offset in ALL.iter() {
+            assert_eq!(below.column_distance(below, *offset), 0);
+            assert_eq!(below.column_distance(above, *offset), above.column_distance(below, *offset));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_labels_hexes_like_a_board() {
+        let label = |column, row, offset| Coordinate::from_offset(column, row, offset).to_label(offset);
+        assert_eq!(label(0, 0, Offset::OddR), Ok("A1".to_string()));
+        assert_eq!(label(1, 2, Offset::OddR), Ok("B3".to_string()));
+        assert_eq!(label(25, 9, Offset::EvenQ), Ok("Z10".to_string()));
+        assert_eq!(label(26, 0, Offset::OddQ), Ok("AA1".to_string()));
+        assert_eq!(label(702, 0, Offset::OddQ), Ok("AAA1".to_string()));
+        assert_eq!(label(-1, 0, Offset::OddR), Err(HexError::OutOfRange));
+        let b3 = Coordinate::from_offset(1, 2, Offset::OddR);
+        assert_eq!(Coordinate::from_label("b3", Offset::OddR), Ok(b3));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn it_round_trips_labels() {
+        for offset in ALL.iter() {
+            for label in ["A1", "B3", "Z26", "AA1", "AZ100", "ZZ7"].iter() {
+                let coord = Coordinate::from_label(label, *offset).unwrap();
+                assert_eq!(coord.to_label(*offset).unwrap(), *label);
+            }
+            for column in 0..60 {
+                for row in 0..5 {
+                    let coord = Coordinate::from_offset(column, row, *offset);
+                    let label = coord.to_label(*offset).unwrap();
+                    assert_eq!(Coordinate::from_label(&label, *offset), Ok(coord));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_labels() {
+        for label in ["", "A", "3", "3A", "A0", "A01", "A1B", "A-1", "Ä1", "A 1"].iter() {
+            assert_eq!(Coordinate::from_label(label, Offset::OddR), Err(HexError::InvalidLabel));
+        }
+        let long = "A99999999999999999999";
+        assert_eq!(Coordinate::from_label(long, Offset::OddR), Err(HexError::OutOfRange));
+    }
+
+    #[test]
+    fn it_keeps_offset_rows_adjacent() {
+        for offset in ALL.iter() {
+            let a = Coordinate::from_offset(2, 3, *offset);
+            let b = Coordinate::from_offset(3, 3, *offset);
+            let c = Coordinate::from_offset(3, 2, *offset);
+            if offset.shifts_rows() {
+                assert_eq!(a.distance_to(b), 1);
+            } else {
+                assert_eq!(b.distance_to(c), 1);
+            }
+        }
+    }
+}
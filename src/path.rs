@@ -0,0 +1,382 @@
+//! Paths of hexes, sampled as smooth motion through their centers.
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use {Coordinate, Direction, HexError, Layout};
+
+
+/// An error from turning a `Path` into directions, or from decoding one.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum PathError {
+    /// The hex at this index of the path isn't a neighbor of the next one.
+    NotAdjacent(usize),
+    /// The encoded path is malformed.
+    Corrupt(&'static str),
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PathError::NotAdjacent(index) => {
+                write!(f, "Path hexes {} and {} aren't adjacent", index, index + 1)
+            }
+            PathError::Corrupt(message) => write!(f, "Corrupt encoded path: {}", message),
+        }
+    }
+}
+
+impl Error for PathError {}
+
+
+/// A sequence of hexes to move through, such as a path found by the
+/// functions in `pathfinding`.
+///
+/// A Path is drawn as the broken line through the pixel centers of its
+/// hexes, and can be sampled anywhere along that line to animate movement.
+/// Consecutive hexes need not be adjacent, and may repeat.
+#[derive(Debug,PartialEq,Eq,Hash,Clone)]
+pub struct Path {
+    hexes: Vec<Coordinate>,
+}
+
+impl Path {
+    /// Create a Path through `hexes`, in order.
+    ///
+    /// This fails with `HexError::EmptyInput` if there are none, since an
+    /// empty path has nowhere to be sampled.
+    pub fn new(hexes: Vec<Coordinate>) -> Result<Path, HexError> {
+        if hexes.is_empty() {
+            Err(HexError::EmptyInput)
+        } else {
+            Ok(Path { hexes })
+        }
+    }
+
+    /// Create the Path that starts at `start` and takes a step in each of
+    /// `directions` in turn.
+    pub fn from_directions(start: Coordinate, directions: &[Direction]) -> Path {
+        let mut hexes = Vec::with_capacity(directions.len() + 1);
+        hexes.push(start);
+        let mut at = start;
+        for dir in directions.iter() {
+            at = at + dir.offset();
+            hexes.push(at);
+        }
+        Path { hexes }
+    }
+
+    /// Get the first hex of this Path and the direction of each step from
+    /// one hex to the next, as taken by `from_directions`.
+    ///
+    /// This fails with `PathError::NotAdjacent` at the first hex that isn't
+    /// a neighbor of the next, including a hex that repeats.
+    pub fn to_directions(&self) -> Result<(Coordinate, Vec<Direction>), PathError> {
+        let directions = self.hexes.windows(2).enumerate()
+            .map(|(i, pair)| {
+                Direction::from_offset(pair[1] - pair[0]).ok_or(PathError::NotAdjacent(i))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((self.hexes[0], directions))
+    }
+
+    /// Encode this Path compactly, e.g. to send over a network, if each hex
+    /// is a neighbor of the next.
+    ///
+    /// The encoding is the first hex's `x` and `y` components and the number
+    /// of steps, as LEB128 varints with the components zigzag-encoded,
+    /// followed by each step's direction as the three-bit index of its
+    /// `Coordinate::neighbors` offset. Directions are packed from the least
+    /// significant bit of each byte, and the last byte is padded with zeros,
+    /// so `n` steps take `ceil(3n / 8)` bytes after the header. This fails as
+    /// for `to_directions`.
+    pub fn encode(&self) -> Result<Vec<u8>, PathError> {
+        let (start, directions) = self.to_directions()?;
+        let mut bytes = Vec::with_capacity(packed_len(directions.len() as u64) as usize + 12);
+        write_signed(&mut bytes, start.x());
+        write_signed(&mut bytes, start.y());
+        write_varint(&mut bytes, directions.len() as u64);
+        let mut bits = 0u32;
+        let mut pending = 0;
+        for dir in directions.iter() {
+            bits |= (*dir as u32) << pending;
+            pending += 3;
+            if pending >= 8 {
+                bytes.push(bits as u8);
+                bits >>= 8;
+                pending -= 8;
+            }
+        }
+        if pending > 0 {
+            bytes.push(bits as u8);
+        }
+        Ok(bytes)
+    }
+
+    /// Decode a Path written by `encode`.
+    ///
+    /// This fails with `PathError::Corrupt` unless `bytes` is exactly one
+    /// encoded path, with valid directions and zero padding, whose hexes all
+    /// fit in a `Coordinate`.
+    pub fn decode(bytes: &[u8]) -> Result<Path, PathError> {
+        let mut rest = bytes;
+        let x = read_signed(&mut rest)?;
+        let y = read_signed(&mut rest)?;
+        let steps = read_varint(&mut rest)?;
+        if rest.len() as u64 != packed_len(steps) {
+            return Err(PathError::Corrupt("Wrong number of direction bytes"));
+        }
+        // Each step moves each component by at most one.
+        let fits = |v: i64| v.unsigned_abs() <= i64::MAX as u64 - steps;
+        let z = x.checked_add(y).and_then(i64::checked_neg);
+        let start = match z {
+            Some(z) if fits(x) && fits(y) && fits(z) => Coordinate::at_unchecked(x, y, z),
+            _ => return Err(PathError::Corrupt("Path out of range")),
+        };
+        let mut directions = Vec::with_capacity(steps as usize);
+        let mut bits = 0u32;
+        let mut pending = 0;
+        for byte in rest.iter() {
+            bits |= u32::from(*byte) << pending;
+            pending += 8;
+            while pending >= 3 && (directions.len() as u64) < steps {
+                let index = (bits & 7) as usize;
+                match Direction::ALL.iter().find(|dir| **dir as usize == index) {
+                    Some(dir) => directions.push(*dir),
+                    None => return Err(PathError::Corrupt("Invalid direction")),
+                }
+                bits >>= 3;
+                pending -= 3;
+            }
+        }
+        if bits != 0 {
+            return Err(PathError::Corrupt("Nonzero padding"));
+        }
+        Ok(Path::from_directions(start, &directions))
+    }
+
+    /// Get the hexes of this Path.
+    pub fn hexes(&self) -> &[Coordinate] {
+        &self.hexes
+    }
+
+    /// Get the length in pixels of the line through the centers of this
+    /// Path's hexes as drawn by `layout`.
+    pub fn length_pixels(&self, layout: &Layout) -> f64 {
+        self.hexes.windows(2)
+            .map(|pair| distance(layout.hex_to_pixel(pair[0]), layout.hex_to_pixel(pair[1])))
+            .sum()
+    }
+
+    /// Get the pixel position fraction `t` of the way along this Path as
+    /// drawn by `layout`, measured by length.
+    ///
+    /// `t` of 0 or less gives the center of the first hex, and 1 or more the
+    /// center of the last. Positions never move backward as `t` increases.
+    pub fn sample(&self, layout: &Layout, t: f64) -> (f64, f64) {
+        self.sample_at_distance(layout, t * self.length_pixels(layout))
+    }
+
+    /// Get the pixel position `d` pixels along this Path as drawn by
+    /// `layout`, so that increasing `d` steadily moves at a constant speed.
+    ///
+    /// Distances are clamped to the Path's `length_pixels`, as for `sample`.
+    pub fn sample_at_distance(&self, layout: &Layout, d: f64) -> (f64, f64) {
+        let first = layout.hex_to_pixel(self.hexes[0]);
+        if d.is_nan() || d <= 0.0 {
+            return first;
+        }
+        if d >= self.length_pixels(layout) {
+            return layout.hex_to_pixel(self.hexes[self.hexes.len() - 1]);
+        }
+        let mut left = d;
+        let mut from = first;
+        for hex in self.hexes[1..].iter() {
+            let to = layout.hex_to_pixel(*hex);
+            let length = distance(from, to);
+            if left < length {
+                let t = left / length;
+                return (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+            }
+            left -= length;
+            from = to;
+        }
+        from
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Get the number of bytes `steps` packed directions take, or more than any
+/// slice can hold if that overflows.
+fn packed_len(steps: u64) -> u64 {
+    steps.checked_mul(3).map_or(u64::MAX, |bits| bits.div_ceil(8))
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64, PathError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first()
+            .ok_or(PathError::Corrupt("Header ended early"))?;
+        *bytes = rest;
+        let bits = u64::from(byte & 0x7f);
+        if shift == 63 && bits > 1 {
+            break;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(PathError::Corrupt("Varint out of range"))
+}
+
+fn write_signed(bytes: &mut Vec<u8>, value: i64) {
+    write_varint(bytes, ((value << 1) ^ (value >> 63)) as u64)
+}
+
+fn read_signed(bytes: &mut &[u8]) -> Result<i64, PathError> {
+    let value = read_varint(bytes)?;
+    Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Orientation;
+
+    fn layout() -> Layout {
+        Layout::new(Orientation::Pointy, (10.0, 10.0), (100.0, 50.0))
+    }
+
+    fn assert_close(a: (f64, f64), b: (f64, f64)) {
+        assert!(distance(a, b) < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn it_samples_the_ends_exactly() {
+        let layout = layout();
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let goal = Coordinate::at(2, 1, -3).unwrap();
+        let path = Path::new(start.line_to(goal)).unwrap();
+        assert_eq!(path.sample(&layout, 0.0), layout.hex_to_pixel(start));
+        assert_eq!(path.sample(&layout, 1.0), layout.hex_to_pixel(goal));
+        assert_eq!(path.sample(&layout, -2.0), layout.hex_to_pixel(start));
+        assert_eq!(path.sample(&layout, 3.0), layout.hex_to_pixel(goal));
+        let length = path.length_pixels(&layout);
+        assert_eq!(path.sample_at_distance(&layout, length), layout.hex_to_pixel(goal));
+    }
+
+    #[test]
+    fn it_samples_edge_midpoints_halfway_between_neighbors() {
+        let layout = layout();
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = a + Coordinate::at(1, -1, 0).unwrap();
+        let path = Path::new(vec![a, b]).unwrap();
+        let shared: Vec<(f64, f64)> = layout.hex_corners(a).iter()
+            .filter(|p| layout.hex_corners(b).iter().any(|q| distance(**p, *q) < 1e-9))
+            .cloned()
+            .collect();
+        assert_eq!(shared.len(), 2);
+        let midpoint = ((shared[0].0 + shared[1].0) / 2.0, (shared[0].1 + shared[1].1) / 2.0);
+        assert_close(path.sample(&layout, 0.5), midpoint);
+        assert!((path.length_pixels(&layout) - 10.0 * 3f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn it_samples_monotonically() {
+        let layout = layout();
+        let corner = Coordinate::at(3, 0, -3).unwrap();
+        let mut hexes = Coordinate::new().line_to(corner);
+        hexes.extend(corner.line_to(Coordinate::at(3, 3, -6).unwrap()));
+        let path = Path::new(hexes).unwrap();
+        let length = path.length_pixels(&layout);
+        let mut travelled = 0.0;
+        let mut last = path.sample(&layout, 0.0);
+        for i in 1..101 {
+            let next = path.sample(&layout, i as f64 / 100.0);
+            travelled += distance(last, next);
+            assert!((travelled - length * i as f64 / 100.0).abs() < 1e-6);
+            last = next;
+        }
+    }
+
+    #[test]
+    fn it_samples_degenerate_paths() {
+        let layout = layout();
+        let hex = Coordinate::at(-3, -1, 4).unwrap();
+        let single = Path::new(vec![hex]).unwrap();
+        assert_eq!(single.length_pixels(&layout), 0.0);
+        assert_eq!(single.sample(&layout, 0.5), layout.hex_to_pixel(hex));
+        assert_eq!(single.sample(&layout, f64::NAN), layout.hex_to_pixel(hex));
+        let repeated = Path::new(vec![hex, hex, hex]).unwrap();
+        assert_eq!(repeated.sample(&layout, 1.0), layout.hex_to_pixel(hex));
+        assert_eq!(Path::new(vec![]), Err(HexError::EmptyInput));
+    }
+
+    #[test]
+    fn it_converts_to_and_from_directions() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let directions = [Direction::E, Direction::E, Direction::NW, Direction::SW, Direction::SE];
+        let path = Path::from_directions(start, &directions);
+        assert_eq!(path.hexes().len(), 6);
+        assert_eq!(path.hexes()[3], start + Coordinate::at(2, -1, -1).unwrap());
+        assert_eq!(path.to_directions(), Ok((start, directions.to_vec())));
+        assert_eq!(Path::from_directions(start, &[]).hexes(), &[start]);
+        let line = Path::new(start.line_to(Coordinate::at(4, -2, -2).unwrap())).unwrap();
+        assert_eq!(Path::from_directions(start, &line.to_directions().unwrap().1), line);
+    }
+
+    #[test]
+    fn it_rejects_non_adjacent_hexes() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let next = start + Coordinate::at(1, 0, -1).unwrap();
+        let far = Coordinate::at(2, 3, -5).unwrap();
+        let jump = Path::new(vec![start, next, far]).unwrap();
+        assert_eq!(jump.to_directions(), Err(PathError::NotAdjacent(1)));
+        assert_eq!(jump.encode(), Err(PathError::NotAdjacent(1)));
+        let repeat = Path::new(vec![start, start]).unwrap();
+        assert_eq!(repeat.to_directions(), Err(PathError::NotAdjacent(0)));
+    }
+
+    #[test]
+    fn it_rejects_corrupt_encodings() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let path = Path::from_directions(start, &[Direction::W, Direction::SE, Direction::NE]);
+        let bytes = path.encode().unwrap();
+        assert_eq!(Path::decode(&bytes), Ok(path));
+        assert!(Path::decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Path::decode(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(Path::decode(&[]).is_err());
+        // One step in direction 6, and one step with a padding bit set.
+        assert_eq!(Path::decode(&[0, 0, 1, 6]), Err(PathError::Corrupt("Invalid direction")));
+        assert_eq!(Path::decode(&[0, 0, 1, 8]), Err(PathError::Corrupt("Nonzero padding")));
+        let huge = [0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0, 1, 0];
+        assert_eq!(Path::decode(&huge), Err(PathError::Corrupt("Path out of range")));
+    }
+
+    proptest! {
+        #[test]
+        fn it_round_trips_random_paths(x in -1000i64..1000, y in -1000i64..1000,
+                                       steps in proptest::collection::vec(0usize..6, 0..100)) {
+            let directions: Vec<_> = steps.iter().map(|i| Direction::ALL[*i]).collect();
+            let path = Path::from_directions(Coordinate::from_axial(x, y), &directions);
+            let bytes = path.encode().unwrap();
+            // Each header varint takes at most two bytes for these values.
+            let packed: usize = (3 * directions.len()).div_ceil(8);
+            prop_assert!(bytes.len() <= packed + 6);
+            prop_assert_eq!(Path::decode(&bytes), Ok(path.clone()));
+            prop_assert_eq!(path.to_directions(), Ok((path.hexes()[0], directions)));
+        }
+    }
+}
@@ -0,0 +1,1139 @@
+//! Searches for paths between hexes.
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use {Coordinate, Direction};
+
+
+/// Get the number of steps on a shortest path from `start` to `goal` that
+/// avoids `blocked` hexes, or `None` if there is no such path.
+///
+/// This searches breadth-first from both ends at once, so it terminates even
+/// though the grid is unbounded: if either end is enclosed, its search runs
+/// out of hexes to visit. No path is built, so this is cheaper than finding
+/// one when only its length is needed.
+pub fn path_length(start: Coordinate, goal: Coordinate, blocked: &HashSet<Coordinate>) -> Option<i64> {
+    bidirectional_length(start, goal, |c| !blocked.contains(&c))
+}
+
+/// Get the number of steps on a shortest path from `start` to `goal` through
+/// `passable` hexes, or `None` if there is no such path of at most `max`
+/// steps.
+///
+/// The search never visits a hex from which `goal` can't be reached within
+/// `max` steps, even ignoring impassable hexes, so it only looks within
+/// `max` of `start` and gives up quickly when the answer is no. Both ends
+/// must be passable.
+pub fn path_distance<F>(start: Coordinate, goal: Coordinate, max: u32, passable: F) -> Option<u32>
+    where F: Fn(Coordinate) -> bool
+{
+    path_distance_with_stats(start, goal, max, passable).0
+}
+
+/// Measure a path like `path_distance`, also counting the work done.
+pub fn path_distance_with_stats<F>(start: Coordinate, goal: Coordinate, max: u32, passable: F) -> (Option<u32>, SearchStats)
+    where F: Fn(Coordinate) -> bool
+{
+    let mut stats = SearchStats::default();
+    let max = i64::from(max);
+    if !passable(start) || !passable(goal) || start.distance_to(goal) > max {
+        return (None, stats);
+    }
+    let mut steps = HashMap::new();
+    let mut frontier = VecDeque::new();
+    steps.insert(start, 0);
+    frontier.push_back(start);
+    while let Some(current) = frontier.pop_front() {
+        let so_far = steps[&current];
+        if current == goal {
+            return (Some(so_far as u32), stats);
+        }
+        stats.expanded += 1;
+        for neighbor in current.iter_neighbors() {
+            let in_reach = so_far + 1 + neighbor.distance_to(goal) <= max;
+            if in_reach && !steps.contains_key(&neighbor) && passable(neighbor) {
+                steps.insert(neighbor, so_far + 1);
+                frontier.push_back(neighbor);
+            }
+        }
+    }
+    (None, stats)
+}
+
+/// Counts of the work done by a search.
+#[derive(Debug,PartialEq,Eq,Clone,Copy,Default)]
+pub struct SearchStats {
+    /// The number of hexes whose neighbors were considered.
+    pub expanded: usize,
+}
+
+/// Find a path from `start` to `goal` that minimizes the total cost of the
+/// hexes entered, or `None` if there is no such path.
+///
+/// `cost` gives the cost of entering a hex, or `None` if it is impassable,
+/// and must never be less than `min_cost`. The path includes both ends; the
+/// cost of `start` is never paid. This is an A* search using `distance_to`
+/// scaled by `min_cost` as its heuristic, so the higher `min_cost`, the
+/// fewer hexes are expanded; with a `min_cost` of 0, hexes are expanded in
+/// order of cost, as in Dijkstra's algorithm.
+///
+/// Ties between estimates go to the hex nearest `goal`, so a search over
+/// hexes costing 0 heads straight for it. A `goal` that can only be reached
+/// at a cost is still never found across an endless region of hexes
+/// costing 0, since every hex in the region is expanded first.
+///
+/// Alongside the search, a breadth-first search out from `goal` checks that
+/// it isn't enclosed, a hex at a time until the two meet, so an enclosed
+/// `start` or `goal` fails without searching the unbounded grid.
+pub fn find_path_weighted<F>(start: Coordinate, goal: Coordinate, min_cost: u32, cost: F) -> Option<Vec<Coordinate>>
+    where F: Fn(Coordinate) -> Option<u32>
+{
+    find_path_weighted_with_stats(start, goal, min_cost, cost).0
+}
+
+/// Find a path like `find_path_weighted`, also counting the work done.
+pub fn find_path_weighted_with_stats<F>(start: Coordinate, goal: Coordinate, min_cost: u32, cost: F) -> (Option<Vec<Coordinate>>, SearchStats)
+    where F: Fn(Coordinate) -> Option<u32>
+{
+    let remaining = |c: Coordinate| c.distance_to(goal) as u64;
+    let mut stats = SearchStats::default();
+    let passable = |c: Coordinate| c == start || cost(c).is_some();
+    let mut enclosure = Enclosure::new(goal, &passable);
+    let mut came_from = HashMap::new();
+    let mut best = HashMap::new();
+    let mut open = BinaryHeap::new();
+    best.insert(start, 0);
+    let estimate = remaining(start) * u64::from(min_cost);
+    open.push(Node { estimate, remaining: remaining(start), cost: 0, coord: start });
+    while let Some(Node { cost: so_far, coord: current, .. }) = open.pop() {
+        if current == goal {
+            return (Some(reconstruct(&came_from, goal)), stats);
+        }
+        if enclosure.is_enclosed(&passable, |c| best.contains_key(c)) {
+            return (None, stats);
+        }
+        if best.get(&current).is_some_and(|&b| so_far > b) {
+            continue;
+        }
+        stats.expanded += 1;
+        for neighbor in current.neighbors().iter() {
+            let step = match cost(*neighbor) {
+                Some(step) => u64::from(step),
+                None => continue,
+            };
+            let total = so_far + step;
+            if best.get(neighbor).is_none_or(|&b| total < b) {
+                best.insert(*neighbor, total);
+                came_from.insert(*neighbor, current);
+                let left = remaining(*neighbor);
+                let estimate = total + left * u64::from(min_cost);
+                open.push(Node { estimate, remaining: left, cost: total, coord: *neighbor });
+            }
+        }
+    }
+    (None, stats)
+}
+
+/// Find a path from `start` to `goal` avoiding `blocked` hexes for a unit
+/// facing `start_dir`, which pays 1 for each step and `turn_cost` more for
+/// each step in a direction other than the one it faces, or `None` if there
+/// is no such path.
+///
+/// The unit faces the direction of its last step, so a path that turns
+/// fewer times is preferred to a shorter one whenever the turns saved cost
+/// more than the extra steps. This is an A* search over each hex and facing,
+/// using `distance_to` as its heuristic, and the ends are checked for
+/// connectivity first with `path_length`'s bidirectional search, so an
+/// enclosed `start` or `goal` fails quickly. The path includes both ends;
+/// `start` may be blocked.
+pub fn find_path_turn_penalty(start: Coordinate, start_dir: Direction, goal: Coordinate, blocked: &HashSet<Coordinate>, turn_cost: u32) -> Option<Vec<Coordinate>> {
+    bidirectional_length(start, goal, |c| c == start || !blocked.contains(&c))?;
+    let mut came_from = HashMap::new();
+    let mut best = HashMap::new();
+    let mut open = BinaryHeap::new();
+    let first = (start, start_dir);
+    best.insert(first, 0);
+    let remaining = start.distance_to(goal) as u64;
+    open.push(Node { estimate: remaining, remaining, cost: 0, coord: first });
+    while let Some(Node { cost: so_far, coord: current, .. }) = open.pop() {
+        let (hex, facing) = current;
+        if hex == goal {
+            return Some(reconstruct(&came_from, current).into_iter().map(|(c, _)| c).collect());
+        }
+        if best.get(&current).is_some_and(|&b| so_far > b) {
+            continue;
+        }
+        for &dir in Direction::ALL.iter() {
+            let next = (hex + dir.offset(), dir);
+            if blocked.contains(&next.0) {
+                continue;
+            }
+            let total = so_far + 1 + if dir == facing { 0 } else { u64::from(turn_cost) };
+            if best.get(&next).is_none_or(|&b| total < b) {
+                best.insert(next, total);
+                came_from.insert(next, current);
+                let remaining = next.0.distance_to(goal) as u64;
+                open.push(Node { estimate: total + remaining, remaining, cost: total, coord: next });
+            }
+        }
+    }
+    None
+}
+
+/// Find a path like `find_path_weighted` for a unit of `side`, which must
+/// stop on entering a hex in an enemy zone of control.
+///
+/// `zones` gives the sides exerting a zone of control on each hex, as from
+/// `algorithms::zone_of_control`; any side other than `side` is an enemy.
+/// Hexes in an enemy zone can end the path but not be passed through,
+/// except for `start`, which a unit can always leave. Whether units' own
+/// hexes can be entered is up to `cost`.
+pub fn astar_with_zoc<S, F>(start: Coordinate, goal: Coordinate, side: S, zones: &HashMap<Coordinate, BTreeSet<S>>, min_cost: u32, cost: F) -> Option<Vec<Coordinate>>
+    where S: Ord,
+          F: Fn(Coordinate) -> Option<u32>
+{
+    // Only the goal can be entered and not passed through, so stopping is
+    // the same as forbidding every other hex in an enemy zone.
+    let stops = |c: Coordinate| zones.get(&c).is_some_and(|sides| sides.iter().any(|s| *s != side));
+    find_path_weighted(start, goal, min_cost, |c| if c != goal && stops(c) { None } else { cost(c) })
+}
+
+/// Find a path like `find_path_weighted`, searching from both ends at once.
+///
+/// The cost of the path found is always the same as for
+/// `find_path_weighted` with the same `min_cost`, though the path itself
+/// may differ when there are several cheapest paths. Searching from both
+/// ends usually expands far fewer hexes on long paths over varied terrain.
+///
+/// Both searches share one potential: half the difference between a hex's
+/// `distance_to` each end, scaled by `min_cost`, as in Ikeda et al.'s
+/// bidirectional A*. This keeps the two searches consistent with each
+/// other, so they can stop as soon as their lowest keys together can't beat
+/// the cheapest path found through a hex both have reached, and that path
+/// is then the cheapest. An enclosed `start` or `goal` runs its search out
+/// of hexes, so the search fails without covering the unbounded grid.
+pub fn astar_bidirectional<F>(start: Coordinate, goal: Coordinate, min_cost: u32, cost: F) -> Option<Vec<Coordinate>>
+    where F: Fn(Coordinate) -> Option<u32>
+{
+    astar_bidirectional_with_stats(start, goal, min_cost, cost).0
+}
+
+/// Find a path like `astar_bidirectional`, also counting the work done.
+pub fn astar_bidirectional_with_stats<F>(start: Coordinate, goal: Coordinate, min_cost: u32, cost: F) -> (Option<Vec<Coordinate>>, SearchStats)
+    where F: Fn(Coordinate) -> Option<u32>
+{
+    let mut stats = SearchStats::default();
+    if start == goal {
+        return (Some(vec![start]), stats);
+    }
+    // Stepping forward from a hex to its neighbor costs entering the
+    // neighbor; stepping backward costs entering the hex stepped from.
+    let forward_step = |_: Coordinate, next: Coordinate| cost(next);
+    let backward_step = |current: Coordinate, next: Coordinate| {
+        if next == start || cost(next).is_some() { cost(current) } else { None }
+    };
+    let mut forward = Frontier::new(start, goal, min_cost);
+    let mut backward = Frontier::new(goal, start, min_cost);
+    let mut meeting: Option<(u64, Coordinate)> = None;
+    let offset = 2 * start.distance_to(goal) as u64 * u64::from(min_cost);
+    while let (Some(f), Some(b)) = (forward.min_key(), backward.min_key()) {
+        if meeting.is_some_and(|(total, _)| f + b >= 2 * total + offset) {
+            break;
+        }
+        let expanded = if forward.open.len() <= backward.open.len() {
+            forward.expand(&backward, &forward_step, &mut meeting)
+        } else {
+            backward.expand(&forward, &backward_step, &mut meeting)
+        };
+        if expanded {
+            stats.expanded += 1;
+        }
+    }
+    let path = meeting.map(|(_, middle)| {
+        let mut path = reconstruct(&forward.came_from, middle);
+        let mut current = middle;
+        while let Some(next) = backward.came_from.get(&current) {
+            path.push(*next);
+            current = *next;
+        }
+        path
+    });
+    (path, stats)
+}
+
+/// One side of a bidirectional A* search.
+///
+/// Hexes are keyed by twice their cost so far plus their potential, scaled
+/// by two and offset to keep keys whole and non-negative: `2 * cost +
+/// min_cost * (distance to target - distance to source + distance between
+/// the two)`.
+struct Frontier {
+    source: Coordinate,
+    target: Coordinate,
+    min_cost: u32,
+    best: HashMap<Coordinate, u64>,
+    came_from: HashMap<Coordinate, Coordinate>,
+    open: BinaryHeap<Node>,
+}
+
+impl Frontier {
+    fn new(source: Coordinate, target: Coordinate, min_cost: u32) -> Self {
+        let mut frontier = Frontier {
+            source,
+            target,
+            min_cost,
+            best: HashMap::new(),
+            came_from: HashMap::new(),
+            open: BinaryHeap::new(),
+        };
+        frontier.best.insert(source, 0);
+        let estimate = frontier.key(source, 0);
+        let remaining = source.distance_to(target) as u64;
+        frontier.open.push(Node { estimate, remaining, cost: 0, coord: source });
+        frontier
+    }
+
+    fn key(&self, c: Coordinate, cost: u64) -> u64 {
+        let potential = c.distance_to(self.target) - c.distance_to(self.source);
+        let offset = (potential + self.source.distance_to(self.target)) as u64;
+        2 * cost + offset * u64::from(self.min_cost)
+    }
+
+    /// The lowest key in the open set. Stale entries may make this lower
+    /// than it should be, which only delays stopping.
+    fn min_key(&self) -> Option<u64> {
+        self.open.peek().map(|node| node.estimate)
+    }
+
+    /// Pop the best hex from the open set and consider its neighbors,
+    /// recording in `meeting` the cheapest path through a hex `other` has
+    /// reached. Returns whether a hex was expanded, rather than a stale entry
+    /// discarded.
+    fn expand<F>(&mut self, other: &Frontier, step: &F, meeting: &mut Option<(u64, Coordinate)>) -> bool
+        where F: Fn(Coordinate, Coordinate) -> Option<u32>
+    {
+        let Node { cost: so_far, coord: current, .. } = match self.open.pop() {
+            Some(node) => node,
+            None => return false,
+        };
+        if self.best.get(&current).is_some_and(|&b| so_far > b) {
+            return false;
+        }
+        for neighbor in current.neighbors().iter() {
+            let total = match step(current, *neighbor) {
+                Some(step) => so_far + u64::from(step),
+                None => continue,
+            };
+            if self.best.get(neighbor).is_none_or(|&b| total < b) {
+                self.best.insert(*neighbor, total);
+                self.came_from.insert(*neighbor, current);
+                let estimate = self.key(*neighbor, total);
+                let remaining = neighbor.distance_to(self.target) as u64;
+                self.open.push(Node { estimate, remaining, cost: total, coord: *neighbor });
+                if let Some(rest) = other.best.get(neighbor) {
+                    if meeting.is_none_or(|(best, _)| total + rest < best) {
+                        *meeting = Some((total + rest, *neighbor));
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Get every hex reachable from `start` within `movement_points`, with the
+/// cheapest total cost of reaching it.
+///
+/// `cost` gives the cost of entering a hex, or `None` if it is impassable,
+/// as for `find_path_weighted`. `start` is always included at cost 0, and
+/// impassable hexes never are. This is a Dijkstra search that never visits
+/// hexes beyond the budget, so it terminates on the unbounded grid as long
+/// as no passable hex is free to enter.
+pub fn field_of_movement<F>(start: Coordinate, movement_points: u32, cost: F) -> HashMap<Coordinate, u32>
+    where F: Fn(Coordinate) -> Option<u32>
+{
+    cheapest_within(&[start], movement_points, cost)
+}
+
+/// Get the cheapest cost of reaching each hex from whichever of `sources` is
+/// cheapest to reach it from, up to `max_cost`.
+///
+/// `cost` gives the cost of entering each passable hex; hexes not in it are
+/// impassable. Every source is included at cost 0. This is a single Dijkstra
+/// search seeded with all the sources at once, which is much faster than
+/// searching from each source and taking the minimum.
+pub fn multi_source_distance_field(sources: &[Coordinate], cost: &HashMap<Coordinate, u32>, max_cost: u32) -> HashMap<Coordinate, u32> {
+    cheapest_within(sources, max_cost, |c| cost.get(&c).cloned())
+}
+
+/// Dijkstra's algorithm from several sources at once, never visiting hexes
+/// that cost more than `budget` to reach.
+fn cheapest_within<F>(sources: &[Coordinate], budget: u32, cost: F) -> HashMap<Coordinate, u32>
+    where F: Fn(Coordinate) -> Option<u32>
+{
+    nearest_sources(sources, u64::from(budget), cost)
+        .into_iter()
+        .map(|(c, (total, _))| (c, total as u32))
+        .collect()
+}
+
+/// Dijkstra's algorithm from several sources at once, never visiting hexes
+/// that cost more than `budget` to reach, giving each hex its cheapest cost
+/// and the index of the source it is cheapest to reach from. Ties go to the
+/// lowest index.
+pub(crate) fn nearest_sources<F>(sources: &[Coordinate], budget: u64, cost: F) -> HashMap<Coordinate, (u64, usize)>
+    where F: Fn(Coordinate) -> Option<u32>
+{
+    let mut best: HashMap<Coordinate, (u64, usize)> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    for (i, source) in sources.iter().enumerate() {
+        if !best.contains_key(source) {
+            best.insert(*source, (0, i));
+            open.push(Node { estimate: 0, remaining: 0, cost: 0, coord: *source });
+        }
+    }
+    while let Some(Node { cost: so_far, coord: current, .. }) = open.pop() {
+        let (cheapest, source) = best[&current];
+        if so_far > cheapest {
+            continue;
+        }
+        for neighbor in current.neighbors().iter() {
+            let total = match cost(*neighbor) {
+                Some(step) => so_far + u64::from(step),
+                None => continue,
+            };
+            if total <= budget && best.get(neighbor).is_none_or(|&b| (total, source) < b) {
+                best.insert(*neighbor, (total, source));
+                open.push(Node { estimate: total, remaining: 0, cost: total, coord: *neighbor });
+            }
+        }
+    }
+    best
+}
+
+/// Which hexes count as lying between two waypoints when smoothing a path.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum LineKind {
+    /// The hexes of `Coordinate::line_to`.
+    Line,
+    /// The hexes of `Coordinate::line_to_supercover`, which keeps smoothed
+    /// paths from squeezing between two impassable hexes that share an edge.
+    Supercover,
+}
+
+/// Remove waypoints from `path` wherever a straight line can replace them.
+///
+/// Starting from the first waypoint, this keeps the furthest later waypoint
+/// that can be reached in a straight line through `passable` hexes, and
+/// repeats from there. The result starts and ends where `path` does, every
+/// pair of consecutive waypoints is joined by a passable line of `kind`, and
+/// walking those lines never takes more steps than walking `path`.
+pub fn smooth<F>(path: &[Coordinate], kind: LineKind, passable: F) -> Vec<Coordinate>
+    where F: Fn(Coordinate) -> bool
+{
+    let clear = |a: Coordinate, b: Coordinate| {
+        let line = match kind {
+            LineKind::Line => a.line_to(b),
+            LineKind::Supercover => a.line_to_supercover(b),
+        };
+        line.into_iter().all(&passable)
+    };
+    let mut smoothed = Vec::new();
+    let mut anchor = 0;
+    while let Some(&from) = path.get(anchor) {
+        smoothed.push(from);
+        // The next waypoint is always reachable, since the path steps there.
+        anchor = (anchor + 2..path.len()).rev()
+            .find(|&i| clear(from, path[i]))
+            .unwrap_or(anchor + 1);
+    }
+    smoothed
+}
+
+/// Remove the waypoints of `path` that lie on a straight line between the
+/// waypoints around them, keeping only the ends of each straight run.
+///
+/// A run is straight when its hexes are exactly the `line_to` of its ends,
+/// so unlike `smooth` this never changes which hexes the path visits.
+pub fn simplify_path(path: &[Coordinate]) -> Vec<Coordinate> {
+    let mut simplified = Vec::new();
+    let mut anchor = 0;
+    while let Some(&from) = path.get(anchor) {
+        simplified.push(from);
+        let mut end = anchor + 1;
+        while end + 1 < path.len() && from.line_to(path[end + 1]) == path[anchor..end + 2] {
+            end += 1;
+        }
+        anchor = end;
+    }
+    simplified
+}
+
+impl Coordinate {
+    /// Get the hexes reachable from this one within `steps` steps avoiding
+    /// `blocked` hexes, grouped by how many steps the shortest path takes.
+    ///
+    /// Band `i` holds the hexes exactly `i` steps away, so band 0 is just
+    /// this hex, blocked or not. There are always `steps + 1` bands, some of
+    /// them empty if the search is enclosed, or none if `steps` is negative.
+    pub fn movement_bands(&self, steps: i64, blocked: &HashSet<Coordinate>) -> Vec<HashSet<Coordinate>> {
+        let mut bands: Vec<HashSet<Coordinate>> = Vec::new();
+        let mut seen: HashSet<Coordinate> = HashSet::new();
+        for _ in 0..steps.max(-1) + 1 {
+            let band: HashSet<Coordinate> = match bands.last() {
+                None => Some(*self).into_iter().collect(),
+                Some(last) => last.iter()
+                    .flat_map(|c| c.iter_neighbors())
+                    .filter(|n| !blocked.contains(n) && !seen.contains(n))
+                    .collect(),
+            };
+            seen.extend(band.iter().cloned());
+            bands.push(band);
+        }
+        bands
+    }
+}
+
+/// Walk `came_from` links back from `goal`, returning the path in order.
+fn reconstruct<S: Eq + Hash + Copy>(came_from: &HashMap<S, S>, goal: S) -> Vec<S> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(previous) = came_from.get(&current) {
+        path.push(*previous);
+        current = *previous;
+    }
+    path.reverse();
+    path
+}
+
+/// An entry in an A* open set, ordered so that a `BinaryHeap` pops the
+/// lowest estimate first, breaking ties toward the least distance remaining
+/// to the goal and then the highest cost so far.
+///
+/// Most searches are over hexes, but a search can track more state with
+/// each hex, such as which way a unit faces.
+#[derive(PartialEq,Eq)]
+struct Node<S = Coordinate> {
+    estimate: u64,
+    remaining: u64,
+    cost: u64,
+    coord: S,
+}
+
+impl<S: Eq> Ord for Node<S> {
+    fn cmp(&self, other: &Node<S>) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+            .then_with(|| other.remaining.cmp(&self.remaining))
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+impl<S: Eq> PartialOrd for Node<S> {
+    fn partial_cmp(&self, other: &Node<S>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Get the unweighted length of a shortest path through `passable` hexes,
+/// searching from both ends at once.
+fn bidirectional_length<F>(start: Coordinate, goal: Coordinate, passable: F) -> Option<i64>
+    where F: Fn(Coordinate) -> bool
+{
+    if !passable(start) || !passable(goal) {
+        return None;
+    }
+    if start == goal {
+        return Some(0);
+    }
+    let mut forward = Search::new(start);
+    let mut backward = Search::new(goal);
+    loop {
+        let (searching, other) = if forward.frontier.len() <= backward.frontier.len() {
+            (&mut forward, &backward)
+        } else {
+            (&mut backward, &forward)
+        };
+        if searching.frontier.is_empty() {
+            return None;
+        }
+        if let Some(length) = searching.expand(other, &passable) {
+            return Some(length);
+        }
+    }
+}
+
+/// A breadth-first search out from a goal through passable hexes, run a hex
+/// at a time beside a search from the start to tell when the goal is
+/// enclosed away from it.
+struct Enclosure {
+    visited: HashSet<Coordinate>,
+    frontier: VecDeque<Coordinate>,
+    connected: bool,
+}
+
+impl Enclosure {
+    fn new<F: Fn(Coordinate) -> bool>(goal: Coordinate, passable: &F) -> Self {
+        let mut visited = HashSet::new();
+        let mut frontier = VecDeque::new();
+        if passable(goal) {
+            visited.insert(goal);
+            frontier.push_back(goal);
+        }
+        Enclosure { visited, frontier, connected: false }
+    }
+
+    /// Visit the next hex out from the goal, returning whether every hex
+    /// connected to it has been visited without meeting the search from the
+    /// start, which has `reached` the hexes it has.
+    ///
+    /// Once the two searches meet, the ends are connected and no more hexes
+    /// are visited.
+    fn is_enclosed<F, R>(&mut self, passable: &F, reached: R) -> bool
+        where F: Fn(Coordinate) -> bool,
+              R: Fn(&Coordinate) -> bool
+    {
+        if self.connected {
+            return false;
+        }
+        let current = match self.frontier.pop_front() {
+            Some(current) => current,
+            None => return true,
+        };
+        if reached(&current) {
+            self.connected = true;
+            return false;
+        }
+        for neighbor in current.neighbors().iter() {
+            if passable(*neighbor) && self.visited.insert(*neighbor) {
+                self.frontier.push_back(*neighbor);
+            }
+        }
+        false
+    }
+}
+
+/// One side of a layered bidirectional breadth-first search.
+struct Search {
+    depths: HashMap<Coordinate, i64>,
+    frontier: Vec<Coordinate>,
+    depth: i64,
+}
+
+impl Search {
+    fn new(start: Coordinate) -> Self {
+        let mut depths = HashMap::new();
+        depths.insert(start, 0);
+        Search { depths, frontier: vec![start], depth: 0 }
+    }
+
+    /// Expand the whole frontier by one layer, returning the shortest length
+    /// through any hex where this search meets `other`.
+    fn expand<F: Fn(Coordinate) -> bool>(&mut self, other: &Search, passable: &F) -> Option<i64> {
+        let mut next = vec![];
+        let mut best = None;
+        for current in self.frontier.drain(..) {
+            for neighbor in current.neighbors().iter() {
+                if !passable(*neighbor) || self.depths.contains_key(neighbor) {
+                    continue;
+                }
+                if let Some(depth) = other.depths.get(neighbor) {
+                    let length = self.depth + 1 + depth;
+                    best = Some(best.map_or(length, |b: i64| b.min(length)));
+                }
+                self.depths.insert(*neighbor, self.depth + 1);
+                next.push(*neighbor);
+            }
+        }
+        self.frontier = next;
+        self.depth += 1;
+        best
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use algorithms::zone_of_control;
+
+    fn ring_around(center: Coordinate) -> HashSet<Coordinate> {
+        center.neighbors().iter().cloned().collect()
+    }
+
+    #[test]
+    fn it_measures_distance_on_an_open_field() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let goal = Coordinate::at(2, 7, -9).unwrap();
+        let blocked = HashSet::new();
+        assert_eq!(path_length(start, goal, &blocked), Some(start.distance_to(goal)));
+        assert_eq!(path_length(start, start, &blocked), Some(0));
+    }
+
+    #[test]
+    fn it_measures_around_a_wall() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(3, 0, -3).unwrap();
+        let blocked: HashSet<Coordinate> = (-2..3)
+            .map(|y| Coordinate::at(1, y, -1 - y).unwrap())
+            .collect();
+        let length = path_length(start, goal, &blocked).unwrap();
+        assert!(length > start.distance_to(goal));
+    }
+
+    #[test]
+    fn it_finds_no_path_to_an_enclosed_goal() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(5, -2, -3).unwrap();
+        assert_eq!(path_length(start, goal, &ring_around(goal)), None);
+        assert_eq!(path_length(goal, start, &ring_around(goal)), None);
+    }
+
+    #[test]
+    fn it_measures_paths_within_a_bound() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(3, 0, -3).unwrap();
+        let blocked: HashSet<Coordinate> = (-2..3)
+            .map(|y| Coordinate::at(1, y, -1 - y).unwrap())
+            .collect();
+        let passable = |c: Coordinate| !blocked.contains(&c);
+        let length = path_length(start, goal, &blocked).unwrap() as u32;
+        assert_eq!(path_distance(start, goal, length, passable), Some(length));
+        assert_eq!(path_distance(start, goal, length + 10, passable), Some(length));
+        assert_eq!(path_distance(start, goal, length - 1, passable), None);
+        assert_eq!(path_distance(start, start, 0, passable), Some(0));
+        assert_eq!(path_distance(start, goal, 2, |_| true), None);
+        assert_eq!(path_distance(start, goal, 3, |_| true), Some(3));
+    }
+
+    #[test]
+    fn it_stays_within_the_bound_when_measuring() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(5, -2, -3).unwrap();
+        let enclosed = ring_around(goal);
+        for max in [5, 10, 20].iter() {
+            let passable = |c: Coordinate| !enclosed.contains(&c);
+            let (length, stats) = path_distance_with_stats(start, goal, *max, passable);
+            assert_eq!(length, None);
+            assert!(stats.expanded > 0);
+            assert!(stats.expanded <= Coordinate::spiral_size(i64::from(*max)));
+        }
+        let (length, stats) = path_distance_with_stats(start, goal, 100, |_| true);
+        assert_eq!(length, Some(5));
+        assert!(stats.expanded <= Coordinate::spiral_size(5));
+    }
+
+    #[test]
+    fn it_prefers_a_cheap_detour_when_weighted() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(4, 0, -4).unwrap();
+        // The straight line along y == 0 is swamp; the row above it is road.
+        let cost = |c: Coordinate| Some(if c.y == 0 && c != goal { 10 } else { 1 });
+        let path = find_path_weighted(start, goal, 1, cost).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(path.len(), 6);
+        for pair in path.windows(2) {
+            assert_eq!(pair[0].distance_to(pair[1]), 1);
+        }
+        let total: u32 = path[1..].iter().map(|c| cost(*c).unwrap()).sum();
+        assert_eq!(total, 5);
+    }
+
+    #[test]
+    fn it_finds_the_cheapest_path_through_free_hexes() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(4, 0, -4).unwrap();
+        // A road two rows above start is free to enter, so climbing to it
+        // and back beats the straight line.
+        let road = |c: Coordinate| c.y == -2 && c.x >= 0 && c.x <= 5;
+        let cost = |c: Coordinate| Some(if road(c) { 0 } else { 1 });
+        let path = find_path_weighted(start, goal, 0, cost).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        for pair in path.windows(2) {
+            assert_eq!(pair[0].distance_to(pair[1]), 1);
+        }
+        let total: u32 = path[1..].iter().map(|c| cost(*c).unwrap()).sum();
+        assert_eq!(total, 3);
+    }
+
+    #[test]
+    fn it_heads_straight_for_the_goal_over_free_hexes() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(30, -10, -20).unwrap();
+        let (path, stats) = find_path_weighted_with_stats(start, goal, 0, |_| Some(0));
+        let path = path.unwrap();
+        assert_eq!(path.len(), 31);
+        assert!(path.windows(2).all(|pair| pair[0].distance_to(pair[1]) == 1));
+        assert_eq!(stats.expanded, 30);
+    }
+
+    #[test]
+    fn it_never_enters_impassable_hexes_when_weighted() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(3, 0, -3).unwrap();
+        let wall: HashSet<Coordinate> = (-2..3)
+            .map(|y| Coordinate::at(1, y, -1 - y).unwrap())
+            .collect();
+        let cost = |c: Coordinate| if wall.contains(&c) { None } else { Some(1) };
+        let path = find_path_weighted(start, goal, 1, cost).unwrap();
+        assert!(path.iter().all(|c| !wall.contains(c)));
+        assert_eq!(path.len() as i64 - 1, path_length(start, goal, &wall).unwrap());
+    }
+
+    #[test]
+    fn it_finds_no_weighted_path_to_an_enclosed_goal() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(5, -2, -3).unwrap();
+        let wall = ring_around(goal);
+        let cost = |c: Coordinate| if wall.contains(&c) { None } else { Some(1) };
+        assert_eq!(find_path_weighted(start, goal, 1, cost), None);
+        assert_eq!(find_path_weighted(goal, start, 1, cost), None);
+    }
+
+    #[test]
+    fn it_finds_weighted_paths_within_an_enclosure() {
+        let center = Coordinate::at(5, -2, -3).unwrap();
+        let wall: HashSet<Coordinate> = center.iter_ring(3).collect();
+        let cost = |c: Coordinate| if wall.contains(&c) { None } else { Some(1) };
+        let start = center + Coordinate::at(-2, 0, 2).unwrap();
+        let goal = center + Coordinate::at(2, 0, -2).unwrap();
+        assert_eq!(find_path_weighted(start, goal, 1, cost).map(|path| path.len()), Some(5));
+    }
+
+    #[test]
+    fn it_moves_further_along_roads() {
+        let start = Coordinate::new();
+        // The row through start is road; everything else is rough.
+        let cost = |c: Coordinate| Some(if c.y == 0 { 1 } else { 3 });
+        let field = field_of_movement(start, 6, cost);
+        assert_eq!(field.get(&start), Some(&0));
+        assert_eq!(field.get(&Coordinate::at(6, 0, -6).unwrap()), Some(&6));
+        assert_eq!(field.get(&Coordinate::at(7, 0, -7).unwrap()), None);
+        // Two steps off the road costs 6, but three doesn't fit.
+        assert_eq!(field.get(&Coordinate::at(0, 2, -2).unwrap()), Some(&6));
+        assert_eq!(field.get(&Coordinate::at(0, 3, -3).unwrap()), None);
+        assert!(field.values().all(|&total| total <= 6));
+    }
+
+    #[test]
+    fn it_excludes_impassable_and_expensive_hexes_from_movement() {
+        let start = Coordinate::new();
+        let wall = ring_around(Coordinate::at(3, 0, -3).unwrap());
+        let swamp = Coordinate::at(-1, 0, 1).unwrap();
+        let cost = |c: Coordinate| match c {
+            c if wall.contains(&c) => None,
+            c if c == swamp => Some(5),
+            _ => Some(1),
+        };
+        let field = field_of_movement(start, 4, cost);
+        assert!(wall.iter().all(|c| !field.contains_key(c)));
+        assert!(!field.contains_key(&Coordinate::at(3, 0, -3).unwrap()));
+        assert!(!field.contains_key(&swamp));
+        assert_eq!(field.get(&(swamp + swamp)), Some(&3));
+    }
+
+    #[test]
+    fn it_bands_an_open_field_by_ring() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let bands = start.movement_bands(5, &HashSet::new());
+        assert_eq!(bands.len(), 6);
+        for (i, band) in bands.iter().enumerate() {
+            let ring: HashSet<Coordinate> = start.ring(i as i64).into_iter().collect();
+            assert_eq!(*band, ring);
+        }
+        assert!(start.movement_bands(-1, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn it_pushes_hexes_behind_a_wall_into_later_bands() {
+        let start = Coordinate::new();
+        let blocked: HashSet<Coordinate> = (-2..3)
+            .map(|y| Coordinate::at(1, y, -1 - y).unwrap())
+            .collect();
+        let behind = Coordinate::at(2, 0, -2).unwrap();
+        let bands = start.movement_bands(8, &blocked);
+        let band_of = |c: Coordinate| bands.iter().position(|band| band.contains(&c));
+        assert_eq!(band_of(behind), path_length(start, behind, &blocked).map(|n| n as usize));
+        assert!(band_of(behind).unwrap() > start.distance_to(behind) as usize);
+        assert!(blocked.iter().all(|c| band_of(*c).is_none()));
+        for c in start.spiral(8) {
+            let length = path_length(start, c, &blocked).filter(|&n| n <= 8);
+            assert_eq!(band_of(c), length.map(|n| n as usize), "{:?}", c);
+        }
+        let enclosed = start.movement_bands(3, &ring_around(start));
+        assert_eq!(enclosed.iter().map(|band| band.len()).collect::<Vec<_>>(), [1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn it_agrees_with_per_target_searches() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        // Scattered impassable hexes among terrain of varying cost.
+        let cost = |c: Coordinate| match (c.x.rem_euclid(4), c.y.rem_euclid(3)) {
+            (0, 0) => None,
+            (1, _) => Some(3),
+            (_, r) => Some(1 + r as u32),
+        };
+        let field = field_of_movement(start, 7, cost);
+        for target in start.spiral(7) {
+            let cheapest = find_path_weighted(start, target, 1, cost)
+                .map(|path| path[1..].iter().map(|c| cost(*c).unwrap()).sum::<u32>())
+                .filter(|&total| total <= 7);
+            assert_eq!(field.get(&target).cloned(), cheapest, "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn it_measures_from_the_nearest_source() {
+        let a = Coordinate::at(-3, 0, 3).unwrap();
+        let b = Coordinate::at(3, 0, -3).unwrap();
+        let cost: HashMap<Coordinate, u32> = Coordinate::new().spiral(8).into_iter()
+            .map(|c| (c, 2))
+            .collect();
+        let field = multi_source_distance_field(&[a, b], &cost, 12);
+        assert_eq!(field.get(&a), Some(&0));
+        assert_eq!(field.get(&b), Some(&0));
+        for (hex, total) in field.iter() {
+            let nearest = a.distance_to(*hex).min(b.distance_to(*hex));
+            assert_eq!(i64::from(*total), 2 * nearest);
+        }
+        // The hexes equidistant from both sources lie on the line x == z.
+        for x in -3..4 {
+            let middle = Coordinate::at(x, -2 * x, x).unwrap();
+            assert_eq!(a.distance_to(middle), b.distance_to(middle));
+            assert_eq!(field.get(&middle).cloned(), Some(2 * a.distance_to(middle) as u32));
+        }
+        let nearer_a = Coordinate::at(-1, 0, 1).unwrap();
+        assert_eq!(field.get(&nearer_a), Some(&4));
+        assert_eq!(field.get(&Coordinate::at(9, -9, 0).unwrap()), None);
+    }
+
+    fn walked_length(path: &[Coordinate]) -> i64 {
+        path.windows(2).map(|pair| pair[0].distance_to(pair[1])).sum()
+    }
+
+    #[test]
+    fn it_straightens_paths_across_open_ground() {
+        let start = Coordinate::new();
+        let corner = Coordinate::at(4, 0, -4).unwrap();
+        let goal = Coordinate::at(4, 4, -8).unwrap();
+        let mut path = start.line_to(corner);
+        path.extend(&corner.line_to(goal)[1..]);
+        for kind in [LineKind::Line, LineKind::Supercover].iter() {
+            let smoothed = smooth(&path, *kind, |_| true);
+            assert_eq!(smoothed, vec![start, goal]);
+            assert!(walked_length(&smoothed) <= walked_length(&path));
+        }
+    }
+
+    #[test]
+    fn it_keeps_corners_around_walls() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(4, 0, -4).unwrap();
+        let wall: HashSet<Coordinate> = (-3..4)
+            .map(|y| Coordinate::at(2, y, -2 - y).unwrap())
+            .collect();
+        let passable = |c: Coordinate| !wall.contains(&c);
+        let cost = |c: Coordinate| if passable(c) { Some(1) } else { None };
+        let path = find_path_weighted(start, goal, 1, cost).unwrap();
+        for kind in [LineKind::Line, LineKind::Supercover].iter() {
+            let smoothed = smooth(&path, *kind, passable);
+            assert_eq!(smoothed.first(), Some(&start));
+            assert_eq!(smoothed.last(), Some(&goal));
+            assert!(smoothed.len() > 2 && smoothed.len() < path.len());
+            for pair in smoothed.windows(2) {
+                assert!(pair[0].line_to(pair[1]).into_iter().all(passable));
+            }
+            assert!(walked_length(&smoothed) <= walked_length(&path));
+        }
+    }
+
+    #[test]
+    fn it_smooths_trivial_paths() {
+        let start = Coordinate::new();
+        assert_eq!(smooth(&[], LineKind::Line, |_| true), vec![]);
+        assert_eq!(smooth(&[start], LineKind::Line, |_| true), vec![start]);
+    }
+
+    #[test]
+    fn it_collapses_straight_paths() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let end = start + Coordinate::at(3, -5, 2).unwrap();
+        let path = start.line_to(end);
+        assert_eq!(simplify_path(&path), vec![start, end]);
+        assert_eq!(simplify_path(&[]), vec![]);
+        assert_eq!(simplify_path(&[start]), vec![start]);
+    }
+
+    #[test]
+    fn it_keeps_corners_when_simplifying() {
+        let start = Coordinate::new();
+        let corner = Coordinate::at(3, 0, -3).unwrap();
+        let end = Coordinate::at(3, 3, -6).unwrap();
+        let mut path = start.line_to(corner);
+        path.extend(corner.line_to(end).into_iter().skip(1));
+        assert_eq!(simplify_path(&path), vec![start, corner, end]);
+        let there_and_back = vec![start, corner, start];
+        assert_eq!(simplify_path(&there_and_back), there_and_back);
+    }
+
+    #[test]
+    fn it_stops_on_entering_enemy_zones() {
+        let start = Coordinate::new();
+        let enemies = [Coordinate::at(2, -1, -1).unwrap(), Coordinate::at(2, 1, -3).unwrap()];
+        let units = [(start, 0), (enemies[0], 1), (enemies[1], 1)];
+        let zones = zone_of_control(&units);
+        let cost = |c: Coordinate| if enemies.contains(&c) { None } else { Some(1) };
+        // The hex between the enemies is surrounded by their zones.
+        let screened = Coordinate::at(2, 0, -2).unwrap();
+        assert_eq!(astar_with_zoc(start, screened, 0, &zones, 1, cost), None);
+        // A hex in their zones can still be entered and stopped in.
+        let adjacent = Coordinate::at(1, 0, -1).unwrap();
+        assert_eq!(astar_with_zoc(start, adjacent, 0, &zones, 1, cost), Some(vec![start, adjacent]));
+        // Getting past them takes the long way around.
+        let goal = Coordinate::at(4, 0, -4).unwrap();
+        let path = astar_with_zoc(start, goal, 0, &zones, 1, cost).unwrap();
+        assert_eq!(path[path.len() - 1], goal);
+        assert!(path.len() - 1 > start.distance_to(goal) as usize);
+        for c in path[1..path.len() - 1].iter() {
+            assert!(zones.get(c).is_none_or(|sides| sides.iter().all(|s| *s == 0)));
+        }
+        // A unit can leave an enemy zone it starts in, and its own side's
+        // zones never stop it.
+        let path = astar_with_zoc(adjacent, goal, 0, &zones, 1, cost).unwrap();
+        assert_eq!(path[0], adjacent);
+        let path = astar_with_zoc(goal, adjacent, 1, &zones, 1, |_| Some(1)).unwrap();
+        assert_eq!(path.len() - 1, goal.distance_to(adjacent) as usize);
+    }
+
+    fn path_cost<F: Fn(Coordinate) -> Option<u32>>(path: &[Coordinate], cost: F) -> u32 {
+        path[1..].iter().map(|c| cost(*c).unwrap()).sum()
+    }
+
+    /// Terrain costing `least` to 3 more, with one hex in ten impassable,
+    /// derived from `seed`.
+    fn terrain(seed: u64, least: u32) -> impl Fn(Coordinate) -> Option<u32> {
+        move |c: Coordinate| {
+            let mut h = seed ^ (c.x as u64).wrapping_mul(0x9e3779b97f4a7c15);
+            h = (h ^ (c.y as u64).wrapping_mul(0xc2b2ae3d27d4eb4f)).wrapping_mul(0x165667b19e3779f9);
+            h ^= h >> 29;
+            match h % 10 {
+                0 => None,
+                n => Some(least + (n % 4) as u32),
+            }
+        }
+    }
+
+    #[test]
+    fn it_matches_astar_between_adjacent_hexes() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let cost = |_: Coordinate| Some(3);
+        for neighbor in start.neighbors().iter() {
+            assert_eq!(astar_bidirectional(start, *neighbor, 3, cost), Some(vec![start, *neighbor]));
+        }
+        assert_eq!(astar_bidirectional(start, start, 3, cost), Some(vec![start]));
+    }
+
+    #[test]
+    fn it_finds_no_bidirectional_path_to_an_enclosed_goal() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(5, -2, -3).unwrap();
+        let wall = ring_around(goal);
+        let cost = |c: Coordinate| if wall.contains(&c) { None } else { Some(1) };
+        assert_eq!(astar_bidirectional(start, goal, 1, cost), None);
+        assert_eq!(astar_bidirectional(goal, start, 1, cost), None);
+    }
+
+    #[test]
+    fn it_meets_in_the_middle_over_free_hexes() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(30, -10, -20).unwrap();
+        let path = astar_bidirectional(start, goal, 0, |_| Some(0)).unwrap();
+        assert_eq!(path.len(), 31);
+        assert!(path.windows(2).all(|pair| pair[0].distance_to(pair[1]) == 1));
+    }
+
+    #[test]
+    fn it_expands_fewer_hexes_bidirectionally() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(40, -20, -20).unwrap();
+        let cost = terrain(7, 1);
+        // Both searches use the same heuristic, so only searching from both
+        // ends can account for the difference.
+        let (one_way, one_way_stats) = find_path_weighted_with_stats(start, goal, 1, &cost);
+        let (both_ways, both_ways_stats) = astar_bidirectional_with_stats(start, goal, 1, &cost);
+        assert_eq!(path_cost(&one_way.unwrap(), &cost), path_cost(&both_ways.unwrap(), &cost));
+        assert!(both_ways_stats.expanded < one_way_stats.expanded);
+    }
+
+    proptest! {
+        #[test]
+        fn it_matches_astar_costs_on_random_terrain(
+            seed: u64, least in 0u32..2, x in -12i64..13, y in -12i64..13
+        ) {
+            let start = Coordinate::new();
+            let goal = Coordinate::from_axial(x, y);
+            // With a least cost of 0, one hex in five is free to enter.
+            let cost = terrain(seed, least);
+            let one_way = find_path_weighted(start, goal, least, &cost);
+            let both_ways = astar_bidirectional(start, goal, least, &cost);
+            prop_assert_eq!(one_way.is_some(), both_ways.is_some());
+            if let (Some(one_way), Some(both_ways)) = (one_way, both_ways) {
+                prop_assert_eq!(both_ways.first(), Some(&start));
+                prop_assert_eq!(both_ways.last(), Some(&goal));
+                for pair in both_ways.windows(2) {
+                    prop_assert_eq!(pair[0].distance_to(pair[1]), 1);
+                }
+                prop_assert_eq!(path_cost(&one_way, &cost), path_cost(&both_ways, &cost));
+            }
+        }
+    }
+
+    /// Count the changes of direction along a path.
+    fn turns(path: &[Coordinate]) -> usize {
+        path.windows(3).filter(|w| w[1] - w[0] != w[2] - w[1]).count()
+    }
+
+    #[test]
+    fn it_prefers_straighter_paths_when_turning_costs() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(12, -6, -6).unwrap();
+        let blocked: HashSet<_> = start.spiral(14).into_iter()
+            .filter(|c| *c != start && *c != goal && terrain(1, 1)(*c).is_none())
+            .collect();
+        let free = find_path_turn_penalty(start, Direction::E, goal, &blocked, 0).unwrap();
+        let costly = find_path_turn_penalty(start, Direction::E, goal, &blocked, 10).unwrap();
+        for path in [&free, &costly].iter() {
+            assert_eq!((path[0], path[path.len() - 1]), (start, goal));
+            assert!(path.windows(2).all(|w| w[0].distance_to(w[1]) == 1));
+            assert!(path.iter().all(|c| !blocked.contains(c)));
+        }
+        assert_eq!(free.len() as i64, path_length(start, goal, &blocked).unwrap() + 1);
+        assert!(turns(&costly) < turns(&free));
+        assert!(costly.len() > free.len());
+    }
+
+    #[test]
+    fn it_counts_the_first_step_as_a_turn_from_the_start_facing() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let goal = start + Coordinate::at(3, -3, 0).unwrap();
+        let blocked = HashSet::new();
+        let ahead = find_path_turn_penalty(start, Direction::NE, goal, &blocked, 5).unwrap();
+        assert_eq!(ahead, start.line_to(goal));
+        let behind = find_path_turn_penalty(start, Direction::SW, goal, &blocked, 5).unwrap();
+        assert_eq!(behind.len(), 4);
+        assert_eq!(turns(&behind), 0);
+        let walled: HashSet<_> = goal.ring(1).into_iter().collect();
+        assert_eq!(find_path_turn_penalty(start, Direction::NE, goal, &walled, 5), None);
+        assert_eq!(find_path_turn_penalty(start, Direction::E, start, &walled, 5), Some(vec![start]));
+    }
+
+    #[test]
+    fn it_finds_no_path_to_a_blocked_goal() {
+        let start = Coordinate::new();
+        let goal = Coordinate::at(5, -2, -3).unwrap();
+        let blocked = vec![goal].into_iter().collect();
+        assert_eq!(path_length(start, goal, &blocked), None);
+    }
+}
@@ -0,0 +1,226 @@
+//! Regions bounded along each cube axis.
+use {Coordinate, Offset};
+
+
+/// The hexes whose cube components each lie within a range, inclusive.
+///
+/// Hexagons, triangles, parallelograms, and the other convex shapes with
+/// edges along the grid are all regions like this. The bounds need not be
+/// tight: a component can be limited more by the other two than by its own
+/// range, and a region can be empty.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct HexRegion {
+    pub x_min: i64,
+    pub x_max: i64,
+    pub y_min: i64,
+    pub y_max: i64,
+    pub z_min: i64,
+    pub z_max: i64,
+}
+
+impl HexRegion {
+    /// Create the region of hexes within `radius` of `center`.
+    pub fn hexagon(center: Coordinate, radius: i64) -> Self {
+        HexRegion {
+            x_min: center.x - radius,
+            x_max: center.x + radius,
+            y_min: center.y - radius,
+            y_max: center.y + radius,
+            z_min: center.z - radius,
+            z_max: center.z + radius,
+        }
+    }
+
+    /// Whether a hex is in the region.
+    pub fn contains(&self, c: Coordinate) -> bool {
+        self.x_min <= c.x && c.x <= self.x_max
+            && self.y_min <= c.y && c.y <= self.y_max
+            && self.z_min <= c.z && c.z <= self.z_max
+    }
+
+    /// Iterate over every hex in the region, by increasing `y` and then
+    /// increasing `x`, i.e. in `Ord` order.
+    pub fn iter(&self) -> impl Iterator<Item = Coordinate> {
+        let region = *self;
+        (region.y_min..region.y_max + 1).flat_map(move |y| {
+            let (lo, hi) = region.row(y);
+            (lo..hi + 1).map(move |x| Coordinate::at_unchecked(x, y, -x - y))
+        })
+    }
+
+    /// Iterate over the region's rows in `offset` coordinates, by increasing
+    /// row, each iterating over its hexes by increasing column. Rows with no
+    /// hexes are skipped.
+    ///
+    /// With `Offset::OddR` or `Offset::EvenR` each row is found directly; with
+    /// the `Q` conventions, which stagger rows, each is checked column by
+    /// column.
+    pub fn rows(&self, offset: Offset)
+        -> impl Iterator<Item = impl Iterator<Item = Coordinate> + Clone>
+    {
+        self.lines(offset, false)
+    }
+
+    /// Iterate over the region's columns in `offset` coordinates, by
+    /// increasing column, each iterating over its hexes by increasing row,
+    /// as for `rows`.
+    pub fn columns(&self, offset: Offset)
+        -> impl Iterator<Item = impl Iterator<Item = Coordinate> + Clone>
+    {
+        self.lines(offset, true)
+    }
+
+    /// Get the least and greatest offset column, or row, of hexes in the
+    /// region, which is empty if the least is greater. Each axial row or
+    /// column is checked only at its ends, since offset columns increase
+    /// along a row and offset rows along a column.
+    pub(crate) fn offset_extent(&self, offset: Offset, columns: bool) -> (i64, i64) {
+        let component = |c: Coordinate| {
+            let (column, row) = c.to_offset(offset);
+            if columns { column } else { row }
+        };
+        let ends = |a: i64| if offset.shifts_rows() {
+            let (lo, hi) = self.row(a);
+            (lo <= hi).then(|| {
+                (component(Coordinate::from_axial(lo, a)), component(Coordinate::from_axial(hi, a)))
+            })
+        } else {
+            let (lo, hi) = self.column(a);
+            (lo <= hi).then(|| {
+                (component(Coordinate::from_axial(a, lo)), component(Coordinate::from_axial(a, hi)))
+            })
+        };
+        let axial = if offset.shifts_rows() {
+            self.y_min..self.y_max + 1
+        } else {
+            self.x_min..self.x_max + 1
+        };
+        axial.filter_map(ends).fold((i64::MAX, i64::MIN), |(lo, hi), (a, b)| (lo.min(a), hi.max(b)))
+    }
+
+    /// Iterate over the region's offset rows, or columns, skipping empty
+    /// ones.
+    fn lines(&self, offset: Offset, columns: bool)
+        -> impl Iterator<Item = impl Iterator<Item = Coordinate> + Clone>
+    {
+        let region = *self;
+        let (lo, hi) = region.offset_extent(offset, columns);
+        let across = region.offset_extent(offset, !columns);
+        (lo..hi + 1)
+            .map(move |line| region.line(offset, columns, line, across))
+            .filter(|hexes| hexes.clone().next().is_some())
+    }
+
+    /// Iterate over the hexes of one offset row, or column, in order, given
+    /// the extent of the region across lines.
+    fn line(self, offset: Offset, column: bool, line: i64, across: (i64, i64))
+        -> impl Iterator<Item = Coordinate> + Clone
+    {
+        let at = move |along: i64| if column {
+            Coordinate::from_offset(line, along, offset)
+        } else {
+            Coordinate::from_offset(along, line, offset)
+        };
+        // A line that is also an axial row or column is contiguous, so only
+        // its ends need finding; a staggered line is checked hex by hex.
+        let (lo, hi) = if column == offset.shifts_rows() {
+            across
+        } else if column {
+            let (lo, hi) = self.column(line);
+            (lo - at(0).y, hi - at(0).y)
+        } else {
+            let (lo, hi) = self.row(line);
+            (lo - at(0).x, hi - at(0).x)
+        };
+        (lo..hi + 1).map(at).filter(move |c| self.contains(*c))
+    }
+
+    /// Get the least and greatest `x` in the row at `y`, which is empty if
+    /// the least is greater.
+    pub(crate) fn row(&self, y: i64) -> (i64, i64) {
+        (self.x_min.max(-y - self.z_max), self.x_max.min(-y - self.z_min))
+    }
+
+    /// Get the least and greatest `y` in the column at `x`, which is empty
+    /// if the least is greater.
+    pub(crate) fn column(&self, x: i64) -> (i64, i64) {
+        (self.y_min.max(-x - self.z_max), self.y_max.min(-x - self.z_min))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_matches_hexagons() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for radius in 0..5 {
+            let region = HexRegion::hexagon(center, radius);
+            let mut expected: Vec<_> = center.iter_spiral(radius).collect();
+            expected.sort();
+            assert_eq!(region.iter().collect::<Vec<_>>(), expected);
+            for c in center.iter_spiral(radius + 2) {
+                assert_eq!(region.contains(c), center.distance_to(c) <= radius);
+            }
+        }
+    }
+
+    #[test]
+    fn it_describes_triangles() {
+        // Every hex with nonnegative x and y whose z is at least -3.
+        let region = HexRegion { x_min: 0, x_max: 10, y_min: 0, y_max: 10, z_min: -3, z_max: 10 };
+        assert_eq!(region.iter().count(), 4 + 3 + 2 + 1);
+        assert!(region.contains(Coordinate::at(3, 0, -3).unwrap()));
+        assert!(!region.contains(Coordinate::at(3, 1, -4).unwrap()));
+        assert!(region.iter().all(|c| region.contains(c)));
+    }
+
+    #[test]
+    fn it_can_be_empty() {
+        let region = HexRegion { x_min: 1, x_max: 2, y_min: 1, y_max: 2, z_min: 1, z_max: 2 };
+        assert_eq!(region.iter().count(), 0);
+        assert!(!region.contains(Coordinate::new()));
+    }
+
+    /// Check that `lines` cover `expected` once each, in increasing `line`
+    /// and then `along` order of offset components.
+    fn assert_ordered<I, J>(lines: I, expected: Vec<Coordinate>, offset: Offset, columns: bool)
+        where I: Iterator<Item = J>, J: Iterator<Item = Coordinate>
+    {
+        let key = |c: Coordinate| {
+            let (column, row) = c.to_offset(offset);
+            if columns { (column, row) } else { (row, column) }
+        };
+        let lines: Vec<Vec<Coordinate>> = lines.map(|line| line.collect()).collect();
+        let mut all: Vec<Coordinate> = lines.iter().flatten().cloned().collect();
+        for line in lines.iter() {
+            assert!(line.iter().all(|c| key(*c).0 == key(line[0]).0));
+            assert!(line.windows(2).all(|w| key(w[0]).1 < key(w[1]).1));
+        }
+        assert!(lines.windows(2).all(|w| key(w[0][0]).0 < key(w[1][0]).0));
+        let mut expected = expected;
+        all.sort();
+        expected.sort();
+        assert_eq!(all, expected);
+    }
+
+    #[test]
+    fn it_iterates_in_offset_rows_and_columns() {
+        let offsets = [Offset::OddR, Offset::EvenR, Offset::OddQ, Offset::EvenQ];
+        let hexagon = HexRegion::hexagon(Coordinate::at(-3, -1, 4).unwrap(), 3);
+        let triangle = HexRegion {
+            x_min: -5, x_max: 10, y_min: -3, y_max: 10, z_min: -3, z_max: 20,
+        };
+        for region in [hexagon, triangle].iter() {
+            for offset in offsets.iter() {
+                let hexes: Vec<_> = region.iter().collect();
+                assert_ordered(region.rows(*offset), hexes.clone(), *offset, false);
+                assert_ordered(region.columns(*offset), hexes, *offset, true);
+            }
+        }
+        let empty = HexRegion { x_min: 1, x_max: 2, y_min: 1, y_max: 2, z_min: 1, z_max: 2 };
+        assert_eq!(empty.rows(Offset::OddQ).count(), 0);
+    }
+}
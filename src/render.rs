@@ -0,0 +1,140 @@
+//! Drawing maps, e.g. for debugging.
+use std::collections::HashSet;
+
+#[cfg(feature = "image")]
+use image::{Rgba, RgbaImage};
+
+#[cfg(feature = "image")]
+use {HexMap, Layout};
+use {Coordinate, Offset};
+
+
+/// Draw hexes as text, with `#` for each hex and spaces for gaps.
+///
+/// Hexes are laid out as in a pointy-top layout, in `Offset::OddR` rows two
+/// characters apart, with odd rows shifted right by one character. The
+/// drawing is cropped to the hexes given, without trailing spaces, and rows
+/// are separated by newlines. No hexes draw an empty string.
+pub fn render_ascii<I: IntoIterator<Item = Coordinate>>(coords: I) -> String {
+    let cells: HashSet<(i64, i64)> = coords.into_iter()
+        .map(|c| {
+            let (column, row) = c.to_offset(Offset::OddR);
+            (2 * column + (row & 1), row)
+        })
+        .collect();
+    let (first_x, first_row) = match (cells.iter().map(|&(x, _)| x).min(), cells.iter().map(|&(_, r)| r).min()) {
+        (Some(x), Some(row)) => (x, row),
+        _ => return String::new(),
+    };
+    let last_row = cells.iter().map(|&(_, r)| r).max().unwrap_or(first_row);
+    let lines: Vec<String> = (first_row..last_row + 1)
+        .map(|row| {
+            let last_x = cells.iter().filter(|&&(_, r)| r == row).map(|&(x, _)| x).max();
+            match last_x {
+                Some(last_x) => (first_x..last_x + 1)
+                    .map(|x| if cells.contains(&(x, row)) { '#' } else { ' ' })
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// The empty pixels `to_image` leaves around a map.
+#[cfg(feature = "image")]
+const MARGIN: u32 = 2;
+
+/// Draw a map as an image, filling each hex as drawn by `layout` with the
+/// color `color` gives for it and its value, as RGBA.
+///
+/// The image covers the corners of every hex, plus a margin of 2 pixels,
+/// with the map shifted by whole pixels so that hexes drawn at negative
+/// pixel positions land inside it. Each pixel is colored
+/// by the hex containing its center, as found by `Layout::nearest_hex`, so
+/// hexes tile without gaps or overlaps. Pixels in no hex of the map are
+/// transparent, and colors are written as given, alpha included, so the
+/// image composites correctly over whatever it is drawn on.
+#[cfg(feature = "image")]
+pub fn to_image<T, F>(layout: &Layout, map: &HexMap<T>, color: F) -> RgbaImage
+    where F: Fn(Coordinate, &T) -> [u8; 4]
+{
+    let corners: Vec<(f64, f64)> = map.iter()
+        .flat_map(|(c, _)| layout.hex_corners(c).to_vec())
+        .collect();
+    if corners.is_empty() {
+        return RgbaImage::new(2 * MARGIN, 2 * MARGIN);
+    }
+    let min_x = corners.iter().map(|p| p.0).fold(f64::INFINITY, f64::min).floor();
+    let min_y = corners.iter().map(|p| p.1).fold(f64::INFINITY, f64::min).floor();
+    let max_x = corners.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max).ceil();
+    let max_y = corners.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max).ceil();
+    let width = (max_x - min_x) as u32 + 2 * MARGIN;
+    let height = (max_y - min_y) as u32 + 2 * MARGIN;
+    let (left, top) = (min_x - f64::from(MARGIN), min_y - f64::from(MARGIN));
+    RgbaImage::from_fn(width, height, |x, y| {
+        let pixel = (left + f64::from(x) + 0.5, top + f64::from(y) + 0.5);
+        let hex = layout.nearest_hex(pixel);
+        Rgba(map.get(hex).map_or([0; 4], |value| color(hex, value)))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_draws_a_single_hex() {
+        let coord = Coordinate::at(-3, -1, 4).unwrap();
+        assert_eq!(render_ascii(vec![coord]), "#");
+        assert_eq!(render_ascii(vec![]), "");
+    }
+
+    #[test]
+    fn it_draws_a_ring() {
+        let ring = Coordinate::new().ring(1);
+        assert_eq!(render_ascii(ring), " # #\n#   #\n # #");
+    }
+
+    #[test]
+    fn it_draws_a_filled_hexagon_around_any_center() {
+        let expected = "  # # #\n # # # #\n# # # # #\n # # # #\n  # # #";
+        assert_eq!(render_ascii(Coordinate::new().spiral(2)), expected);
+        let center = Coordinate::at(4, -2, -2).unwrap();
+        assert_eq!(render_ascii(center.spiral(2)), expected);
+    }
+
+    #[test]
+    fn it_keeps_empty_rows() {
+        let a = Coordinate::new();
+        let b = Coordinate::at(0, 2, -2).unwrap();
+        assert_eq!(render_ascii(vec![a, b]), "#\n\n  #");
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn it_fills_hexes_in_an_image() {
+        use Orientation;
+        let layout = Layout::new(Orientation::Pointy, (8.0, 8.0), (0.0, 0.0));
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let map = HexMap::from_fn(center.spiral(1), |c| if *c == center { 255 } else { 100 });
+        let image = to_image(&layout, &map, |_, v| [*v, 0, 0, 255]);
+        // The hexes' corners span x from -69.3 to -27.7 and y from -32 to 8,
+        // rounded out to whole pixels, plus the margins.
+        assert_eq!((image.width(), image.height()), (43 + 4, 40 + 4));
+        let (left, top) = (-70.0 - 2.0, -32.0 - 2.0);
+        for c in center.spiral(1) {
+            let (x, y) = layout.hex_to_pixel(c);
+            let pixel = image.get_pixel((x - left) as u32, (y - top) as u32);
+            assert_eq!(pixel.0, [*map.get(c).unwrap(), 0, 0, 255]);
+        }
+        let (w, h) = (image.width() - 1, image.height() - 1);
+        for &(x, y) in [(0, 0), (w, 0), (0, h), (w, h)].iter() {
+            assert_eq!(image.get_pixel(x, y).0, [0; 4]);
+        }
+        let drawn = image.pixels().filter(|p| p.0[3] == 255).count() as f64;
+        let area = 7.0 * 1.5 * 3f64.sqrt() * 64.0;
+        assert!((drawn - area).abs() < area * 0.02);
+    }
+}
@@ -0,0 +1,155 @@
+//! Regions of hexes picked out on screen, e.g. by box selection, and
+//! shapes that can be tested and measured without listing their hexes.
+use {Coordinate, Offset};
+#[cfg(feature = "std")]
+use {Layout, Orientation};
+
+#[cfg(feature = "std")]
+mod shape;
+
+#[cfg(feature = "std")]
+pub use self::shape::{
+    CompositeShape, HexagonShape, ParallelogramShape, RectangleShape, Shape, TriangleShape,
+};
+
+
+/// Iterate over the hexes in the box of offset columns and rows spanned by
+/// `a` and `b`, including both.
+///
+/// The corners can be given in any order. Hexes come row by row, by
+/// increasing row and then increasing column. Since offset conventions
+/// stagger every other row or column, the box's edges along the staggered
+/// axis zigzag, exactly as the rows or columns are drawn.
+pub fn rect_between(a: Coordinate, b: Coordinate, offset: Offset) -> impl Iterator<Item = Coordinate> {
+    let (a_column, a_row) = a.to_offset(offset);
+    let (b_column, b_row) = b.to_offset(offset);
+    let min = (a_column.min(b_column), a_row.min(b_row));
+    let max = (a_column.max(b_column), a_row.max(b_row));
+    offset_box(min, max, offset)
+}
+
+/// Iterate over the hexes whose centers, as drawn by `layout`, fall in the
+/// pixel rectangle with corners `a` and `b`, including its edges, e.g. to
+/// find the hexes under a drag selection.
+///
+/// The corners can be given in any order. Hexes come in screen order: by
+/// row and then by column in the layout's own offset convention, `OddR` for
+/// a pointy-top layout and `OddQ` for a flat-top one.
+#[cfg(feature = "std")]
+pub fn rect_between_pixels(layout: &Layout, a: (f64, f64), b: (f64, f64)) -> impl Iterator<Item = Coordinate> + '_ {
+    let (left, right) = (a.0.min(b.0), a.0.max(b.0));
+    let (top, bottom) = (a.1.min(b.1), a.1.max(b.1));
+    let offset = match layout.orientation() {
+        Orientation::Pointy => Offset::OddR,
+        Orientation::Flat => Offset::OddQ,
+    };
+    // Every center in the rectangle is at most one column or row beyond
+    // the hexes containing its corners.
+    let corners = [(left, top), (right, top), (left, bottom), (right, bottom)];
+    let (mut min, mut max) = ((i64::MAX, i64::MAX), (i64::MIN, i64::MIN));
+    for corner in corners.iter() {
+        let (column, row) = layout.nearest_hex(*corner).to_offset(offset);
+        min = (min.0.min(column - 1), min.1.min(row - 1));
+        max = (max.0.max(column + 1), max.1.max(row + 1));
+    }
+    offset_box(min, max, offset).filter(move |c| {
+        let (x, y) = layout.hex_to_pixel(*c);
+        left <= x && x <= right && top <= y && y <= bottom
+    })
+}
+
+/// Iterate over the hexes from column and row `min` to `max` inclusive,
+/// row by row.
+fn offset_box(min: (i64, i64), max: (i64, i64), offset: Offset) -> impl Iterator<Item = Coordinate> {
+    (min.1..max.1 + 1).flat_map(move |row| {
+        (min.0..max.0 + 1).map(move |column| Coordinate::from_offset(column, row, offset))
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const ALL: [Offset; 4] = [Offset::OddR, Offset::EvenR, Offset::OddQ, Offset::EvenQ];
+
+    #[test]
+    fn it_takes_corners_in_any_order() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(2, 3, -5).unwrap();
+        for offset in ALL.iter() {
+            let (a_column, a_row) = a.to_offset(*offset);
+            let (b_column, b_row) = b.to_offset(*offset);
+            let other_a = Coordinate::from_offset(a_column, b_row, *offset);
+            let other_b = Coordinate::from_offset(b_column, a_row, *offset);
+            let forward: Vec<_> = rect_between(a, b, *offset).collect();
+            assert_eq!(rect_between(b, a, *offset).collect::<Vec<_>>(), forward);
+            assert_eq!(rect_between(other_a, other_b, *offset).collect::<Vec<_>>(), forward);
+            assert_eq!(rect_between(other_b, other_a, *offset).collect::<Vec<_>>(), forward);
+        }
+    }
+
+    #[test]
+    fn it_follows_the_stagger_along_its_edges() {
+        // Columns 0 to 1 of rows 0 to 2. Going down, the first hex's x
+        // component drops by one on entering each even row in odd-r, and
+        // each odd row in even-r.
+        let corner = Coordinate::from_offset(1, 2, Offset::OddR);
+        let hexes: Vec<_> = rect_between(Coordinate::new(), corner, Offset::OddR)
+            .map(|c| (c.x(), c.y()))
+            .collect();
+        assert_eq!(hexes, vec![(0, 0), (1, 0), (0, 1), (1, 1), (-1, 2), (0, 2)]);
+        let corner = Coordinate::from_offset(1, 2, Offset::EvenR);
+        let even: Vec<_> = rect_between(Coordinate::new(), corner, Offset::EvenR)
+            .map(|c| (c.x(), c.y()))
+            .collect();
+        assert_eq!(even, vec![(0, 0), (1, 0), (-1, 1), (0, 1), (-1, 2), (0, 2)]);
+    }
+
+    #[test]
+    fn it_matches_a_brute_force_filter() {
+        let a = Coordinate::at(-3, -1, 4).unwrap();
+        let b = Coordinate::at(4, 2, -6).unwrap();
+        for offset in ALL.iter() {
+            let (a_column, a_row) = a.to_offset(*offset);
+            let (b_column, b_row) = b.to_offset(*offset);
+            let expected: HashSet<_> = Coordinate::new().iter_spiral(20)
+                .filter(|c| {
+                    let (column, row) = c.to_offset(*offset);
+                    a_column.min(b_column) <= column && column <= a_column.max(b_column)
+                        && a_row.min(b_row) <= row && row <= a_row.max(b_row)
+                })
+                .collect();
+            let hexes: Vec<_> = rect_between(b, a, *offset).collect();
+            let columns = (a_column - b_column).abs() + 1;
+            let rows = (a_row - b_row).abs() + 1;
+            assert_eq!(hexes.len() as i64, columns * rows);
+            assert_eq!(hexes.into_iter().collect::<HashSet<_>>(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn it_selects_hexes_centered_in_a_pixel_rectangle() {
+        for orientation in [Orientation::Pointy, Orientation::Flat].iter() {
+            let layout = Layout::new(*orientation, (10.0, 12.0), (100.0, 50.0));
+            let rects: [((f64, f64), (f64, f64)); 2] =
+                [((-37.0, 21.5), (148.0, -80.0)), ((100.0, 50.0), (100.0, 50.0))];
+            for &(a, b) in rects.iter() {
+                let expected: HashSet<_> = Coordinate::new().spiral(20).into_iter()
+                    .filter(|c| {
+                        let (x, y) = layout.hex_to_pixel(*c);
+                        a.0.min(b.0) <= x && x <= a.0.max(b.0)
+                            && a.1.min(b.1) <= y && y <= a.1.max(b.1)
+                    })
+                    .collect();
+                let hexes: Vec<_> = rect_between_pixels(&layout, a, b).collect();
+                let flipped: Vec<_> = rect_between_pixels(&layout, (a.0, b.1), (b.0, a.1)).collect();
+                assert_eq!(hexes, flipped);
+                assert_eq!(hexes.len(), expected.len());
+                assert_eq!(hexes.into_iter().collect::<HashSet<_>>(), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Shapes that know their own hexes.
+use algorithms::{bounds, Bounds};
+use shapes::offset_box;
+use {Coordinate, HexRegion, Offset};
+
+
+/// A set of hexes that can be tested and measured without listing them.
+///
+/// Anything that takes a shape takes `&dyn Shape`, or a generic `S: Shape +
+/// ?Sized`, so custom shapes can be used wherever the ones here are.
+pub trait Shape {
+    /// Whether a hex is in the shape.
+    fn contains(&self, c: Coordinate) -> bool;
+
+    /// Get the number of hexes in the shape.
+    fn area(&self) -> usize;
+
+    /// Get the least and greatest value of each cube component over the
+    /// shape's hexes, or `None` if it has none.
+    fn bounds(&self) -> Option<Bounds>;
+
+    /// Iterate over the shape's hexes, each exactly once.
+    fn iter(&self) -> Box<dyn Iterator<Item = Coordinate> + '_>;
+}
+
+impl<S: Shape + ?Sized> Shape for &S {
+    fn contains(&self, c: Coordinate) -> bool {
+        (**self).contains(c)
+    }
+
+    fn area(&self) -> usize {
+        (**self).area()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        (**self).bounds()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Coordinate> + '_> {
+        (**self).iter()
+    }
+}
+
+impl<S: Shape + ?Sized> Shape for Box<S> {
+    fn contains(&self, c: Coordinate) -> bool {
+        (**self).contains(c)
+    }
+
+    fn area(&self) -> usize {
+        (**self).area()
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        (**self).bounds()
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = Coordinate> + '_> {
+        (**self).iter()
+    }
+}
+
+/// The bounds of a region whose ranges are all reached, or `None` if it is
+/// empty.
+fn region_bounds(region: &HexRegion) -> Option<Bounds> {
+    region.iter().next().map(|_| Bounds::from_tight(region))
+}
+
+
+/// The hexes within `radius` of `center`, as given by `Coordinate::spiral`.
+///
+/// A negative radius gives an empty shape.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct HexagonShape {
+    center: Coordinate,
+    radius: i64,
+}
+
+impl HexagonShape {
+    /// Create the hexagon of hexes within `radius` of `center`.
+    pub fn new(center: Coordinate, radius: i64) -> Self {
+        HexagonShape { center, radius }
+    }
+}
+
+impl Shape for HexagonShape {
+    fn contains(&self, c: Coordinate) -> bool {
+        self.radius >= 0 && self.center.distance_to(c) <= self.radius
+    }
+
+    fn area(&self) -> usize {
+        Coordinate::spiral_size(self.radius)
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        region_bounds(&HexRegion::hexagon(self.center, self.radius))
+    }
+
+    /// Iterate over the hexagon's hexes in `Ord` order.
+    fn iter(&self) -> Box<dyn Iterator<Item = Coordinate> + '_> {
+        Box::new(HexRegion::hexagon(self.center, self.radius).iter())
+    }
+}
+
+
+/// The box of offset columns and rows spanned by two corners, as given by
+/// `rect_between`.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub struct RectangleShape {
+    min: (i64, i64),
+    max: (i64, i64),
+    offset: Offset,
+}
+
+impl RectangleShape {
+    /// Create the box of `offset` columns and rows spanned by `a` and `b`,
+    /// including both, which can be given in any order.
+    pub fn new(a: Coordinate, b: Coordinate, offset: Offset) -> Self {
+        let (a_column, a_row) = a.to_offset(offset);
+        let (b_column, b_row) = b.to_offset(offset);
+        RectangleShape {
+            min: (a_column.min(b_column), a_row.min(b_row)),
+            max: (a_column.max(b_column), a_row.max(b_row)),
+            offset,
+        }
+    }
+}
+
+impl Shape for RectangleShape {
+    fn contains(&self, c: Coordinate) -> bool {
+        let (column, row) = c.to_offset(self.offset);
+        self.min.0 <= column && column <= self.max.0 && self.min.1 <= row && row <= self.max.1
+    }
+
+    fn area(&self) -> usize {
+        ((self.max.0 - self.min.0 + 1) * (self.max.1 - self.min.1 + 1)) as usize
+    }
+
+    /// Get the box's bounds, which are always reached along its edges, so
+    /// only those are checked.
+    fn bounds(&self) -> Option<Bounds> {
+        let (min, max, offset) = (self.min, self.max, self.offset);
+        let edges = offset_box(min, (max.0, min.1), offset)
+            .chain(offset_box((min.0, max.1), max, offset))
+            .chain(offset_box(min, (min.0, max.1), offset))
+            .chain(offset_box((max.0, min.1), max, offset));
+        bounds(edges)
+    }
+
+    /// Iterate over the box's hexes row by row, as for `rect_between`.
+    fn iter(&self) -> Box<dyn Iterator<Item = Coordinate> + '_> {
+        Box::new(offset_box(self.min, self.max, self.offset))
+    }
+}
+
+
+/// The hexes whose axial `q` and `r` components each lie between those of
+/// two corners.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct ParallelogramShape {
+    region: HexRegion,
+}
+
+impl ParallelogramShape {
+    /// Create the parallelogram with opposite corners `a` and `b`, which can
+    /// be given in any order.
+    pub fn new(a: Coordinate, b: Coordinate) -> Self {
+        let (x_min, x_max) = (a.x().min(b.x()), a.x().max(b.x()));
+        let (y_min, y_max) = (a.y().min(b.y()), a.y().max(b.y()));
+        let region = HexRegion {
+            x_min,
+            x_max,
+            y_min,
+            y_max,
+            z_min: -x_max - y_max,
+            z_max: -x_min - y_min,
+        };
+        ParallelogramShape { region }
+    }
+}
+
+impl Shape for ParallelogramShape {
+    fn contains(&self, c: Coordinate) -> bool {
+        self.region.contains(c)
+    }
+
+    fn area(&self) -> usize {
+        let region = &self.region;
+        ((region.x_max - region.x_min + 1) * (region.y_max - region.y_min + 1)) as usize
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        region_bounds(&self.region)
+    }
+
+    /// Iterate over the parallelogram's hexes in `Ord` order.
+    fn iter(&self) -> Box<dyn Iterator<Item = Coordinate> + '_> {
+        Box::new(self.region.iter())
+    }
+}
+
+
+/// The triangle of hexes with one corner at a given hex, and sides running
+/// east and southeast from it.
+///
+/// A triangle of size `n` has `n` hexes along each side; one of size zero
+/// or less is empty.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct TriangleShape {
+    region: HexRegion,
+    size: i64,
+}
+
+impl TriangleShape {
+    /// Create the triangle of `size` hexes a side with a corner at `corner`.
+    pub fn new(corner: Coordinate, size: i64) -> Self {
+        let size = size.max(0);
+        let region = HexRegion {
+            x_min: corner.x(),
+            x_max: corner.x() + size - 1,
+            y_min: corner.y(),
+            y_max: corner.y() + size - 1,
+            z_min: corner.z() - size + 1,
+            z_max: corner.z(),
+        };
+        TriangleShape { region, size }
+    }
+}
+
+impl Shape for TriangleShape {
+    fn contains(&self, c: Coordinate) -> bool {
+        self.region.contains(c)
+    }
+
+    fn area(&self) -> usize {
+        let n = self.size as usize;
+        n * (n + 1) / 2
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        region_bounds(&self.region)
+    }
+
+    /// Iterate over the triangle's hexes in `Ord` order.
+    fn iter(&self) -> Box<dyn Iterator<Item = Coordinate> + '_> {
+        Box::new(self.region.iter())
+    }
+}
+
+
+/// Two shapes combined hex by hex.
+///
+/// Membership is checked against both shapes directly. `area` iterates over
+/// the second shape, so it is cheapest when that is the smaller one, e.g.
+/// the hole in a `Difference`.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub enum CompositeShape<A, B> {
+    /// The hexes in either shape.
+    Union(A, B),
+    /// The hexes in the first shape but not the second.
+    Difference(A, B),
+}
+
+impl<A: Shape, B: Shape> Shape for CompositeShape<A, B> {
+    fn contains(&self, c: Coordinate) -> bool {
+        match *self {
+            CompositeShape::Union(ref a, ref b) => a.contains(c) || b.contains(c),
+            CompositeShape::Difference(ref a, ref b) => a.contains(c) && !b.contains(c),
+        }
+    }
+
+    fn area(&self) -> usize {
+        match *self {
+            CompositeShape::Union(ref a, ref b) => {
+                a.area() + b.iter().filter(|c| !a.contains(*c)).count()
+            }
+            CompositeShape::Difference(ref a, ref b) => {
+                a.area() - b.iter().filter(|c| a.contains(*c)).count()
+            }
+        }
+    }
+
+    /// Get the bounds of the combined hexes. For a `Difference`, which can
+    /// shrink the first shape's bounds, every hex is checked.
+    fn bounds(&self) -> Option<Bounds> {
+        match *self {
+            CompositeShape::Union(ref a, ref b) => match (a.bounds(), b.bounds()) {
+                (Some(a), Some(b)) => Some(a.merge(b)),
+                (a, b) => a.or(b),
+            },
+            CompositeShape::Difference(..) => bounds(self.iter()),
+        }
+    }
+
+    /// Iterate over the first shape's hexes in its order, followed, for a
+    /// `Union`, by the second's that aren't in the first.
+    fn iter(&self) -> Box<dyn Iterator<Item = Coordinate> + '_> {
+        match *self {
+            CompositeShape::Union(ref a, ref b) => {
+                Box::new(a.iter().chain(b.iter().filter(move |c| !a.contains(*c))))
+            }
+            CompositeShape::Difference(ref a, ref b) => {
+                Box::new(a.iter().filter(move |c| !b.contains(*c)))
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn shapes() -> Vec<Box<dyn Shape>> {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let far = Coordinate::at(2, 3, -5).unwrap();
+        vec![
+            Box::new(HexagonShape::new(center, 3)),
+            Box::new(HexagonShape::new(center, 0)),
+            Box::new(HexagonShape::new(center, -1)),
+            Box::new(RectangleShape::new(far, center, Offset::OddR)),
+            Box::new(RectangleShape::new(center, far, Offset::EvenQ)),
+            Box::new(ParallelogramShape::new(far, center)),
+            Box::new(TriangleShape::new(center, 5)),
+            Box::new(TriangleShape::new(center, 0)),
+            Box::new(CompositeShape::Union(
+                HexagonShape::new(center, 2),
+                TriangleShape::new(center, 6),
+            )),
+            Box::new(CompositeShape::Difference(
+                ParallelogramShape::new(center, far),
+                HexagonShape::new(far, 2),
+            )),
+        ]
+    }
+
+    #[test]
+    fn it_agrees_with_its_own_hexes() {
+        for shape in shapes().iter() {
+            let hexes: Vec<_> = shape.iter().collect();
+            let set: HashSet<_> = hexes.iter().cloned().collect();
+            assert_eq!(set.len(), hexes.len());
+            assert_eq!(shape.area(), hexes.len());
+            for c in Coordinate::new().spiral(15) {
+                assert_eq!(shape.contains(c), set.contains(&c), "{:?}", c);
+            }
+            assert_eq!(shape.bounds(), bounds(hexes));
+        }
+    }
+
+    #[test]
+    fn it_measures_triangles_and_parallelograms() {
+        let corner = Coordinate::at(-3, -1, 4).unwrap();
+        let triangle = TriangleShape::new(corner, 4);
+        assert_eq!(triangle.area(), 10);
+        assert!(triangle.contains(corner));
+        assert!(triangle.contains(Coordinate::at(0, -1, 1).unwrap()));
+        assert!(triangle.contains(Coordinate::at(-3, 2, 1).unwrap()));
+        assert!(!triangle.contains(Coordinate::at(0, 2, -2).unwrap()));
+        let opposite = Coordinate::at(1, 1, -2).unwrap();
+        let parallelogram = ParallelogramShape::new(opposite, corner);
+        assert_eq!(parallelogram.area(), 15);
+        assert_eq!(parallelogram.bounds().unwrap().z(), (-2, 4));
+    }
+
+    #[test]
+    fn it_cuts_an_annulus_out_of_a_hexagon() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let annulus = CompositeShape::Difference(
+            HexagonShape::new(center, 3),
+            HexagonShape::new(center, 1),
+        );
+        assert_eq!(annulus.area(), 37 - 7);
+        for c in center.spiral(5) {
+            let distance = center.distance_to(c);
+            assert_eq!(annulus.contains(c), (2..=3).contains(&distance), "{:?}", c);
+        }
+        let mut hexes: Vec<_> = annulus.iter().collect();
+        let mut expected = center.annulus(2, 3);
+        hexes.sort();
+        expected.sort();
+        assert_eq!(hexes, expected);
+        assert_eq!(annulus.bounds(), HexagonShape::new(center, 3).bounds());
+    }
+
+    #[test]
+    fn it_takes_shapes_behind_references() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let hexagon = HexagonShape::new(center, 2);
+        let shape: &dyn Shape = &hexagon;
+        let triangle: Box<dyn Shape> = Box::new(TriangleShape::new(center, 4));
+        let union = CompositeShape::Union(shape, triangle);
+        assert_eq!(union.area(), 19 + 10 - 6);
+    }
+}
@@ -0,0 +1,214 @@
+//! Import of hexagonal maps from Tiled's JSON format, enabled by the `tiled`
+//! feature.
+//!
+//! See https://doc.mapeditor.org/en/stable/reference/json-map-format/ for the
+//! format.
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::Read;
+
+use serde_json::{self, Value};
+
+use {Coordinate, Offset};
+
+
+/// An error loading a Tiled map.
+#[derive(Debug)]
+pub enum TiledError {
+    /// The input isn't valid JSON.
+    Json(serde_json::Error),
+    /// The map isn't a hexagonal map.
+    NotHexagonal,
+    /// The map uses a feature this importer doesn't support.
+    Unsupported(&'static str),
+    /// The map is missing a field, or a field has the wrong type.
+    Invalid(&'static str),
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TiledError::Json(ref err) => write!(f, "Invalid JSON: {}", err),
+            TiledError::NotHexagonal => write!(f, "Map is not hexagonal"),
+            TiledError::Unsupported(what) => write!(f, "Unsupported map feature: {}", what),
+            TiledError::Invalid(what) => write!(f, "Invalid map: {}", what),
+        }
+    }
+}
+
+impl Error for TiledError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            TiledError::Json(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for TiledError {
+    fn from(err: serde_json::Error) -> Self {
+        TiledError::Json(err)
+    }
+}
+
+/// A hexagonal map loaded from Tiled.
+#[derive(Debug,Clone)]
+pub struct TiledMap {
+    /// The offset convention implied by the map's stagger axis and index.
+    pub offset: Offset,
+    /// The length of the sides of a hex parallel to the stagger axis, in
+    /// pixels.
+    pub hex_side_length: Option<u32>,
+    /// The map's tile layers, in order. Other kinds of layer are skipped.
+    pub layers: Vec<TileLayer>,
+}
+
+/// A tile layer of a Tiled map.
+#[derive(Debug,Clone)]
+pub struct TileLayer {
+    /// The layer's name.
+    pub name: String,
+    /// The global tile ID at each hex with a tile. Empty hexes (ID 0) are
+    /// omitted, and the flip flags in the high bits are left in place.
+    pub tiles: HashMap<Coordinate, u32>,
+}
+
+/// Load a hexagonal map from Tiled's JSON format.
+///
+/// Tiles are placed by converting their column and row to a Coordinate,
+/// using the offset convention that matches the map's `staggeraxis` and
+/// `staggerindex`. Infinite maps, compressed or encoded layer data, and group
+/// layers are not supported.
+pub fn load<R: Read>(reader: R) -> Result<TiledMap, TiledError> {
+    let map: Value = serde_json::from_reader(reader)?;
+    if map["orientation"] != "hexagonal" {
+        return Err(TiledError::NotHexagonal);
+    }
+    if map["infinite"] == true {
+        return Err(TiledError::Unsupported("infinite maps"));
+    }
+    let offset = match (map["staggeraxis"].as_str(), map["staggerindex"].as_str()) {
+        (Some("y"), Some("odd")) => Offset::OddR,
+        (Some("y"), Some("even")) => Offset::EvenR,
+        (Some("x"), Some("odd")) => Offset::OddQ,
+        (Some("x"), Some("even")) => Offset::EvenQ,
+        _ => return Err(TiledError::Invalid("staggeraxis or staggerindex")),
+    };
+    let hex_side_length = map["hexsidelength"].as_u64().map(|n| n as u32);
+    let mut layers = vec![];
+    for layer in map["layers"].as_array().ok_or(TiledError::Invalid("layers"))? {
+        match layer["type"].as_str() {
+            Some("tilelayer") => layers.push(load_layer(layer, offset)?),
+            Some("group") => return Err(TiledError::Unsupported("group layers")),
+            _ => {}
+        }
+    }
+    Ok(TiledMap { offset, hex_side_length, layers })
+}
+
+fn load_layer(layer: &Value, offset: Offset) -> Result<TileLayer, TiledError> {
+    if !layer["chunks"].is_null() {
+        return Err(TiledError::Unsupported("chunked layer data"));
+    }
+    if !layer["encoding"].is_null() && layer["encoding"] != "csv" {
+        return Err(TiledError::Unsupported("encoded layer data"));
+    }
+    let name = layer["name"].as_str().unwrap_or("").to_string();
+    let width = layer["width"].as_i64().ok_or(TiledError::Invalid("layer width"))?;
+    let data = layer["data"].as_array().ok_or(TiledError::Invalid("layer data"))?;
+    if width <= 0 {
+        return Err(TiledError::Invalid("layer width"));
+    }
+    let mut tiles = HashMap::new();
+    for (i, gid) in data.iter().enumerate() {
+        let gid = gid.as_u64().ok_or(TiledError::Invalid("tile ID"))? as u32;
+        if gid != 0 {
+            let (column, row) = (i as i64 % width, i as i64 / width);
+            tiles.insert(Coordinate::from_offset(column, row, offset), gid);
+        }
+    }
+    Ok(TileLayer { name, tiles })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_fixture(source: &str) -> TiledMap {
+        load(source.as_bytes()).unwrap()
+    }
+
+    fn assert_tile(map: &TiledMap, gid: u32, x: i64, y: i64, z: i64) {
+        let coord = Coordinate::at(x, y, z).unwrap();
+        assert_eq!(map.layers[0].tiles.get(&coord), Some(&gid));
+    }
+
+    #[test]
+    fn it_loads_odd_staggered_rows() {
+        let map = load_fixture(include_str!("../fixtures/tiled/stagger-y-odd.json"));
+        assert_eq!(map.offset, Offset::OddR);
+        assert_tile(&map, 1, 0, 0, 0);
+        assert_tile(&map, 5, 1, 1, -2);
+        assert_tile(&map, 6, 2, 1, -3);
+    }
+
+    #[test]
+    fn it_loads_even_staggered_rows() {
+        let map = load_fixture(include_str!("../fixtures/tiled/stagger-y-even.json"));
+        assert_eq!(map.offset, Offset::EvenR);
+        assert_tile(&map, 5, 0, 1, -1);
+        assert_tile(&map, 6, 1, 1, -2);
+    }
+
+    #[test]
+    fn it_loads_odd_staggered_columns() {
+        let map = load_fixture(include_str!("../fixtures/tiled/stagger-x-odd.json"));
+        assert_eq!(map.offset, Offset::OddQ);
+        assert_tile(&map, 5, 1, 1, -2);
+        assert_tile(&map, 6, 2, 0, -2);
+    }
+
+    #[test]
+    fn it_loads_even_staggered_columns() {
+        let map = load_fixture(include_str!("../fixtures/tiled/stagger-x-even.json"));
+        assert_eq!(map.offset, Offset::EvenQ);
+        assert_tile(&map, 5, 1, 0, -1);
+        assert_tile(&map, 6, 2, 0, -2);
+    }
+
+    #[test]
+    fn it_loads_every_tile_layer() {
+        let map = load_fixture(include_str!("../fixtures/tiled/stagger-y-odd.json"));
+        assert_eq!(map.hex_side_length, Some(12));
+        assert_eq!(map.layers.len(), 2);
+        assert_eq!(map.layers[0].name, "ground");
+        assert_eq!(map.layers[0].tiles.len(), 6);
+        assert_eq!(map.layers[1].name, "decoration");
+        let tiles: Vec<_> = map.layers[1].tiles.iter().collect();
+        assert_eq!(tiles, vec![(&Coordinate::at(2, 0, -2).unwrap(), &9)]);
+    }
+
+    #[test]
+    fn it_rejects_infinite_maps() {
+        let source = r#"{
+            "orientation": "hexagonal", "staggeraxis": "y", "staggerindex": "odd",
+            "infinite": true,
+            "layers": [{"type": "tilelayer", "chunks": [], "name": "ground"}]
+        }"#;
+        match load(source.as_bytes()) {
+            Err(TiledError::Unsupported(_)) => {}
+            other => panic!("Expected an unsupported error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_rejects_other_orientations() {
+        let source = r#"{"orientation": "orthogonal", "layers": []}"#;
+        match load(source.as_bytes()) {
+            Err(TiledError::NotHexagonal) => {}
+            other => panic!("Expected a not hexagonal error, got {:?}", other),
+        }
+    }
+}
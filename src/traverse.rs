@@ -0,0 +1,111 @@
+//! Lazy traversals of the grid.
+use std::collections::{HashSet, VecDeque};
+
+use Coordinate;
+
+
+/// Visit hexes outward from `start` in breadth-first order, yielding each
+/// with its depth: the number of steps taken from `start` to reach it.
+///
+/// `expand` is called once for each yielded hex, just before the next one
+/// is found, and decides whether its neighbors are visited. Returning false
+/// prunes everything reached only through that hex, while the hex itself is
+/// still yielded, so walls can be reported without looking past them. The
+/// grid is unbounded, so the traversal ends only once `expand` has stopped
+/// every branch.
+///
+/// Each hex is yielded at most once, at its least depth. Neighbors are
+/// queued in the order `neighbors` gives them, so the traversal is deterministic.
+pub fn breadth_first<F>(start: Coordinate, expand: F) -> impl Iterator<Item = (Coordinate, u32)>
+    where F: FnMut(Coordinate) -> bool
+{
+    let mut visited = HashSet::new();
+    visited.insert(start);
+    let mut frontier = VecDeque::new();
+    frontier.push_back((start, 0));
+    BreadthFirst { frontier, visited, last: None, expand }
+}
+
+/// The traversal returned by `breadth_first`.
+struct BreadthFirst<F> {
+    frontier: VecDeque<(Coordinate, u32)>,
+    visited: HashSet<Coordinate>,
+    /// The hex yielded last, whose neighbors are queued on the next call.
+    last: Option<(Coordinate, u32)>,
+    expand: F,
+}
+
+impl<F: FnMut(Coordinate) -> bool> Iterator for BreadthFirst<F> {
+    type Item = (Coordinate, u32);
+
+    fn next(&mut self) -> Option<(Coordinate, u32)> {
+        if let Some((hex, depth)) = self.last.take() {
+            if (self.expand)(hex) {
+                for neighbor in hex.neighbors().iter() {
+                    if self.visited.insert(*neighbor) {
+                        self.frontier.push_back((*neighbor, depth + 1));
+                    }
+                }
+            }
+        }
+        self.last = self.frontier.pop_front();
+        self.last
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_depths_on_open_ground() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let visited: Vec<_> = breadth_first(start, |c| start.distance_to(c) < 4).collect();
+        assert_eq!(visited.len(), Coordinate::spiral_size(4));
+        assert_eq!(visited[0], (start, 0));
+        for &(hex, depth) in visited.iter() {
+            assert_eq!(i64::from(depth), start.distance_to(hex));
+        }
+        assert!(visited.windows(2).all(|w| w[0].1 <= w[1].1));
+    }
+
+    #[test]
+    fn it_reports_walls_but_not_beyond_them() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let wall: HashSet<_> = start.ring(2).into_iter().collect();
+        let visited: Vec<_> = breadth_first(start, |c| !wall.contains(&c)).collect();
+        assert_eq!(visited.len(), Coordinate::spiral_size(2));
+        assert!(wall.iter().all(|c| visited.contains(&(*c, 2))));
+        let spilled = breadth_first(start, |c| !wall.contains(&c) && start.distance_to(c) < 6)
+            .any(|(c, _)| start.distance_to(c) > 2);
+        assert!(!spilled);
+    }
+
+    #[test]
+    fn it_yields_each_hex_once() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let pillar = start + Coordinate::at(2, -1, -1).unwrap();
+        let visited: Vec<_> = breadth_first(start, |c| c != pillar && start.distance_to(c) < 5)
+            .map(|(c, _)| c)
+            .collect();
+        let distinct: HashSet<_> = visited.iter().cloned().collect();
+        assert_eq!(distinct.len(), visited.len());
+        assert!(distinct.contains(&pillar));
+    }
+
+    #[test]
+    fn it_expands_only_as_far_as_it_is_driven() {
+        let start = Coordinate::at(-3, -1, 4).unwrap();
+        let mut expanded = 0;
+        {
+            let mut unbounded = breadth_first(start, |_| {
+                expanded += 1;
+                true
+            });
+            assert_eq!(unbounded.nth(6), Some((start.neighbors()[5], 1)));
+        }
+        // The last hex yielded hasn't been expanded yet.
+        assert_eq!(expanded, 6);
+    }
+}
@@ -0,0 +1,105 @@
+//! The corners where three hexes meet.
+use {Coordinate, Direction, HexError};
+
+
+/// The corner shared by three mutually adjacent hexes.
+///
+/// Corners of a hex are numbered 0 to 5, counterclockwise, with corner `i`
+/// lying between the neighbors in `Direction::ALL[i]` and
+/// `Direction::ALL[(i + 1) % 6]`. In a pointy-top layout corner 0 is the
+/// upper-right corner, between the `E` and `NE` neighbors.
+///
+/// Every corner is either corner 0 or corner 1 of exactly one hex, so a
+/// Vertex is stored as that hex and that corner. The same point therefore
+/// always produces the same Vertex, whichever of its three hexes it is
+/// reached from.
+#[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+pub struct Vertex {
+    hex: Coordinate,
+    corner: u8,
+}
+
+impl Vertex {
+    /// Create the Vertex at the given corner of a hex, if the corner is valid.
+    pub fn new(hex: Coordinate, corner: usize) -> Result<Vertex, HexError> {
+        let (dir, corner) = match corner {
+            0 => (None, 0),
+            1 => (None, 1),
+            2 => (Some(Direction::W), 0),
+            3 => (Some(Direction::SW), 1),
+            4 => (Some(Direction::SW), 0),
+            5 => (Some(Direction::SE), 1),
+            _ => return Err(HexError::InvalidCorner),
+        };
+        let hex = dir.map_or(hex, |dir| hex + dir.offset());
+        Ok(Vertex { hex, corner })
+    }
+
+    /// Get the three hexes that meet at this Vertex, in counterclockwise
+    /// order around it.
+    pub fn hexes(&self) -> [Coordinate; 3] {
+        let (a, b) = match self.corner {
+            0 => (Direction::E, Direction::NE),
+            _ => (Direction::NE, Direction::NW),
+        };
+        [self.hex, self.hex + a.offset(), self.hex + b.offset()]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn it_matches_corners_shared_by_neighbors() {
+        let hex = Coordinate::at(-3, -1, 4).unwrap();
+        for i in 0..6 {
+            let vertex = Vertex::new(hex, i).unwrap();
+            // The same point is corner i + 2 of the neighbor before it and
+            // corner i + 4 of the neighbor after it.
+            let before = hex + Direction::ALL[i].offset();
+            let after = hex + Direction::ALL[(i + 1) % 6].offset();
+            assert_eq!(Vertex::new(before, (i + 2) % 6), Ok(vertex));
+            assert_eq!(Vertex::new(after, (i + 4) % 6), Ok(vertex));
+        }
+    }
+
+    #[test]
+    fn it_distinguishes_the_corners_of_a_hex() {
+        let hex = Coordinate::at(-3, -1, 4).unwrap();
+        let corners: HashSet<_> = (0..6).map(|i| Vertex::new(hex, i).unwrap()).collect();
+        assert_eq!(corners.len(), 6);
+        assert_eq!(Vertex::new(hex, 6), Err(HexError::InvalidCorner));
+    }
+
+    #[test]
+    fn it_returns_the_hexes_that_meet_there() {
+        let hex = Coordinate::at(-3, -1, 4).unwrap();
+        for i in 0..6 {
+            let hexes = Vertex::new(hex, i).unwrap().hexes();
+            assert!(hexes.contains(&hex));
+            assert!(hexes.contains(&(hex + Direction::ALL[i].offset())));
+            assert!(hexes.contains(&(hex + Direction::ALL[(i + 1) % 6].offset())));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn it_numbers_corners_counterclockwise_from_upper_right() {
+        use {Layout, Orientation};
+        let layout = Layout::new(Orientation::Pointy, (10.0, 10.0), (0.0, 0.0));
+        let hex = Coordinate::at(-3, -1, 4).unwrap();
+        let corners = layout.hex_corners(hex);
+        for i in 0..6 {
+            // Pixel y increases downward, so hex_corners runs clockwise.
+            let expected = corners[5 - i];
+            let hexes = Vertex::new(hex, i).unwrap().hexes();
+            let centers: Vec<_> = hexes.iter().map(|c| layout.hex_to_pixel(*c)).collect();
+            let x = centers.iter().map(|p| p.0).sum::<f64>() / 3.0;
+            let y = centers.iter().map(|p| p.1).sum::<f64>() / 3.0;
+            assert!((x - expected.0).abs() < 1e-9 && (y - expected.1).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Which hexes can be seen from where.
+use std::collections::HashSet;
+
+use Coordinate;
+
+
+/// Get every hex within `radius` of `origin` that can be seen from it, by
+/// shadowcasting ring by ring.
+///
+/// Each hex on a ring around `origin` covers an equal share of the ring's
+/// angle, measured by its position along the ring. A visible opaque hex casts
+/// a shadow over the angle it covers, and a hex further out is hidden if the
+/// center of its share lies inside the shadows. Hexes exactly on the edge of
+/// a shadow are visible, unless another shadow starts there too, so a lone
+/// opaque hex hides only the hexes directly behind it, while a line of them
+/// hides everything behind it. Shadows widen as they get further away.
+///
+/// Opaque hexes that are visible are included, since their faces can be
+/// seen; `origin` is always included, even if it is opaque.
+pub fn field_of_view(origin: Coordinate, radius: i64, opaque: &HashSet<Coordinate>) -> HashSet<Coordinate> {
+    let mut visible = HashSet::new();
+    if radius < 0 {
+        return visible;
+    }
+    visible.insert(origin);
+    // Shadows, as the ring and position along it of the hex casting each.
+    let mut shadows: Vec<(i64, i64)> = Vec::new();
+    for k in 1..radius + 1 {
+        let mut new_shadows = Vec::new();
+        for (index, hex) in origin.iter_ring(k).enumerate() {
+            let position = index as i64;
+            if in_shadow(k, position, &shadows) {
+                continue;
+            }
+            visible.insert(hex);
+            if opaque.contains(&hex) {
+                new_shadows.push((k, position));
+            }
+        }
+        shadows.extend(new_shadows);
+    }
+    visible
+}
+
+/// Whether the center of the hex at `position` on ring `ring` is inside
+/// `shadows`: strictly inside one of them, or on the edge between two.
+///
+/// The hex at position `p` on ring `k` covers the fraction of a turn from
+/// `(p - 0.5) / 6k` to `(p + 0.5) / 6k`, and its center is at `p / 6k`.
+/// These are compared exactly, with the fractions cross-multiplied.
+fn in_shadow(ring: i64, position: i64, shadows: &[(i64, i64)]) -> bool {
+    let turn = 6 * ring;
+    let (mut at_start, mut at_end) = (false, false);
+    for &(k, p) in shadows {
+        for q in [position - turn, position, position + turn].iter() {
+            let center = 2 * k * q;
+            let (start, end) = ((2 * p - 1) * ring, (2 * p + 1) * ring);
+            if start < center && center < end {
+                return true;
+            }
+            at_start |= center == start;
+            at_end |= center == end;
+        }
+    }
+    at_start && at_end
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_sees_everything_in_a_clear_field() {
+        let origin = Coordinate::at(-3, -1, 4).unwrap();
+        let visible = field_of_view(origin, 4, &HashSet::new());
+        let expected: HashSet<_> = origin.spiral(4).into_iter().collect();
+        assert_eq!(visible, expected);
+    }
+
+    #[test]
+    fn it_always_sees_the_origin() {
+        let origin = Coordinate::at(-3, -1, 4).unwrap();
+        let walls: HashSet<_> = origin.spiral(1).into_iter().collect();
+        let visible = field_of_view(origin, 5, &walls);
+        assert_eq!(visible, walls);
+        assert_eq!(field_of_view(origin, 0, &walls), vec![origin].into_iter().collect());
+    }
+
+    #[test]
+    fn it_hides_hexes_behind_a_wall() {
+        let origin = Coordinate::at(-3, -1, 4).unwrap();
+        let east = |n: i64| origin + Coordinate::at(n, 0, -n).unwrap();
+        let walls = vec![east(1)].into_iter().collect();
+        let visible = field_of_view(origin, 5, &walls);
+        assert!(visible.contains(&east(1)));
+        for n in 2..6 {
+            assert!(!visible.contains(&east(n)));
+        }
+        // A single hex doesn't hide the hexes beside the one behind it.
+        assert!(visible.contains(&(east(2) + Coordinate::at(0, -1, 1).unwrap())));
+        assert!(visible.contains(&(east(2) + Coordinate::at(-1, 1, 0).unwrap())));
+        // The shadow widens with distance.
+        assert!(!visible.contains(&(east(4) + Coordinate::at(0, -1, 1).unwrap())));
+    }
+
+    #[test]
+    fn it_casts_shadows_across_the_start_of_the_ring() {
+        let origin = Coordinate::new();
+        let walls = vec![Coordinate::at(1, 0, -1).unwrap()].into_iter().collect();
+        let visible = field_of_view(origin, 4, &walls);
+        // Just clockwise of due east, at the end of each ring, and just
+        // counterclockwise of it, at the start.
+        assert!(!visible.contains(&Coordinate::at(4, -1, -3).unwrap()));
+        assert!(!visible.contains(&Coordinate::at(3, 1, -4).unwrap()));
+        assert!(visible.contains(&Coordinate::at(2, 2, -4).unwrap()));
+    }
+
+    #[test]
+    fn it_sees_nothing_with_a_negative_radius() {
+        assert!(field_of_view(Coordinate::new(), -1, &HashSet::new()).is_empty());
+    }
+}
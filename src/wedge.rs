@@ -0,0 +1,191 @@
+//! Wedges of hexes facing a direction.
+use {Coordinate, Direction};
+
+
+/// How many sextants a wedge spans.
+///
+/// A sextant is the 60-degree slice of the grid centered on one of the six
+/// directions; see `Coordinate::wedge`. Wider wedges always include the
+/// facing sextant, so `Two` can't be centered on it, and instead extends
+/// clockwise.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum WedgeWidth {
+    /// The sextant centered on the facing direction, 60 degrees wide.
+    One,
+    /// The facing sextant and the one clockwise of it, 120 degrees wide. A
+    /// wedge facing `E` covers the `E` and `SE` sextants; to cover `E` and
+    /// `NE` instead, face `NE`.
+    Two,
+    /// The facing sextant and the ones on either side of it, 180 degrees
+    /// wide.
+    Three,
+}
+
+impl WedgeWidth {
+    /// Whether a wedge of this width facing `dir` includes `sextant`.
+    fn includes(self, dir: Direction, sextant: Direction) -> bool {
+        match self {
+            WedgeWidth::One => sextant == dir,
+            WedgeWidth::Two => sextant == dir || sextant == dir.clockwise(),
+            WedgeWidth::Three => {
+                sextant == dir || sextant == dir.clockwise() || sextant == dir.counter_clockwise()
+            }
+        }
+    }
+}
+
+impl Coordinate {
+    /// Get the direction whose sextant around this Coordinate contains
+    /// `other`, as for `wedge`, or `None` if they are the same hex.
+    ///
+    /// Along each ring around this Coordinate, a sextant covers the hexes
+    /// nearest the ray through its direction. A hex exactly halfway between
+    /// two of those rays belongs to the sextant counterclockwise of it.
+    pub fn sextant_of(&self, other: Coordinate) -> Option<Direction> {
+        let (k, side, j) = other.spiral_key(*self);
+        if k == 0 {
+            return None;
+        }
+        // The position along the ring, counterclockwise from due east, shifted
+        // so that each sextant starts at a multiple of k.
+        let position = i64::from(side) * k + j + k / 2;
+        Some(Direction::ALL[(position / k % 6) as usize])
+    }
+
+    /// Get the direction that points most nearly from this Coordinate toward
+    /// `other`, or `None` if they are the same hex.
+    ///
+    /// This is the direction whose 60-degree sector, centered on its ray,
+    /// contains `other`, which is `sextant_of`. So when `other` lies exactly
+    /// between two directions, as `(2, -1, -1)` lies between `E` and `NE`,
+    /// the counterclockwise one is chosen.
+    pub fn direction_to(&self, other: Coordinate) -> Option<Direction> {
+        self.sextant_of(other)
+    }
+
+    /// Iterate over the hexes within `radius` of this Coordinate that lie in
+    /// the wedge of `width` sextants facing `dir`.
+    ///
+    /// The sextant of a direction is the 60-degree slice of the grid
+    /// centered on that direction's ray. The six sextants tile the grid
+    /// without overlap: a hex exactly on the line between two sextants
+    /// belongs to the counterclockwise one. For example, `(2, -1, -1)` lies
+    /// between the `E` and `NE` rays, and is in the `NE` sextant. This
+    /// Coordinate itself is in no sextant, so it is never included.
+    ///
+    /// Hexes come in the order `spiral` visits them.
+    pub fn wedge(&self, dir: Direction, radius: u32, width: WedgeWidth) -> impl Iterator<Item = Coordinate> {
+        let center = *self;
+        center.iter_spiral(i64::from(radius)).filter(move |&hex| {
+            center.sextant_of(hex).is_some_and(|sextant| width.includes(dir, sextant))
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use DIAGONALS;
+
+    #[test]
+    fn it_partitions_the_range_into_sextants() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for radius in 0..6 {
+            let mut seen = HashSet::new();
+            for dir in Direction::ALL.iter() {
+                for hex in center.wedge(*dir, radius, WedgeWidth::One) {
+                    assert!(seen.insert(hex), "{:?} is in two sextants", hex);
+                }
+            }
+            let expected: HashSet<_> = center.iter_spiral(i64::from(radius)).skip(1).collect();
+            assert_eq!(seen, expected);
+        }
+    }
+
+    #[test]
+    fn it_includes_direction_rays_in_their_own_sextant() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for dir in Direction::ALL.iter() {
+            let wedge: Vec<_> = center.wedge(*dir, 3, WedgeWidth::One).collect();
+            let mut hex = center;
+            for _ in 0..3 {
+                hex = hex + dir.offset();
+                assert!(wedge.contains(&hex));
+            }
+        }
+    }
+
+    #[test]
+    fn it_assigns_diagonal_rays_counterclockwise() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let diagonal = Coordinate::at(2, -1, -1).unwrap();
+        let ne: Vec<_> = center.wedge(Direction::NE, 4, WedgeWidth::One).collect();
+        let e: Vec<_> = center.wedge(Direction::E, 4, WedgeWidth::One).collect();
+        assert!(ne.contains(&(center + diagonal)));
+        assert!(ne.contains(&(center + diagonal + diagonal)));
+        assert!(!e.contains(&(center + diagonal)));
+        let opposite = Coordinate::at(-2, 1, 1).unwrap();
+        let sw: Vec<_> = center.wedge(Direction::SW, 2, WedgeWidth::One).collect();
+        assert!(sw.contains(&(center + opposite)));
+    }
+
+    #[test]
+    fn it_points_along_unit_directions() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for dir in Direction::ALL.iter() {
+            assert_eq!(center.direction_to(center + dir.offset()), Some(*dir));
+            assert_eq!(Coordinate::new().direction_to(dir.offset()), Some(*dir));
+        }
+        assert_eq!(center.direction_to(center), None);
+        assert_eq!(center.sextant_of(center), None);
+    }
+
+    #[test]
+    fn it_points_toward_distant_targets() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        // Mostly east, a little to either side.
+        let target = center + Coordinate::at(10, -3, -7).unwrap();
+        assert_eq!(center.direction_to(target), Some(Direction::E));
+        let target = center + Coordinate::at(10, 4, -14).unwrap();
+        assert_eq!(center.direction_to(target), Some(Direction::E));
+        // Just past the diagonal between E and NE, and on it.
+        let target = center + Coordinate::at(10, -6, -4).unwrap();
+        assert_eq!(center.direction_to(target), Some(Direction::NE));
+        let target = center + Coordinate::at(10, -5, -5).unwrap();
+        assert_eq!(center.direction_to(target), Some(Direction::NE));
+        let target = center + Coordinate::at(10, -4, -6).unwrap();
+        assert_eq!(center.direction_to(target), Some(Direction::E));
+        // Every diagonal goes counterclockwise.
+        for dir in Direction::ALL.iter() {
+            let diagonal = DIAGONALS[*dir as usize];
+            assert_eq!(center.direction_to(center + diagonal), Some(dir.counter_clockwise()));
+        }
+    }
+
+    #[test]
+    fn it_points_into_its_own_wedge() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        for dir in Direction::ALL.iter() {
+            for hex in center.wedge(*dir, 6, WedgeWidth::One) {
+                assert_eq!(center.direction_to(hex), Some(*dir));
+                assert_eq!(center.sextant_of(hex), Some(*dir));
+            }
+        }
+    }
+
+    #[test]
+    fn it_widens_to_adjacent_sextants() {
+        let center = Coordinate::at(-3, -1, 4).unwrap();
+        let count = |width| center.wedge(Direction::E, 4, width).count();
+        // The six sextants split every hex but the center evenly.
+        let per_sextant = (Coordinate::spiral_size(4) - 1) / 6;
+        let one = count(WedgeWidth::One);
+        assert_eq!(one, per_sextant);
+        assert_eq!(count(WedgeWidth::Two), 2 * one);
+        assert_eq!(count(WedgeWidth::Three), 3 * one);
+        let two: Vec<_> = center.wedge(Direction::E, 1, WedgeWidth::Two).collect();
+        assert_eq!(two, vec![center + Direction::E.offset(), center + Direction::SE.offset()]);
+    }
+}
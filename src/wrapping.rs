@@ -0,0 +1,139 @@
+//! Finite maps whose edges wrap around.
+use alloc::vec::Vec;
+
+use {Coordinate, HexError, Offset};
+
+
+/// Which axes of a `WrappingMap` wrap around.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub enum Wrap {
+    /// Columns wrap, making a cylinder that can be walked around east-west.
+    Columns,
+    /// Rows wrap, making a cylinder that can be walked around north-south.
+    Rows,
+    /// Both wrap, making a torus.
+    Both,
+}
+
+/// A rectangular map of `columns` by `rows` hexes whose edges wrap around.
+///
+/// The map covers offset coordinates `(0, 0)` to `(columns - 1, rows - 1)`
+/// in the given `Offset` convention. Wrapping is done in offset space: a hex
+/// that steps off one edge re-enters at the opposite edge in the same row (or
+/// column), so leaving column `-1` lands in column `columns - 1`.
+///
+/// For that to keep neighbors adjacent, the wrapped axis must preserve the
+/// stagger of the offset convention. Wrapping rows in an `R` convention, or
+/// columns in a `Q` convention, therefore requires an even count along that
+/// axis.
+#[derive(Debug,PartialEq,Eq,Clone,Copy)]
+pub struct WrappingMap {
+    offset: Offset,
+    columns: i64,
+    rows: i64,
+    wrap: Wrap,
+}
+
+impl WrappingMap {
+    /// Create a new WrappingMap, if the dimensions are valid for the wrap.
+    pub fn new(offset: Offset, columns: i64, rows: i64, wrap: Wrap) -> Result<Self, HexError> {
+        if columns <= 0 || rows <= 0 {
+            return Err(HexError::InvalidDimensions("Sizes must be positive"));
+        }
+        let wraps_columns = wrap != Wrap::Rows;
+        let wraps_rows = wrap != Wrap::Columns;
+        let staggered = if offset.shifts_rows() {
+            wraps_rows && rows % 2 != 0
+        } else {
+            wraps_columns && columns % 2 != 0
+        };
+        if staggered {
+            return Err(HexError::InvalidDimensions("Wrapped staggered axis must have an even length"));
+        }
+        Ok(WrappingMap { offset, columns, rows, wrap })
+    }
+
+    /// Whether a Coordinate lies within the map, without wrapping.
+    pub fn contains(&self, c: Coordinate) -> bool {
+        let (column, row) = c.to_offset(self.offset);
+        (0..self.columns).contains(&column) && (0..self.rows).contains(&row)
+    }
+
+    /// Map a Coordinate back into the map along the wrapping axes.
+    ///
+    /// Returns `None` if the Coordinate lies beyond an edge that doesn't wrap.
+    pub fn wrap(&self, c: Coordinate) -> Option<Coordinate> {
+        let (mut column, mut row) = c.to_offset(self.offset);
+        if self.wrap != Wrap::Rows {
+            column = column.rem_euclid(self.columns);
+        }
+        if self.wrap != Wrap::Columns {
+            row = row.rem_euclid(self.rows);
+        }
+        let wrapped = Coordinate::from_offset(column, row, self.offset);
+        if self.contains(wrapped) {
+            Some(wrapped)
+        } else {
+            None
+        }
+    }
+
+    /// Get the neighbors of a Coordinate, wrapped back into the map.
+    ///
+    /// Neighbors beyond an edge that doesn't wrap are omitted.
+    pub fn wrapped_neighbors(&self, c: Coordinate) -> Vec<Coordinate> {
+        c.neighbors().iter().filter_map(|n| self.wrap(*n)).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_wraps_the_east_edge_to_the_west_edge() {
+        let map = WrappingMap::new(Offset::OddR, 8, 5, Wrap::Columns).unwrap();
+        let east = Coordinate::from_offset(7, 2, Offset::OddR);
+        let neighbors = map.wrapped_neighbors(east);
+        assert_eq!(neighbors.len(), 6);
+        assert!(neighbors.contains(&Coordinate::from_offset(0, 2, Offset::OddR)));
+    }
+
+    #[test]
+    fn it_leaves_interior_neighbors_alone() {
+        let map = WrappingMap::new(Offset::OddR, 8, 5, Wrap::Columns).unwrap();
+        let interior = Coordinate::from_offset(3, 2, Offset::OddR);
+        assert_eq!(map.wrapped_neighbors(interior), interior.neighbors().to_vec());
+    }
+
+    #[test]
+    fn it_drops_neighbors_beyond_edges_that_dont_wrap() {
+        let map = WrappingMap::new(Offset::OddR, 8, 5, Wrap::Columns).unwrap();
+        let north = Coordinate::from_offset(3, 0, Offset::OddR);
+        assert_eq!(map.wrapped_neighbors(north).len(), 4);
+    }
+
+    #[test]
+    fn it_wraps_every_neighbor_on_a_torus() {
+        let map = WrappingMap::new(Offset::OddR, 6, 4, Wrap::Both).unwrap();
+        for column in 0..6 {
+            for row in 0..4 {
+                let c = Coordinate::from_offset(column, row, Offset::OddR);
+                let neighbors = map.wrapped_neighbors(c);
+                assert_eq!(neighbors.len(), 6);
+                for n in neighbors {
+                    assert!(map.contains(n));
+                    assert!(map.wrapped_neighbors(n).contains(&c));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_rejects_odd_wrapped_staggered_axes() {
+        assert!(WrappingMap::new(Offset::OddR, 6, 5, Wrap::Both).is_err());
+        assert!(WrappingMap::new(Offset::EvenQ, 5, 6, Wrap::Columns).is_err());
+        assert!(WrappingMap::new(Offset::EvenQ, 5, 6, Wrap::Rows).is_ok());
+    }
+}